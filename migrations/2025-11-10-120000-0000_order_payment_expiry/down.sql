@@ -0,0 +1,10 @@
+DROP TABLE IF EXISTS notifications;
+DROP TYPE IF EXISTS notification_type;
+
+DROP INDEX IF EXISTS idx_orders_pending_payment_deadline;
+
+ALTER TABLE orders
+    DROP COLUMN IF EXISTS payment_deadline,
+    DROP COLUMN IF EXISTS cancelled_at;
+
+-- PostgreSQL cannot drop a value from an enum type, so 'CANCELLED' stays on order_status
@@ -0,0 +1,25 @@
+-- Orders that are never paid are cancelled by the expiry job
+ALTER TYPE order_status ADD VALUE IF NOT EXISTS 'CANCELLED';
+
+ALTER TABLE orders
+    ADD COLUMN payment_deadline TIMESTAMP,
+    ADD COLUMN cancelled_at TIMESTAMP;
+
+CREATE INDEX idx_orders_pending_payment_deadline
+    ON orders (payment_deadline)
+    WHERE order_status = 'PENDING_PAYMENT';
+
+-- Notifications
+CREATE TYPE notification_type AS ENUM ('ORDER_EXPIRED');
+
+CREATE TABLE notifications (
+    notification_id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    order_id BIGINT REFERENCES orders(order_id) ON DELETE CASCADE,
+    notification_type notification_type NOT NULL,
+    message TEXT NOT NULL,
+    is_read BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_notifications_user_id ON notifications (user_id);
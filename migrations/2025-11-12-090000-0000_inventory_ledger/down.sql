@@ -0,0 +1,11 @@
+ALTER TABLE products ADD COLUMN stock_quantity INTEGER;
+
+UPDATE products p
+SET stock_quantity = (SELECT SUM(v.stock_quantity) FROM variants v WHERE v.product_id = p.id);
+
+ALTER TABLE variants
+    ALTER COLUMN stock_quantity DROP NOT NULL,
+    ALTER COLUMN stock_quantity DROP DEFAULT;
+
+DROP TABLE IF EXISTS inventory_movements;
+DROP TYPE IF EXISTS inventory_reason;
@@ -0,0 +1,38 @@
+-- Inventory ledger: every stock change is recorded as a movement and
+-- variants.stock_quantity is kept as the running balance of its movements
+CREATE TYPE inventory_reason AS ENUM ('RESTOCK', 'SALE', 'CANCELLATION', 'ADJUSTMENT', 'RETURN');
+
+CREATE TABLE inventory_movements (
+    movement_id BIGSERIAL PRIMARY KEY,
+    variant_id BIGINT NOT NULL REFERENCES variants(variant_id) ON DELETE CASCADE,
+    quantity_change INT NOT NULL,
+    reason inventory_reason NOT NULL,
+    order_id BIGINT REFERENCES orders(order_id) ON DELETE SET NULL,
+    note TEXT,
+    created_by VARCHAR(100),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_inventory_movements_variant_id ON inventory_movements (variant_id, created_at);
+
+-- Products that tracked stock without variants get a default variant to hold it
+INSERT INTO variants (product_id, stock_quantity)
+SELECT p.id, p.stock_quantity
+FROM products p
+WHERE p.stock_quantity IS NOT NULL
+  AND NOT EXISTS (SELECT 1 FROM variants v WHERE v.product_id = p.id);
+
+UPDATE variants SET stock_quantity = 0 WHERE stock_quantity IS NULL;
+
+ALTER TABLE variants
+    ALTER COLUMN stock_quantity SET DEFAULT 0,
+    ALTER COLUMN stock_quantity SET NOT NULL;
+
+-- Opening balances
+INSERT INTO inventory_movements (variant_id, quantity_change, reason, note)
+SELECT variant_id, stock_quantity, 'ADJUSTMENT', 'Opening balance'
+FROM variants
+WHERE stock_quantity <> 0;
+
+-- Product stock is derived from its variants
+ALTER TABLE products DROP COLUMN stock_quantity;
@@ -0,0 +1,5 @@
+ALTER TABLE payments
+    ADD COLUMN transferred_at TIMESTAMP,
+    ADD COLUMN submitted_by BIGINT REFERENCES users(user_id);
+
+CREATE INDEX idx_payments_order_id ON payments (order_id);
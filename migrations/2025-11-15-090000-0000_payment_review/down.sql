@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS idx_payments_status;
+
+ALTER TABLE payments
+    DROP COLUMN IF EXISTS reviewed_at,
+    DROP COLUMN IF EXISTS reviewed_by,
+    DROP COLUMN IF EXISTS rejection_reason;
+
+-- PostgreSQL cannot drop values from an enum type, so notification_type keeps them
@@ -0,0 +1,9 @@
+ALTER TABLE payments
+    ADD COLUMN rejection_reason TEXT,
+    ADD COLUMN reviewed_by VARCHAR(100),
+    ADD COLUMN reviewed_at TIMESTAMP;
+
+CREATE INDEX idx_payments_status ON payments (payment_status, created_at);
+
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'PAYMENT_VERIFIED';
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'PAYMENT_REJECTED';
@@ -0,0 +1,7 @@
+DROP TABLE IF EXISTS payment_events;
+
+DROP INDEX IF EXISTS idx_payments_provider_reference;
+
+ALTER TABLE payments
+    DROP COLUMN IF EXISTS provider_reference,
+    DROP COLUMN IF EXISTS provider;
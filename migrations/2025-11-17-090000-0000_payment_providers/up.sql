@@ -0,0 +1,17 @@
+-- Payments taken through an online gateway instead of a transfer slip
+ALTER TABLE payments
+    ADD COLUMN provider VARCHAR(50),
+    ADD COLUMN provider_reference VARCHAR(100);
+
+CREATE UNIQUE INDEX idx_payments_provider_reference
+    ON payments (provider, provider_reference)
+    WHERE provider_reference IS NOT NULL;
+
+-- Callback events already applied, so replays are ignored
+CREATE TABLE payment_events (
+    provider VARCHAR(50) NOT NULL,
+    event_id VARCHAR(100) NOT NULL,
+    payment_id BIGINT NOT NULL REFERENCES payments(payment_id) ON DELETE CASCADE,
+    received_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (provider, event_id)
+);
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS refund_items;
+DROP TABLE IF EXISTS refunds;
+
+-- PostgreSQL cannot drop values from an enum type, so order_status and notification_type keep them
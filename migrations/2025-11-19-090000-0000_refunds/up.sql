@@ -0,0 +1,30 @@
+ALTER TYPE order_status ADD VALUE IF NOT EXISTS 'PARTIALLY_REFUNDED';
+ALTER TYPE order_status ADD VALUE IF NOT EXISTS 'REFUNDED';
+
+ALTER TYPE notification_type ADD VALUE IF NOT EXISTS 'REFUND_ISSUED';
+
+-- Money sent back against a verified payment
+CREATE TABLE refunds (
+    refund_id BIGSERIAL PRIMARY KEY,
+    payment_id BIGINT NOT NULL REFERENCES payments(payment_id) ON DELETE CASCADE,
+    order_id BIGINT NOT NULL REFERENCES orders(order_id) ON DELETE CASCADE,
+    amount NUMERIC(10,2) NOT NULL CHECK (amount > 0),
+    reason TEXT NOT NULL,
+    restocked BOOLEAN NOT NULL DEFAULT FALSE,
+    evidence_url VARCHAR(255),
+    provider_reference VARCHAR(100),
+    created_by VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_refunds_payment_id ON refunds (payment_id);
+CREATE INDEX idx_refunds_order_id ON refunds (order_id);
+
+-- Order lines covered by a refund
+CREATE TABLE refund_items (
+    refund_id BIGINT NOT NULL REFERENCES refunds(refund_id) ON DELETE CASCADE,
+    order_item_id BIGINT NOT NULL REFERENCES order_items(order_item_id) ON DELETE CASCADE,
+    quantity INT NOT NULL CHECK (quantity > 0),
+    amount NUMERIC(10,2) NOT NULL,
+    PRIMARY KEY (refund_id, order_item_id)
+);
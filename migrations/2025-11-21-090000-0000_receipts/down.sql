@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS receipts;
+DROP TABLE IF EXISTS document_counters;
+DROP TYPE IF EXISTS document_type;
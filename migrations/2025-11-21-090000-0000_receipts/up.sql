@@ -0,0 +1,23 @@
+CREATE TYPE document_type AS ENUM ('RECEIPT', 'TAX_INVOICE');
+
+-- Last number handed out per document type and year. Numbers are taken inside the issuing
+-- transaction, so a rolled back issue does not leave a gap.
+CREATE TABLE document_counters (
+    document_type document_type NOT NULL,
+    year INT NOT NULL,
+    last_number INT NOT NULL,
+    PRIMARY KEY (document_type, year)
+);
+
+-- Issued receipts and tax invoices. Rows are never deleted, so orders keep theirs for good.
+CREATE TABLE receipts (
+    receipt_id BIGSERIAL PRIMARY KEY,
+    receipt_number VARCHAR(30) NOT NULL UNIQUE,
+    document_type document_type NOT NULL,
+    order_id BIGINT NOT NULL REFERENCES orders(order_id),
+    buyer_name VARCHAR(200) NOT NULL,
+    buyer_tax_id VARCHAR(13),
+    buyer_address TEXT,
+    issued_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (order_id, document_type)
+);
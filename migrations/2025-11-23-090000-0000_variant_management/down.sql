@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_variants_product_options;
+
+ALTER TABLE variants
+    DROP COLUMN price_override,
+    DROP COLUMN sku;
@@ -0,0 +1,8 @@
+-- Per-variant SKU and an optional price that replaces the product's base price
+ALTER TABLE variants
+    ADD COLUMN sku VARCHAR(64) UNIQUE,
+    ADD COLUMN price_override NUMERIC(10,2) CHECK (price_override > 0);
+
+-- A product has at most one variant per size/colour combination
+CREATE UNIQUE INDEX idx_variants_product_options
+    ON variants (product_id, COALESCE(size, ''), COALESCE(color, ''));
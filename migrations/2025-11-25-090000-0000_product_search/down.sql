@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_products_name_trgm;
+DROP INDEX IF EXISTS idx_products_search_document;
+DROP FUNCTION IF EXISTS product_search_document(TEXT, TEXT, TEXT);
+-- pg_trgm is left installed; other objects may depend on it
@@ -0,0 +1,25 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- The 'simple' configuration doesn't stem, so Thai and English words in one query are
+-- matched the same way. Search and the index below must both go through this function
+-- for the index to be used.
+CREATE OR REPLACE FUNCTION product_search_document(
+    name TEXT,
+    category TEXT,
+    description TEXT
+) RETURNS tsvector
+LANGUAGE SQL
+IMMUTABLE
+PARALLEL SAFE
+AS $$
+    SELECT setweight(to_tsvector('simple'::regconfig, COALESCE(name, '')), 'A')
+        || setweight(to_tsvector('simple'::regconfig, COALESCE(category, '')), 'B')
+        || setweight(to_tsvector('simple'::regconfig, COALESCE(description, '')), 'C')
+$$;
+
+CREATE INDEX idx_products_search_document ON products
+    USING GIN (product_search_document(name, category, description));
+
+-- Typo-tolerant and substring matching on names; Thai is written without spaces, so
+-- a Thai word inside a longer name is only found this way
+CREATE INDEX idx_products_name_trgm ON products USING GIN (name gin_trgm_ops);
@@ -0,0 +1,11 @@
+-- One row per normalised search text, counted as users search
+CREATE TABLE search_queries (
+    query VARCHAR(100) PRIMARY KEY,
+    search_count INT NOT NULL DEFAULT 0,
+    zero_result_count INT NOT NULL DEFAULT 0,
+    last_result_count BIGINT NOT NULL DEFAULT 0,
+    last_searched_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_search_queries_zero_results ON search_queries (zero_result_count DESC)
+    WHERE zero_result_count > 0;
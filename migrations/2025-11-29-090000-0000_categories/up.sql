@@ -0,0 +1,51 @@
+CREATE TABLE categories (
+    category_id BIGSERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    slug VARCHAR(120) NOT NULL UNIQUE,
+    parent_id BIGINT REFERENCES categories(category_id),
+    display_order INT NOT NULL DEFAULT 0,
+    image_url VARCHAR(255),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (parent_id <> category_id)
+);
+
+CREATE INDEX idx_categories_parent ON categories (parent_id);
+
+-- Free-text values that only differ by case, spacing or punctuation become one category,
+-- named after the first product that used it
+CREATE TEMP TABLE category_slugs ON COMMIT DROP AS
+SELECT
+    id AS product_id,
+    TRIM(category) AS name,
+    COALESCE(
+        NULLIF(TRIM(BOTH '-' FROM REGEXP_REPLACE(LOWER(TRIM(category)), '[^a-z0-9ก-๛]+', '-', 'g')), ''),
+        'category'
+    ) AS slug
+FROM products
+WHERE category IS NOT NULL AND TRIM(category) <> '';
+
+INSERT INTO categories (name, slug)
+SELECT DISTINCT ON (slug) name, slug
+FROM category_slugs
+ORDER BY slug, product_id;
+
+ALTER TABLE products
+    ADD COLUMN category_id BIGINT REFERENCES categories(category_id);
+
+CREATE INDEX idx_products_category ON products (category_id);
+
+UPDATE products p
+SET category_id = c.category_id
+FROM category_slugs s
+JOIN categories c ON c.slug = s.slug
+WHERE p.id = s.product_id;
+
+-- products.category now only mirrors the category's name for search; it is written by the
+-- application whenever category_id or the category's name changes
+UPDATE products p
+SET category = c.name
+FROM categories c
+WHERE c.category_id = p.category_id;
+
+UPDATE products SET category = NULL WHERE category_id IS NULL;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS product_slug_redirects;
+ALTER TABLE products DROP COLUMN IF EXISTS slug;
@@ -0,0 +1,12 @@
+-- Existing products are given slugs by the application at startup, since Thai names need
+-- transliterating
+ALTER TABLE products ADD COLUMN slug VARCHAR(160) UNIQUE;
+
+-- Slugs a product used before it was renamed, so old links keep resolving
+CREATE TABLE product_slug_redirects (
+    slug VARCHAR(160) PRIMARY KEY,
+    product_id BIGINT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_product_slug_redirects_product ON product_slug_redirects (product_id);
@@ -0,0 +1,5 @@
+-- Archived products stay in the table so order history keeps its variants, but are hidden
+-- from the storefront
+ALTER TABLE products ADD COLUMN archived_at TIMESTAMP;
+
+CREATE INDEX idx_products_archived ON products (archived_at) WHERE archived_at IS NOT NULL;
@@ -0,0 +1,8 @@
+DROP FUNCTION IF EXISTS product_on_sale(TIMESTAMP, product_visibility, TIMESTAMP, TIMESTAMP);
+DROP INDEX IF EXISTS idx_products_scheduled;
+ALTER TABLE products
+    DROP CONSTRAINT IF EXISTS products_schedule_order,
+    DROP COLUMN IF EXISTS unpublish_at,
+    DROP COLUMN IF EXISTS publish_at,
+    DROP COLUMN IF EXISTS visibility;
+DROP TYPE IF EXISTS product_visibility;
@@ -0,0 +1,27 @@
+CREATE TYPE product_visibility AS ENUM ('DRAFT', 'SCHEDULED', 'PUBLISHED', 'HIDDEN');
+
+-- Products that already exist were on sale, so they stay published
+ALTER TABLE products
+    ADD COLUMN visibility product_visibility NOT NULL DEFAULT 'PUBLISHED',
+    ADD COLUMN publish_at TIMESTAMP,
+    ADD COLUMN unpublish_at TIMESTAMP,
+    ADD CONSTRAINT products_schedule_order CHECK (unpublish_at IS NULL OR publish_at IS NULL OR unpublish_at > publish_at);
+
+ALTER TABLE products ALTER COLUMN visibility SET DEFAULT 'DRAFT';
+
+CREATE INDEX idx_products_scheduled ON products (publish_at) WHERE visibility = 'SCHEDULED';
+
+-- Whether the storefront shows a product and lets it be bought. Timestamps are UTC like the
+-- rest of the schema. Schedules are checked against the clock here, so a launch goes live on
+-- time even before the schedule job records it.
+CREATE OR REPLACE FUNCTION product_on_sale(
+    archived_at TIMESTAMP,
+    visibility product_visibility,
+    publish_at TIMESTAMP,
+    unpublish_at TIMESTAMP
+) RETURNS BOOLEAN AS $$
+    SELECT archived_at IS NULL
+        AND (visibility = 'PUBLISHED'
+            OR (visibility = 'SCHEDULED' AND publish_at <= NOW() AT TIME ZONE 'UTC'))
+        AND (unpublish_at IS NULL OR unpublish_at > NOW() AT TIME ZONE 'UTC')
+$$ LANGUAGE SQL STABLE;
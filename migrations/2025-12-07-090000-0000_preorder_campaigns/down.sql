@@ -0,0 +1,4 @@
+ALTER TABLE orders DROP COLUMN IF EXISTS deposit_amount;
+ALTER TABLE order_items DROP COLUMN IF EXISTS preorder;
+DROP TABLE IF EXISTS preorder_campaigns;
+DROP TYPE IF EXISTS preorder_payment_mode;
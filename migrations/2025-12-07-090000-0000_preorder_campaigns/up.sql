@@ -0,0 +1,24 @@
+CREATE TYPE preorder_payment_mode AS ENUM ('FULL', 'DEPOSIT');
+
+-- Pre-order terms for a product with the PREORDER status; one campaign per product
+CREATE TABLE preorder_campaigns (
+    product_id BIGINT PRIMARY KEY REFERENCES products(id) ON DELETE CASCADE,
+    opens_at TIMESTAMP NOT NULL,
+    closes_at TIMESTAMP NOT NULL,
+    -- Units across all variants; no limit when NULL
+    quota INT CHECK (quota > 0),
+    estimated_delivery DATE,
+    payment_mode preorder_payment_mode NOT NULL DEFAULT 'FULL',
+    deposit_percent INT CHECK (deposit_percent BETWEEN 1 AND 99),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (closes_at > opens_at),
+    CHECK ((payment_mode = 'DEPOSIT') = (deposit_percent IS NOT NULL))
+);
+
+-- Pre-ordered lines take no stock when sold and return none when cancelled
+ALTER TABLE order_items ADD COLUMN preorder BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- What must be paid to confirm an order that has deposit pre-order lines; the rest of
+-- total_amount is paid as a balance afterwards
+ALTER TABLE orders ADD COLUMN deposit_amount NUMERIC(10, 2);
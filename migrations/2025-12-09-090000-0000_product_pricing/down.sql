@@ -0,0 +1,11 @@
+DROP FUNCTION IF EXISTS product_effective_price(NUMERIC, NUMERIC, TIMESTAMP, TIMESTAMP);
+DROP TRIGGER IF EXISTS record_product_price ON products;
+DROP FUNCTION IF EXISTS record_product_price();
+DROP TABLE IF EXISTS product_price_history;
+ALTER TABLE products
+    DROP CONSTRAINT IF EXISTS products_sale_times,
+    DROP CONSTRAINT IF EXISTS products_sale_order,
+    DROP COLUMN IF EXISTS sale_ends_at,
+    DROP COLUMN IF EXISTS sale_starts_at,
+    DROP COLUMN IF EXISTS sale_price,
+    DROP COLUMN IF EXISTS compare_at_price;
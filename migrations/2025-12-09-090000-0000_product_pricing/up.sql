@@ -0,0 +1,68 @@
+-- A "compare at" price shown struck through next to the price, and an optional sale price
+-- that applies between its start and end times (open-ended when either is NULL)
+ALTER TABLE products
+    ADD COLUMN compare_at_price NUMERIC(10, 2) CHECK (compare_at_price > 0),
+    ADD COLUMN sale_price NUMERIC(10, 2) CHECK (sale_price > 0),
+    ADD COLUMN sale_starts_at TIMESTAMP,
+    ADD COLUMN sale_ends_at TIMESTAMP,
+    ADD CONSTRAINT products_sale_order CHECK (sale_ends_at IS NULL OR sale_starts_at IS NULL OR sale_ends_at > sale_starts_at),
+    ADD CONSTRAINT products_sale_times CHECK (sale_price IS NOT NULL OR (sale_starts_at IS NULL AND sale_ends_at IS NULL));
+
+-- Every price a product has had, newest last
+CREATE TABLE product_price_history (
+    history_id BIGSERIAL PRIMARY KEY,
+    product_id BIGINT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    price NUMERIC(10, 2) NOT NULL,
+    compare_at_price NUMERIC(10, 2),
+    sale_price NUMERIC(10, 2),
+    sale_starts_at TIMESTAMP,
+    sale_ends_at TIMESTAMP,
+    changed_at TIMESTAMP NOT NULL DEFAULT (NOW() AT TIME ZONE 'UTC')
+);
+
+CREATE INDEX idx_product_price_history_product ON product_price_history (product_id, history_id);
+
+-- Recorded by a trigger so every write path (updates, imports, scripts) keeps the history
+CREATE OR REPLACE FUNCTION record_product_price() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT'
+        OR NEW.price IS DISTINCT FROM OLD.price
+        OR NEW.compare_at_price IS DISTINCT FROM OLD.compare_at_price
+        OR NEW.sale_price IS DISTINCT FROM OLD.sale_price
+        OR NEW.sale_starts_at IS DISTINCT FROM OLD.sale_starts_at
+        OR NEW.sale_ends_at IS DISTINCT FROM OLD.sale_ends_at
+    THEN
+        INSERT INTO product_price_history
+            (product_id, price, compare_at_price, sale_price, sale_starts_at, sale_ends_at)
+        VALUES
+            (NEW.id, NEW.price, NEW.compare_at_price, NEW.sale_price, NEW.sale_starts_at, NEW.sale_ends_at);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_product_price
+    AFTER INSERT OR UPDATE ON products
+    FOR EACH ROW EXECUTE FUNCTION record_product_price();
+
+-- Start the history from today's prices
+INSERT INTO product_price_history (product_id, price)
+SELECT id, price FROM products;
+
+-- What a unit costs right now: the regular price (a variant's own price or the product's),
+-- or the sale price while the sale runs and it is lower. Timestamps are UTC like the rest
+-- of the schema.
+CREATE OR REPLACE FUNCTION product_effective_price(
+    regular_price NUMERIC,
+    sale_price NUMERIC,
+    sale_starts_at TIMESTAMP,
+    sale_ends_at TIMESTAMP
+) RETURNS NUMERIC AS $$
+    SELECT CASE
+        WHEN sale_price IS NOT NULL
+            AND (sale_starts_at IS NULL OR sale_starts_at <= NOW() AT TIME ZONE 'UTC')
+            AND (sale_ends_at IS NULL OR sale_ends_at > NOW() AT TIME ZONE 'UTC')
+        THEN LEAST(regular_price, sale_price)
+        ELSE regular_price
+    END
+$$ LANGUAGE SQL STABLE;
@@ -0,0 +1,7 @@
+ALTER TABLE order_items DROP COLUMN IF EXISTS discount_amount;
+ALTER TABLE orders DROP COLUMN IF EXISTS shipping_fee;
+ALTER TABLE orders DROP COLUMN IF EXISTS discount_amount;
+DROP TABLE IF EXISTS order_promotions;
+DROP TABLE IF EXISTS cart_promotions;
+DROP TABLE IF EXISTS promotions;
+DROP TYPE IF EXISTS promotion_kind;
@@ -0,0 +1,60 @@
+CREATE TYPE promotion_kind AS ENUM ('PERCENTAGE', 'FIXED', 'FREE_SHIPPING');
+
+-- Coupon codes customers apply to their cart. Codes are stored upper-case and matched
+-- case-insensitively.
+CREATE TABLE promotions (
+    promotion_id BIGSERIAL PRIMARY KEY,
+    code VARCHAR(40) NOT NULL UNIQUE CHECK (code = UPPER(code)),
+    description TEXT,
+    kind promotion_kind NOT NULL,
+    -- Percent off for PERCENTAGE, amount off for FIXED; unused for FREE_SHIPPING
+    value NUMERIC(10, 2) CHECK (value > 0),
+    -- Caps a percentage discount
+    max_discount NUMERIC(10, 2) CHECK (max_discount > 0),
+    -- Compared with the subtotal of the lines the code applies to
+    min_spend NUMERIC(10, 2) CHECK (min_spend > 0),
+    -- The code applies to the whole cart when both lists are empty; categories include
+    -- their subcategories
+    product_ids BIGINT[] NOT NULL DEFAULT '{}',
+    category_ids BIGINT[] NOT NULL DEFAULT '{}',
+    -- Redemptions by orders that weren't cancelled; no limit when NULL
+    usage_limit INT CHECK (usage_limit > 0),
+    per_user_limit INT CHECK (per_user_limit > 0),
+    starts_at TIMESTAMP,
+    ends_at TIMESTAMP,
+    -- A code that isn't stackable can't share a cart with any other code
+    stackable BOOLEAN NOT NULL DEFAULT FALSE,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (ends_at IS NULL OR starts_at IS NULL OR ends_at > starts_at),
+    CHECK ((kind = 'FREE_SHIPPING') = (value IS NULL)),
+    CHECK (kind <> 'PERCENTAGE' OR value <= 100)
+);
+
+-- Codes applied to a cart, in the order they were applied
+CREATE TABLE cart_promotions (
+    cart_id BIGINT NOT NULL REFERENCES cart(cart_id) ON DELETE CASCADE,
+    promotion_id BIGINT NOT NULL REFERENCES promotions(promotion_id) ON DELETE CASCADE,
+    added_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (cart_id, promotion_id)
+);
+
+-- Codes redeemed by an order, frozen at checkout
+CREATE TABLE order_promotions (
+    order_id BIGINT NOT NULL REFERENCES orders(order_id) ON DELETE CASCADE,
+    promotion_id BIGINT NOT NULL REFERENCES promotions(promotion_id),
+    code VARCHAR(40) NOT NULL,
+    discount_amount NUMERIC(10, 2) NOT NULL DEFAULT 0,
+    free_shipping BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (order_id, promotion_id)
+);
+
+CREATE INDEX idx_order_promotions_promotion ON order_promotions(promotion_id);
+
+-- total_amount = item subtotal - discount_amount + shipping_fee
+ALTER TABLE orders ADD COLUMN discount_amount NUMERIC(10, 2) NOT NULL DEFAULT 0;
+ALTER TABLE orders ADD COLUMN shipping_fee NUMERIC(10, 2) NOT NULL DEFAULT 0;
+
+-- The line's share of the order discount, for all of its units
+ALTER TABLE order_items ADD COLUMN discount_amount NUMERIC(10, 2) NOT NULL DEFAULT 0;
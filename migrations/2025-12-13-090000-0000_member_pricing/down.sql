@@ -0,0 +1,26 @@
+ALTER TABLE order_items DROP COLUMN IF EXISTS price_tier;
+DROP TYPE IF EXISTS price_tier;
+
+CREATE OR REPLACE FUNCTION record_product_price() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT'
+        OR NEW.price IS DISTINCT FROM OLD.price
+        OR NEW.compare_at_price IS DISTINCT FROM OLD.compare_at_price
+        OR NEW.sale_price IS DISTINCT FROM OLD.sale_price
+        OR NEW.sale_starts_at IS DISTINCT FROM OLD.sale_starts_at
+        OR NEW.sale_ends_at IS DISTINCT FROM OLD.sale_ends_at
+    THEN
+        INSERT INTO product_price_history
+            (product_id, price, compare_at_price, sale_price, sale_starts_at, sale_ends_at)
+        VALUES
+            (NEW.id, NEW.price, NEW.compare_at_price, NEW.sale_price, NEW.sale_starts_at, NEW.sale_ends_at);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE product_price_history DROP COLUMN IF EXISTS member_price;
+ALTER TABLE variants DROP COLUMN IF EXISTS member_price_override;
+ALTER TABLE products DROP COLUMN IF EXISTS member_price;
+DROP TABLE IF EXISTS student_affiliations;
+DROP TYPE IF EXISTS affiliation_status;
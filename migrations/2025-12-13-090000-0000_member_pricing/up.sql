@@ -0,0 +1,47 @@
+CREATE TYPE affiliation_status AS ENUM ('PENDING', 'VERIFIED', 'REJECTED');
+
+-- A customer's claim to be an Intania student, checked by an admin. Verified students pay
+-- member prices.
+CREATE TABLE student_affiliations (
+    user_id BIGINT PRIMARY KEY REFERENCES users(user_id) ON DELETE CASCADE,
+    student_id VARCHAR(20) NOT NULL UNIQUE,
+    status affiliation_status NOT NULL DEFAULT 'PENDING',
+    -- Why the claim was rejected
+    note TEXT,
+    submitted_at TIMESTAMP NOT NULL DEFAULT (NOW() AT TIME ZONE 'UTC'),
+    reviewed_by VARCHAR(100),
+    reviewed_at TIMESTAMP
+);
+
+CREATE INDEX idx_student_affiliations_status ON student_affiliations (status, submitted_at);
+
+-- What verified members pay; a variant's own member price replaces the product's. Members
+-- pay whichever is lower of this and the regular or sale price.
+ALTER TABLE products ADD COLUMN member_price NUMERIC(10, 2) CHECK (member_price > 0);
+ALTER TABLE variants ADD COLUMN member_price_override NUMERIC(10, 2) CHECK (member_price_override > 0);
+
+ALTER TABLE product_price_history ADD COLUMN member_price NUMERIC(10, 2);
+
+CREATE OR REPLACE FUNCTION record_product_price() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT'
+        OR NEW.price IS DISTINCT FROM OLD.price
+        OR NEW.compare_at_price IS DISTINCT FROM OLD.compare_at_price
+        OR NEW.sale_price IS DISTINCT FROM OLD.sale_price
+        OR NEW.sale_starts_at IS DISTINCT FROM OLD.sale_starts_at
+        OR NEW.sale_ends_at IS DISTINCT FROM OLD.sale_ends_at
+        OR NEW.member_price IS DISTINCT FROM OLD.member_price
+    THEN
+        INSERT INTO product_price_history
+            (product_id, price, compare_at_price, sale_price, sale_starts_at, sale_ends_at, member_price)
+        VALUES
+            (NEW.id, NEW.price, NEW.compare_at_price, NEW.sale_price, NEW.sale_starts_at, NEW.sale_ends_at, NEW.member_price);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Which price an order line was sold at
+CREATE TYPE price_tier AS ENUM ('REGULAR', 'SALE', 'MEMBER');
+
+ALTER TABLE order_items ADD COLUMN price_tier price_tier NOT NULL DEFAULT 'REGULAR';
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS order_item_components;
+DROP TABLE IF EXISTS cart_item_choices;
+DROP TABLE IF EXISTS bundle_components;
+ALTER TABLE products DROP COLUMN IF EXISTS kind;
+DROP TYPE IF EXISTS product_kind;
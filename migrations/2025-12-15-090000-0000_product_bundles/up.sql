@@ -0,0 +1,37 @@
+CREATE TYPE product_kind AS ENUM ('STANDARD', 'BUNDLE');
+
+-- A bundle is sold at its own price through its own variant, but takes its stock from the
+-- products it is made of
+ALTER TABLE products ADD COLUMN kind product_kind NOT NULL DEFAULT 'STANDARD';
+
+-- What goes into a bundle: a set variant, or any variant of the product as the customer
+-- chooses when variant_id is NULL
+CREATE TABLE bundle_components (
+    component_id BIGSERIAL PRIMARY KEY,
+    bundle_id BIGINT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    product_id BIGINT NOT NULL REFERENCES products(id),
+    variant_id BIGINT REFERENCES variants(variant_id),
+    quantity INT NOT NULL DEFAULT 1 CHECK (quantity > 0),
+    position INT NOT NULL DEFAULT 0,
+    CHECK (product_id <> bundle_id)
+);
+
+CREATE INDEX idx_bundle_components_bundle ON bundle_components (bundle_id, position);
+CREATE INDEX idx_bundle_components_product ON bundle_components (product_id);
+
+-- The variants a customer chose for a bundle in their cart
+CREATE TABLE cart_item_choices (
+    item_id BIGINT NOT NULL REFERENCES cart_items(item_id) ON DELETE CASCADE,
+    component_id BIGINT NOT NULL REFERENCES bundle_components(component_id) ON DELETE CASCADE,
+    variant_id BIGINT NOT NULL REFERENCES variants(variant_id) ON DELETE CASCADE,
+    PRIMARY KEY (item_id, component_id)
+);
+
+-- The component variants a bundle order line took from stock, per bundle sold, so
+-- cancellations and refunds put back the same units
+CREATE TABLE order_item_components (
+    order_item_id BIGINT NOT NULL REFERENCES order_items(order_item_id) ON DELETE CASCADE,
+    variant_id BIGINT NOT NULL REFERENCES variants(variant_id),
+    quantity INT NOT NULL CHECK (quantity > 0),
+    PRIMARY KEY (order_item_id, variant_id)
+);
@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS refresh_product_rating ON product_reviews;
+DROP FUNCTION IF EXISTS refresh_product_rating();
+ALTER TABLE products DROP COLUMN IF EXISTS rating_count, DROP COLUMN IF EXISTS rating_average;
+DROP TABLE IF EXISTS product_reviews;
+DROP TYPE IF EXISTS review_status;
@@ -0,0 +1,50 @@
+-- New reviews wait for an admin to approve them; hidden ones stay on record but are not shown
+CREATE TYPE review_status AS ENUM ('PENDING', 'APPROVED', 'HIDDEN');
+
+-- One review per customer per product, from customers with a completed order for it
+CREATE TABLE product_reviews (
+    review_id BIGSERIAL PRIMARY KEY,
+    product_id BIGINT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    rating SMALLINT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    body TEXT,
+    photo_urls TEXT[] NOT NULL DEFAULT '{}',
+    status review_status NOT NULL DEFAULT 'PENDING',
+    moderated_by VARCHAR(100),
+    moderated_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT (NOW() AT TIME ZONE 'UTC'),
+    UNIQUE (product_id, user_id)
+);
+
+CREATE INDEX idx_product_reviews_product ON product_reviews (product_id, status, created_at DESC);
+CREATE INDEX idx_product_reviews_status ON product_reviews (status, created_at);
+
+-- Average and count of approved reviews, kept on the product so listings don't aggregate
+ALTER TABLE products
+    ADD COLUMN rating_average NUMERIC(3, 2),
+    ADD COLUMN rating_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE OR REPLACE FUNCTION refresh_product_rating() RETURNS TRIGGER AS $$
+DECLARE
+    target BIGINT;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        target := OLD.product_id;
+    ELSE
+        target := NEW.product_id;
+    END IF;
+
+    UPDATE products
+    SET (rating_average, rating_count) = (
+        SELECT ROUND(AVG(rating), 2), COUNT(*)
+        FROM product_reviews
+        WHERE product_id = target AND status = 'APPROVED'
+    )
+    WHERE id = target;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER refresh_product_rating
+    AFTER INSERT OR UPDATE OR DELETE ON product_reviews
+    FOR EACH ROW EXECUTE FUNCTION refresh_product_rating();
@@ -0,0 +1,22 @@
+-- Questions customers ask about a product, answered publicly by staff. Hidden questions are
+-- kept but no longer shown.
+CREATE TABLE product_questions (
+    question_id BIGSERIAL PRIMARY KEY,
+    product_id BIGINT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    answer TEXT,
+    answered_by VARCHAR(100),
+    answered_at TIMESTAMP,
+    hidden_by VARCHAR(100),
+    hidden_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT (NOW() AT TIME ZONE 'UTC'),
+    CONSTRAINT product_questions_answered CHECK ((answer IS NULL) = (answered_at IS NULL))
+);
+
+CREATE INDEX idx_product_questions_product ON product_questions (product_id, created_at DESC)
+    WHERE hidden_at IS NULL;
+
+-- The admin queue of questions still waiting for an answer
+CREATE INDEX idx_product_questions_unanswered ON product_questions (created_at)
+    WHERE answer IS NULL AND hidden_at IS NULL;
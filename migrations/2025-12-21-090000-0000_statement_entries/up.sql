@@ -0,0 +1,17 @@
+-- Deposits read from imported bank statements. Re-importing a statement finds its rows here,
+-- so a deposit that already verified a payment can't verify another one. Identical rows in
+-- one statement are told apart by their occurrence.
+CREATE TABLE statement_entries (
+    entry_id BIGSERIAL PRIMARY KEY,
+    bank VARCHAR(20) NOT NULL,
+    transferred_at TIMESTAMP NOT NULL,
+    amount NUMERIC(12, 2) NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    occurrence SMALLINT NOT NULL DEFAULT 1,
+    payment_id BIGINT REFERENCES payments(payment_id) ON DELETE SET NULL,
+    imported_at TIMESTAMP NOT NULL DEFAULT (NOW() AT TIME ZONE 'UTC'),
+    UNIQUE (bank, transferred_at, amount, description, occurrence)
+);
+
+CREATE UNIQUE INDEX idx_statement_entries_payment ON statement_entries (payment_id)
+    WHERE payment_id IS NOT NULL;
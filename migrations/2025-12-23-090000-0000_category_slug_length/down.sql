@@ -0,0 +1 @@
+ALTER TABLE categories ALTER COLUMN slug TYPE VARCHAR(120);
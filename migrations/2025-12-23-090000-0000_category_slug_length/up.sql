@@ -0,0 +1,2 @@
+-- Category slugs come from the same slugify as product slugs, which allows 150 characters
+ALTER TABLE categories ALTER COLUMN slug TYPE VARCHAR(150);
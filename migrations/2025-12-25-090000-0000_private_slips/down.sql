@@ -0,0 +1,2 @@
+ALTER TABLE refunds RENAME COLUMN evidence_object TO evidence_url;
+ALTER TABLE payments RENAME COLUMN slip_object TO slip_url;
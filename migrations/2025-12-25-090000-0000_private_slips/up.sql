@@ -0,0 +1,14 @@
+-- Payment and refund slips show bank account details, so they now go to the private bucket
+-- and are served by the API. Rows keep the object name rather than a public URL. Objects
+-- uploaded before this migration have to be moved to the private bucket under the same name.
+ALTER TABLE payments RENAME COLUMN slip_url TO slip_object;
+UPDATE payments
+SET slip_object = regexp_replace(
+    slip_object, '^https://storage\.googleapis\.com/(projects/_/buckets/)?[^/]+/', '')
+WHERE slip_object LIKE 'https://%';
+
+ALTER TABLE refunds RENAME COLUMN evidence_url TO evidence_object;
+UPDATE refunds
+SET evidence_object = regexp_replace(
+    evidence_object, '^https://storage\.googleapis\.com/(projects/_/buckets/)?[^/]+/', '')
+WHERE evidence_object LIKE 'https://%';
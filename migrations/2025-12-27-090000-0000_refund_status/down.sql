@@ -0,0 +1,2 @@
+ALTER TABLE refunds DROP COLUMN IF EXISTS refund_status;
+DROP TYPE IF EXISTS refund_status;
@@ -0,0 +1,7 @@
+-- A refund is recorded as PENDING under the payment lock before a provider is asked to send
+-- the money, so a concurrent refund sees the reduced balance. It becomes COMPLETED once the
+-- money has gone out, or FAILED when the provider turned it down; failed refunds don't count
+-- against the payment.
+CREATE TYPE refund_status AS ENUM ('PENDING', 'COMPLETED', 'FAILED');
+
+ALTER TABLE refunds ADD COLUMN refund_status refund_status NOT NULL DEFAULT 'COMPLETED';
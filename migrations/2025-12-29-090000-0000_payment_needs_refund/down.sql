@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_payments_needs_refund;
+ALTER TABLE payments DROP COLUMN IF EXISTS needs_refund;
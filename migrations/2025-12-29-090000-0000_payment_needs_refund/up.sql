@@ -0,0 +1,5 @@
+-- A gateway charge can succeed after its order was cancelled or already paid another way.
+-- The money is kept on record and the payment flagged until an admin refunds it.
+ALTER TABLE payments ADD COLUMN needs_refund BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX idx_payments_needs_refund ON payments (payment_id) WHERE needs_refund;
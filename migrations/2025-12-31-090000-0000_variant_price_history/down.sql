@@ -0,0 +1,8 @@
+DROP TRIGGER IF EXISTS record_variant_price ON variants;
+DROP FUNCTION IF EXISTS record_variant_price();
+
+DELETE FROM product_price_history WHERE variant_id IS NOT NULL;
+ALTER TABLE product_price_history
+    DROP COLUMN IF EXISTS member_price_override,
+    DROP COLUMN IF EXISTS price_override,
+    DROP COLUMN IF EXISTS variant_id;
@@ -0,0 +1,41 @@
+-- Variant price overrides are part of what a unit sells for, so their changes are kept in
+-- the same history. Rows with a variant_id record that variant's overrides alongside the
+-- product's prices at the time; product rows leave it NULL.
+ALTER TABLE product_price_history
+    ADD COLUMN variant_id BIGINT REFERENCES variants(variant_id) ON DELETE CASCADE,
+    ADD COLUMN price_override NUMERIC(10, 2),
+    ADD COLUMN member_price_override NUMERIC(10, 2);
+
+CREATE OR REPLACE FUNCTION record_variant_price() RETURNS TRIGGER AS $$
+BEGIN
+    IF (TG_OP = 'INSERT'
+            AND (NEW.price_override IS NOT NULL OR NEW.member_price_override IS NOT NULL))
+        OR (TG_OP = 'UPDATE'
+            AND (NEW.price_override IS DISTINCT FROM OLD.price_override
+                OR NEW.member_price_override IS DISTINCT FROM OLD.member_price_override))
+    THEN
+        INSERT INTO product_price_history
+            (product_id, price, compare_at_price, sale_price, sale_starts_at, sale_ends_at,
+             member_price, variant_id, price_override, member_price_override)
+        SELECT p.id, p.price, p.compare_at_price, p.sale_price, p.sale_starts_at, p.sale_ends_at,
+               p.member_price, NEW.variant_id, NEW.price_override, NEW.member_price_override
+        FROM products p
+        WHERE p.id = NEW.product_id;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_variant_price
+    AFTER INSERT OR UPDATE ON variants
+    FOR EACH ROW EXECUTE FUNCTION record_variant_price();
+
+-- Start each variant's history from its current overrides
+INSERT INTO product_price_history
+    (product_id, price, compare_at_price, sale_price, sale_starts_at, sale_ends_at,
+     member_price, variant_id, price_override, member_price_override)
+SELECT p.id, p.price, p.compare_at_price, p.sale_price, p.sale_starts_at, p.sale_ends_at,
+       p.member_price, v.variant_id, v.price_override, v.member_price_override
+FROM variants v
+JOIN products p ON p.id = v.product_id
+WHERE v.price_override IS NOT NULL OR v.member_price_override IS NOT NULL;
@@ -0,0 +1,10 @@
+-- Slugs a category used before, so old links keep resolving. Categories created before slugs
+-- were romanised still have Thai slugs; the application replaces them at startup and keeps
+-- the old ones here.
+CREATE TABLE category_slug_redirects (
+    slug VARCHAR(150) PRIMARY KEY,
+    category_id BIGINT NOT NULL REFERENCES categories(category_id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_category_slug_redirects_category ON category_slug_redirects (category_id);
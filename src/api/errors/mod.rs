@@ -66,12 +66,10 @@ pub fn internal_error(message: impl Into<String>) -> ApiError {
     ApiError::InternalServerError(message.into())
 }
 
-#[allow(dead_code)] // Expected to be used as the API grows
 pub fn unauthorized(message: impl Into<String>) -> ApiError {
     ApiError::Unauthorized(message.into())
 }
 
-#[allow(dead_code)] // Expected to be used as the API grows
 pub fn forbidden(message: impl Into<String>) -> ApiError {
     ApiError::Forbidden(message.into())
 }
@@ -1,14 +1,21 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header::AUTHORIZATION, request::Parts},
+};
 use chrono::prelude::*;
-use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use std::env;
 
+use crate::api::errors::{ApiError, forbidden, unauthorized};
 use crate::core::user::entity::Role;
 use crate::utils::errors::{Error, ErrorCode};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Claims {
     pub id: String,
+    pub user_id: i64,
     pub role: Role,
     #[serde(with = "date_serializer")]
     iat: DateTime<Utc>,
@@ -39,7 +46,7 @@ mod date_serializer {
 }
 
 impl Claims {
-    pub fn new(username: String, role: Role) -> Claims {
+    pub fn new(user_id: i64, username: String, role: Role) -> Claims {
         let iat = Utc::now();
         let exp = match role {
             Role::Admin => iat + chrono::Duration::minutes(30),
@@ -48,6 +55,7 @@ impl Claims {
 
         Claims {
             id: username,
+            user_id,
             role,
             iat,
             exp,
@@ -65,4 +73,55 @@ impl Claims {
             Err(_) => Err(Error::new(ErrorCode::InternalError)),
         }
     }
+
+    pub fn decode(token: &str) -> Result<Claims, Error> {
+        let key = env::var("JWT_PASSWORD").unwrap();
+        let validation = Validation::new(Algorithm::HS512);
+
+        jsonwebtoken::decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(key.as_bytes()),
+            &validation,
+        )
+        .map(|data| data.claims)
+        .map_err(|_| Error::new(ErrorCode::InvalidCredentials))
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Claims
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| unauthorized("Missing bearer token"))?;
+
+        Claims::decode(token).map_err(|_| unauthorized("Invalid or expired token"))
+    }
+}
+
+// Claims of an authenticated user with the admin role
+pub struct AdminClaims(pub Claims);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for AdminClaims
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let claims = Claims::from_request_parts(parts, state).await?;
+        if claims.role != Role::Admin {
+            return Err(forbidden("Admin access required"));
+        }
+        Ok(AdminClaims(claims))
+    }
 }
@@ -0,0 +1,101 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::{AdminClaims, Claims};
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::affiliation::{
+    diesel::DieselAffiliationRepository,
+    entity::{AffiliationQuery, AffiliationRequest, AffiliationStatus, RejectAffiliationRequest},
+    service::AffiliationService,
+};
+use crate::utils::errors::{Error, ErrorCode};
+
+fn get_service(state: &ApiState) -> AffiliationService {
+    let repo = Arc::new(DieselAffiliationRepository::new(state.pool.clone()));
+    AffiliationService::new(repo)
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        ErrorCode::ResourceAlreadyExists => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+// GET /users/me/affiliation
+pub async fn get_affiliation(claims: Claims, State(state): State<ApiState>) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.get_affiliation(claims.user_id).await {
+        Ok(affiliation) => (StatusCode::OK, Json(ApiResponse::ok(affiliation))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// PUT /users/me/affiliation
+pub async fn submit_affiliation(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Json(req): Json<AffiliationRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service
+        .submit_affiliation(claims.user_id, &req.student_id)
+        .await
+    {
+        Ok(affiliation) => (StatusCode::OK, Json(ApiResponse::ok(affiliation))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /admin/affiliations?status=
+pub async fn list_affiliations(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Query(query): Query<AffiliationQuery>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    let status = query.status.unwrap_or(AffiliationStatus::Pending);
+    match service.list_affiliations(status).await {
+        Ok(affiliations) => (StatusCode::OK, Json(ApiResponse::ok(affiliations))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/affiliations/:user_id/verify
+pub async fn verify_affiliation(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    Path(user_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.verify_affiliation(user_id, &claims.id).await {
+        Ok(affiliation) => (StatusCode::OK, Json(ApiResponse::ok(affiliation))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/affiliations/:user_id/reject
+pub async fn reject_affiliation(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    Path(user_id): Path<i64>,
+    Json(req): Json<RejectAffiliationRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service
+        .reject_affiliation(user_id, &claims.id, &req.reason)
+        .await
+    {
+        Ok(affiliation) => (StatusCode::OK, Json(ApiResponse::ok(affiliation))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
@@ -0,0 +1,68 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::AdminClaims;
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::bundle::{
+    diesel::DieselBundleRepository, entity::BundleRequest, service::BundleService,
+};
+use crate::utils::errors::{Error, ErrorCode};
+
+fn get_service(state: &ApiState) -> BundleService {
+    let repo = Arc::new(DieselBundleRepository::new(state.pool.clone()));
+    BundleService::new(repo)
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+// GET /products/:id/bundle
+pub async fn get_components(
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.get_components(product_id).await {
+        Ok(components) => (StatusCode::OK, Json(ApiResponse::ok(components))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// PUT /admin/products/:id/bundle
+pub async fn set_components(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+    Json(req): Json<BundleRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.set_components(product_id, req).await {
+        Ok(components) => (StatusCode::OK, Json(ApiResponse::ok(components))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// DELETE /admin/products/:id/bundle
+pub async fn remove_bundle(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.remove_bundle(product_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => error_response(&err),
+    }
+}
@@ -1,29 +1,79 @@
-use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
 use std::sync::Arc;
 
 use crate::api::ApiState;
+use crate::api::guards::guard::Claims;
 use crate::api::response::{ApiError, ApiResponse};
 use crate::core::cart::{
     diesel::DieselCartRepository, entity::AddToCartRequest, service::CartService,
 };
+use crate::core::promotion::entity::ApplyCouponRequest;
+use crate::utils::errors::{Error, ErrorCode};
 
 fn get_service(state: &ApiState) -> CartService {
     let repo = Arc::new(DieselCartRepository::new(state.pool.clone()));
     CartService::new(repo)
 }
 
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        ErrorCode::ResourceAlreadyExists => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+// GET /cart
+pub async fn get_cart(claims: Claims, State(state): State<ApiState>) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.get_cart(claims.user_id).await {
+        Ok(cart) => (StatusCode::OK, Json(ApiResponse::ok(cart))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
 // PUT /cart/items
 pub async fn add_item(
+    claims: Claims,
     State(state): State<ApiState>,
     Json(req): Json<AddToCartRequest>,
 ) -> impl IntoResponse {
     let service = get_service(&state);
-    match service.add_to_cart(req).await {
+    match service.add_to_cart(claims.user_id, req).await {
         Ok(resp) => (StatusCode::OK, Json(ApiResponse::ok(resp))).into_response(),
-        Err(err) => (
-            StatusCode::BAD_REQUEST,
-            Json(ApiError::new(err.to_string())),
-        )
-            .into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /cart/coupon
+pub async fn apply_coupon(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Json(req): Json<ApplyCouponRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.apply_coupon(claims.user_id, &req.code).await {
+        Ok(cart) => (StatusCode::OK, Json(ApiResponse::ok(cart))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// DELETE /cart/coupon/:code
+pub async fn remove_coupon(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(code): Path<String>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.remove_coupon(claims.user_id, &code).await {
+        Ok(cart) => (StatusCode::OK, Json(ApiResponse::ok(cart))).into_response(),
+        Err(err) => error_response(&err),
     }
 }
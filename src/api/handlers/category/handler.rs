@@ -0,0 +1,85 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::AdminClaims;
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::category::{
+    diesel::DieselCategoryRepository,
+    entity::{DeleteCategoryQuery, NewCategory, UpdateCategory},
+    service::CategoryService,
+};
+use crate::utils::errors::{Error, ErrorCode};
+
+fn get_service(state: &ApiState) -> CategoryService {
+    let repo = Arc::new(DieselCategoryRepository::new(state.pool.clone()));
+    CategoryService::new(repo)
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        ErrorCode::ResourceAlreadyExists => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+// GET /categories
+pub async fn category_tree(State(state): State<ApiState>) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.category_tree().await {
+        Ok(tree) => (StatusCode::OK, Json(ApiResponse::ok(tree))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/categories
+pub async fn create_category(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Json(req): Json<NewCategory>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.create_category(req).await {
+        Ok(category) => (StatusCode::CREATED, Json(ApiResponse::ok(category))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// PUT /admin/categories/:id
+pub async fn update_category(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(category_id): Path<i64>,
+    Json(req): Json<UpdateCategory>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.update_category(category_id, req).await {
+        Ok(category) => (StatusCode::OK, Json(ApiResponse::ok(category))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// DELETE /admin/categories/:id?reassign_to=
+pub async fn delete_category(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(category_id): Path<i64>,
+    Query(query): Query<DeleteCategoryQuery>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service
+        .delete_category(category_id, query.reassign_to)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => error_response(&err),
+    }
+}
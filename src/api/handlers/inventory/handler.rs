@@ -0,0 +1,75 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::AdminClaims;
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::inventory::{
+    diesel::DieselInventoryRepository,
+    entity::{MovementQuery, StockAdjustmentRequest},
+    service::InventoryService,
+};
+use crate::utils::errors::{Error, ErrorCode};
+
+fn get_service(state: &ApiState) -> InventoryService {
+    let repo = Arc::new(DieselInventoryRepository::new(state.pool.clone()));
+    InventoryService::new(repo)
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+// POST /admin/inventory/variants/:id/movements
+pub async fn adjust_stock(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    Path(variant_id): Path<i64>,
+    Json(req): Json<StockAdjustmentRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.adjust_stock(variant_id, req, claims.id).await {
+        Ok(resp) => (StatusCode::CREATED, Json(ApiResponse::ok(resp))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /admin/inventory/variants/:id/movements
+pub async fn list_movements(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(variant_id): Path<i64>,
+    Query(query): Query<MovementQuery>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(20).clamp(1, 100);
+
+    match service.movement_history(variant_id, page, page_size).await {
+        Ok(history) => (StatusCode::OK, Json(ApiResponse::ok(history))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /admin/inventory/reconciliation
+pub async fn reconcile(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.reconcile().await {
+        Ok(drift) => (StatusCode::OK, Json(ApiResponse::ok(drift))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
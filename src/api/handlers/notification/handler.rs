@@ -0,0 +1,30 @@
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::Claims;
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::notification::{
+    diesel::DieselNotificationRepository, service::NotificationService,
+};
+
+fn get_service(state: &ApiState) -> NotificationService {
+    let repo = Arc::new(DieselNotificationRepository::new(state.pool.clone()));
+    NotificationService::new(repo)
+}
+
+// GET /notifications
+pub async fn list_notifications(
+    claims: Claims,
+    State(state): State<ApiState>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.list_for_user(claims.user_id).await {
+        Ok(notifications) => (StatusCode::OK, Json(ApiResponse::ok(notifications))).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::new(err.to_string())),
+        )
+            .into_response(),
+    }
+}
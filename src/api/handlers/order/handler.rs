@@ -0,0 +1,147 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::Claims;
+use crate::api::handlers::payment::handler as payment_handler;
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::notification::{
+    diesel::DieselNotificationRepository, service::NotificationService,
+};
+use crate::core::order::{
+    diesel::DieselOrderRepository,
+    entity::{CheckoutRequest, CheckoutResponse, PaymentMethod, QrImageFormat, QrImageQuery},
+    service::OrderService,
+};
+use crate::core::user::entity::Role;
+use crate::utils::errors::{Error, ErrorCode};
+use crate::utils::promptpay;
+
+fn get_service(state: &ApiState) -> OrderService {
+    let repo = Arc::new(DieselOrderRepository::new(state.pool.clone()));
+    let notifications = NotificationService::new(Arc::new(DieselNotificationRepository::new(
+        state.pool.clone(),
+    )));
+    OrderService::new(
+        repo,
+        notifications,
+        chrono::Duration::minutes(state.config.order_payment_timeout_minutes),
+        state.config.promptpay_id.clone(),
+        state.config.shipping_fee.clone(),
+    )
+}
+
+// POST /orders
+pub async fn checkout(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Json(req): Json<CheckoutRequest>,
+) -> impl IntoResponse {
+    let payment_method = req.payment_method.unwrap_or_default();
+    let service = get_service(&state);
+    let order = match service.checkout(claims.user_id, req).await {
+        Ok(order) => order,
+        Err(err) => return error_response(&err),
+    };
+
+    // The order stands even if the charge fails; the customer can retry via /orders/:id/charges
+    let charge = match payment_method {
+        PaymentMethod::Transfer => None,
+        PaymentMethod::Gateway => {
+            let payments = payment_handler::get_service(&state);
+            match payments
+                .create_charge(order.order.order_id, claims.user_id)
+                .await
+            {
+                Ok(charge) => Some(charge),
+                Err(e) => {
+                    error!(error = %e, order_id = order.order.order_id, "Failed to open charge at checkout");
+                    None
+                }
+            }
+        }
+    };
+
+    (
+        StatusCode::CREATED,
+        Json(ApiResponse::ok(CheckoutResponse { order, charge })),
+    )
+        .into_response()
+}
+
+// GET /orders/:id
+pub async fn get_order(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(order_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    let is_admin = claims.role == Role::Admin;
+    match service.get_order(order_id, claims.user_id, is_admin).await {
+        Ok(order) => (StatusCode::OK, Json(ApiResponse::ok(order))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /orders/:id/promptpay
+pub async fn get_promptpay(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(order_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    let is_admin = claims.role == Role::Admin;
+    match service
+        .get_promptpay(order_id, claims.user_id, is_admin)
+        .await
+    {
+        Ok(qr) => (StatusCode::OK, Json(ApiResponse::ok(qr))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /orders/:id/promptpay/qr?format=png|svg
+pub async fn get_promptpay_qr(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(order_id): Path<i64>,
+    Query(query): Query<QrImageQuery>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    let is_admin = claims.role == Role::Admin;
+    let qr = match service
+        .get_promptpay(order_id, claims.user_id, is_admin)
+        .await
+    {
+        Ok(qr) => qr,
+        Err(err) => return error_response(&err),
+    };
+
+    match query.format.unwrap_or_default() {
+        QrImageFormat::Svg => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "image/svg+xml")],
+            qr.qr_svg,
+        )
+            .into_response(),
+        QrImageFormat::Png => match promptpay::render_png(&qr.payload) {
+            Ok(png) => (StatusCode::OK, [(header::CONTENT_TYPE, "image/png")], png).into_response(),
+            Err(err) => error_response(&err),
+        },
+    }
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
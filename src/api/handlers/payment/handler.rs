@@ -0,0 +1,331 @@
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Multipart, Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::IntoResponse,
+};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDateTime};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::{AdminClaims, Claims};
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::notification::{
+    diesel::DieselNotificationRepository, service::NotificationService,
+};
+use crate::core::payment::{
+    diesel::DieselPaymentRepository,
+    entity::{PaymentQueueQuery, PaymentStatus, PaymentSubmission, RejectPaymentRequest},
+    service::PaymentService,
+    statement::StatementFormat,
+};
+use crate::core::user::entity::Role;
+use crate::utils::errors::{Error, ErrorCode};
+
+pub(crate) fn get_service(state: &ApiState) -> PaymentService {
+    let repo = Arc::new(DieselPaymentRepository::new(state.pool.clone()));
+    let notifications = NotificationService::new(Arc::new(DieselNotificationRepository::new(
+        state.pool.clone(),
+    )));
+    PaymentService::new(
+        repo,
+        state.storage_service.clone(),
+        notifications,
+        state.payment_provider.clone(),
+    )
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        ErrorCode::ResourceAlreadyExists => StatusCode::CONFLICT,
+        ErrorCode::InvalidCredentials => StatusCode::UNAUTHORIZED,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+fn bad_request(message: impl Into<String>) -> axum::response::Response {
+    (StatusCode::BAD_REQUEST, Json(ApiError::new(message))).into_response()
+}
+
+// Accepts RFC 3339 timestamps as well as local "YYYY-MM-DDTHH:MM[:SS]" values
+fn parse_transfer_time(value: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.naive_utc())
+        .ok()
+        .or_else(|| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").ok())
+        .or_else(|| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M").ok())
+}
+
+// POST /orders/:id/payments (multipart: slip, amount_paid, transferred_at)
+pub async fn submit_payment(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(order_id): Path<i64>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let mut slip: Option<(Vec<u8>, String)> = None;
+    let mut amount_paid: Option<BigDecimal> = None;
+    let mut transferred_at: Option<NaiveDateTime> = None;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return bad_request(format!("Invalid multipart body: {}", e)),
+        };
+
+        match field.name().unwrap_or_default() {
+            "slip" => {
+                let filename = field.file_name().unwrap_or("slip").to_string();
+                match field.bytes().await {
+                    Ok(bytes) => slip = Some((bytes.to_vec(), filename)),
+                    Err(e) => return bad_request(format!("Failed to read file: {}", e)),
+                }
+            }
+            "amount_paid" => {
+                let text = field.text().await.unwrap_or_default();
+                match BigDecimal::from_str(text.trim()) {
+                    Ok(amount) => amount_paid = Some(amount),
+                    Err(_) => return bad_request("amount_paid must be a number"),
+                }
+            }
+            "transferred_at" => {
+                let text = field.text().await.unwrap_or_default();
+                match parse_transfer_time(text.trim()) {
+                    Some(at) => transferred_at = Some(at),
+                    None => return bad_request("transferred_at must be a valid timestamp"),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Some((slip_data, slip_filename)) = slip else {
+        return bad_request("slip is required");
+    };
+    let Some(amount_paid) = amount_paid else {
+        return bad_request("amount_paid is required");
+    };
+    let Some(transferred_at) = transferred_at else {
+        return bad_request("transferred_at is required");
+    };
+
+    let service = get_service(&state);
+    let submission = PaymentSubmission {
+        amount_paid,
+        transferred_at,
+        slip_data,
+        slip_filename,
+    };
+
+    match Box::pin(service.submit_payment(order_id, claims.user_id, submission)).await {
+        Ok(payment) => (StatusCode::CREATED, Json(ApiResponse::ok(payment))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /orders/:id/payments
+pub async fn list_payments(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(order_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.list_payments(order_id, claims.user_id).await {
+        Ok(payments) => (StatusCode::OK, Json(ApiResponse::ok(payments))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /payments/:id/slip
+pub async fn get_slip(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(payment_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    let is_admin = claims.role == Role::Admin;
+    match service.slip(payment_id, claims.user_id, is_admin).await {
+        Ok((slip, content_type)) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, content_type),
+                (header::CACHE_CONTROL, "private, no-store".to_string()),
+            ],
+            slip,
+        )
+            .into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /orders/:id/charges
+pub async fn create_charge(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(order_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.create_charge(order_id, claims.user_id).await {
+        Ok(charge) => (StatusCode::CREATED, Json(ApiResponse::ok(charge))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /payments/callbacks/:provider
+pub async fn provider_callback(
+    State(state): State<ApiState>,
+    Path(provider): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let signature = state
+        .payment_provider
+        .as_ref()
+        .and_then(|p| headers.get(p.signature_header()))
+        .and_then(|value| value.to_str().ok());
+
+    let service = get_service(&state);
+    match service.handle_callback(&provider, signature, &body).await {
+        Ok(()) => (StatusCode::OK, Json(ApiResponse::ok(()))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/payments/:id/sync
+pub async fn sync_payment(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(payment_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.sync_payment(payment_id).await {
+        Ok(payment) => (StatusCode::OK, Json(ApiResponse::ok(payment))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /admin/payments?status=Pending
+pub async fn review_queue(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Query(query): Query<PaymentQueueQuery>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    let status = query.status.unwrap_or(PaymentStatus::Pending);
+    match service.review_queue(status).await {
+        Ok(queue) => (StatusCode::OK, Json(ApiResponse::ok(queue))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /admin/payments/refunds-due
+pub async fn refunds_due(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.refunds_due().await {
+        Ok(payments) => (StatusCode::OK, Json(ApiResponse::ok(payments))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/payments/:id/verify
+pub async fn verify_payment(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    Path(payment_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.verify_payment(payment_id, &claims.id).await {
+        Ok(payment) => (StatusCode::OK, Json(ApiResponse::ok(payment))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/payments/:id/reject
+pub async fn reject_payment(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    Path(payment_id): Path<i64>,
+    Json(req): Json<RejectPaymentRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service
+        .reject_payment(payment_id, &claims.id, &req.reason)
+        .await
+    {
+        Ok(payment) => (StatusCode::OK, Json(ApiResponse::ok(payment))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/payments/statements (multipart: file, format, window_minutes)
+pub async fn import_statement(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let mut file: Option<Vec<u8>> = None;
+    let mut format: Option<StatementFormat> = None;
+    let mut window_minutes: i64 = 30;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return bad_request(format!("Invalid multipart body: {}", e)),
+        };
+
+        match field.name().unwrap_or_default() {
+            "file" => match field.bytes().await {
+                Ok(bytes) => file = Some(bytes.to_vec()),
+                Err(e) => return bad_request(format!("Failed to read file: {}", e)),
+            },
+            "format" => {
+                let text = field.text().await.unwrap_or_default();
+                match StatementFormat::from_str(&text) {
+                    Ok(parsed) => format = Some(parsed),
+                    Err(e) => return bad_request(e.to_string()),
+                }
+            }
+            "window_minutes" => {
+                let text = field.text().await.unwrap_or_default();
+                match text.trim().parse::<i64>() {
+                    Ok(minutes) if (1..=1440).contains(&minutes) => window_minutes = minutes,
+                    _ => return bad_request("window_minutes must be between 1 and 1440"),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Some(file) = file else {
+        return bad_request("file is required");
+    };
+    let Some(format) = format else {
+        return bad_request("format is required");
+    };
+
+    let service = get_service(&state);
+    let reviewed_by = format!("statement:{}", claims.id);
+    match service
+        .import_statement(
+            format,
+            &file,
+            chrono::Duration::minutes(window_minutes),
+            &reviewed_by,
+        )
+        .await
+    {
+        Ok(report) => (StatusCode::OK, Json(ApiResponse::ok(report))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
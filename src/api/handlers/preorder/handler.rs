@@ -0,0 +1,82 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::AdminClaims;
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::preorder::{
+    diesel::DieselPreorderRepository, entity::PreorderCampaignRequest, service::PreorderService,
+};
+use crate::utils::errors::{Error, ErrorCode};
+
+fn get_service(state: &ApiState) -> PreorderService {
+    let repo = Arc::new(DieselPreorderRepository::new(state.pool.clone()));
+    PreorderService::new(repo)
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        ErrorCode::ResourceAlreadyExists => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+// GET /products/:id/preorder
+pub async fn get_campaign(
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.get_campaign(product_id).await {
+        Ok(campaign) => (StatusCode::OK, Json(ApiResponse::ok(campaign))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// PUT /admin/preorders/:product_id
+pub async fn save_campaign(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+    Json(req): Json<PreorderCampaignRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.save_campaign(product_id, req).await {
+        Ok(campaign) => (StatusCode::OK, Json(ApiResponse::ok(campaign))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// DELETE /admin/preorders/:product_id
+pub async fn remove_campaign(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.remove_campaign(product_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /admin/preorders/:product_id/report
+pub async fn report(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.report(product_id).await {
+        Ok(report) => (StatusCode::OK, Json(ApiResponse::ok(report))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
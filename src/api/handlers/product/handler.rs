@@ -1,16 +1,22 @@
 use axum::{
     Json,
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::{Multipart, Path, Query, State},
+    http::{StatusCode, header},
     response::IntoResponse,
 };
 use std::sync::Arc;
 
 use crate::api::ApiState;
+use crate::api::guards::guard::AdminClaims;
 use crate::api::handlers::product::response::{
-    ErrorResponse, ProductQuery, ProductResponse, SearchQuery,
+    ArchivedQuery, ErrorResponse, ExportQuery, ProductQuery, ProductResponse, SearchQuery,
+};
+use crate::api::handlers::search::handler as search_handler;
+use crate::core::product::sheet::SheetFormat;
+use crate::core::product::{
+    DieselProductRepository, NewProduct, ProductPricing, ProductSchedule, ProductService,
+    SlugLookup, UpdateProduct,
 };
-use crate::core::product::{DieselProductRepository, NewProduct, ProductService, UpdateProduct};
 fn get_product_service(state: &ApiState) -> ProductService {
     let repository = Arc::new(DieselProductRepository::new(state.pool.clone()));
     ProductService::new(repository)
@@ -18,6 +24,7 @@ fn get_product_service(state: &ApiState) -> ProductService {
 
 // POST /products
 pub async fn create_product(
+    AdminClaims(_): AdminClaims,
     State(state): State<ApiState>,
     Json(new_product): Json<NewProduct>,
 ) -> impl IntoResponse {
@@ -58,7 +65,35 @@ pub async fn get_product(
     }
 }
 
-// GET /products
+// GET /products/by-slug/:slug
+pub async fn get_product_by_slug(
+    State(state): State<ApiState>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    let service = get_product_service(&state);
+
+    match service.get_product_by_slug(&slug).await {
+        Ok(SlugLookup::Found(product_detail)) => {
+            (StatusCode::OK, Json(ProductResponse::new(*product_detail))).into_response()
+        }
+        // Old slugs redirect permanently so links and search engines follow the rename
+        Ok(SlugLookup::Moved(current)) => (
+            StatusCode::MOVED_PERMANENTLY,
+            [(header::LOCATION, format!("/products/by-slug/{}", current))],
+        )
+            .into_response(),
+        Err(e) => {
+            let status = if e.to_string().contains("not found") {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            (status, Json(ErrorResponse::new(e.to_string()))).into_response()
+        }
+    }
+}
+
+// GET /products?category=&status=&min_price=&max_price=&size=&color=&sort=
 pub async fn list_products(
     State(state): State<ApiState>,
     Query(query): Query<ProductQuery>,
@@ -68,7 +103,7 @@ pub async fn list_products(
     let page = query.page.unwrap_or(1).max(1);
     let page_size = query.page_size.unwrap_or(10).clamp(1, 100);
 
-    match service.list_products(page, page_size).await {
+    match service.list_products(query.filter(), page, page_size).await {
         Ok(response) => (StatusCode::OK, Json(ProductResponse::new(response))).into_response(),
         Err(e) => (
             StatusCode::BAD_REQUEST,
@@ -80,6 +115,7 @@ pub async fn list_products(
 
 // PUT /products/:id
 pub async fn update_product(
+    AdminClaims(_): AdminClaims,
     State(state): State<ApiState>,
     Path(product_id): Path<i64>,
     Json(update_product): Json<UpdateProduct>,
@@ -100,7 +136,161 @@ pub async fn update_product(
 }
 
 // DELETE /products/:id
+// Archives rather than deletes, since orders may still refer to the product's variants
 pub async fn delete_product(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_product_service(&state);
+
+    match service.archive_product(product_id).await {
+        Ok(_) => (StatusCode::NO_CONTENT, "").into_response(),
+        Err(e) => {
+            let status = if e.to_string().contains("not found") {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            (status, Json(ErrorResponse::new(e.to_string()))).into_response()
+        }
+    }
+}
+
+// GET /admin/products/:id/preview
+// Shows drafts, scheduled and hidden products as the storefront would once they're live
+pub async fn preview_product(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_product_service(&state);
+
+    match service.preview_product(product_id).await {
+        Ok(product_detail) => {
+            (StatusCode::OK, Json(ProductResponse::new(product_detail))).into_response()
+        }
+        Err(e) => {
+            let status = if e.to_string().contains("not found") {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            (status, Json(ErrorResponse::new(e.to_string()))).into_response()
+        }
+    }
+}
+
+// PUT /admin/products/:id/visibility
+pub async fn set_product_schedule(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+    Json(schedule): Json<ProductSchedule>,
+) -> impl IntoResponse {
+    let service = get_product_service(&state);
+
+    match service.set_product_schedule(product_id, schedule).await {
+        Ok(product) => (StatusCode::OK, Json(ProductResponse::new(product))).into_response(),
+        Err(e) => {
+            let status = if e.to_string().contains("not found") {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            (status, Json(ErrorResponse::new(e.to_string()))).into_response()
+        }
+    }
+}
+
+// PUT /admin/products/:id/pricing
+pub async fn set_product_pricing(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+    Json(pricing): Json<ProductPricing>,
+) -> impl IntoResponse {
+    let service = get_product_service(&state);
+
+    match service.set_product_pricing(product_id, pricing).await {
+        Ok(product) => (StatusCode::OK, Json(ProductResponse::new(product))).into_response(),
+        Err(e) => {
+            let status = if e.to_string().contains("not found") {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            (status, Json(ErrorResponse::new(e.to_string()))).into_response()
+        }
+    }
+}
+
+// GET /admin/products/:id/price-history
+pub async fn get_price_history(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_product_service(&state);
+
+    match service.get_price_history(product_id).await {
+        Ok(history) => (StatusCode::OK, Json(ProductResponse::new(history))).into_response(),
+        Err(e) => {
+            let status = if e.to_string().contains("not found") {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            (status, Json(ErrorResponse::new(e.to_string()))).into_response()
+        }
+    }
+}
+
+// GET /admin/products/archived
+pub async fn list_archived_products(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Query(query): Query<ArchivedQuery>,
+) -> impl IntoResponse {
+    let service = get_product_service(&state);
+
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(10).clamp(1, 100);
+
+    match service.list_archived_products(page, page_size).await {
+        Ok(response) => (StatusCode::OK, Json(ProductResponse::new(response))).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+// POST /admin/products/:id/restore
+pub async fn restore_product(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_product_service(&state);
+
+    match service.restore_product(product_id).await {
+        Ok(product) => (StatusCode::OK, Json(ProductResponse::new(product))).into_response(),
+        Err(e) => {
+            let status = if e.to_string().contains("not found") {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            (status, Json(ErrorResponse::new(e.to_string()))).into_response()
+        }
+    }
+}
+
+// DELETE /admin/products/:id
+pub async fn purge_product(
+    AdminClaims(_): AdminClaims,
     State(state): State<ApiState>,
     Path(product_id): Path<i64>,
 ) -> impl IntoResponse {
@@ -111,6 +301,8 @@ pub async fn delete_product(
         Err(e) => {
             let status = if e.to_string().contains("not found") {
                 StatusCode::NOT_FOUND
+            } else if e.to_string().contains("has been ordered") {
+                StatusCode::CONFLICT
             } else {
                 StatusCode::BAD_REQUEST
             };
@@ -119,7 +311,101 @@ pub async fn delete_product(
     }
 }
 
-// GET /products/search?q=name
+// POST /admin/products/import (multipart: file, format, dry_run)
+// The format defaults to the file's extension
+pub async fn import_products(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let bad_request = |message: String| {
+        (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(message))).into_response()
+    };
+
+    let mut file: Option<(Vec<u8>, Option<SheetFormat>)> = None;
+    let mut format: Option<SheetFormat> = None;
+    let mut dry_run = false;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return bad_request(format!("Invalid multipart body: {}", e)),
+        };
+
+        match field.name().unwrap_or_default() {
+            "file" => {
+                let from_name = field.file_name().and_then(SheetFormat::from_filename);
+                match field.bytes().await {
+                    Ok(bytes) => file = Some((bytes.to_vec(), from_name)),
+                    Err(e) => return bad_request(format!("Failed to read file: {}", e)),
+                }
+            }
+            "format" => {
+                let text = field.text().await.unwrap_or_default();
+                match text.parse::<SheetFormat>() {
+                    Ok(parsed) => format = Some(parsed),
+                    Err(e) => return bad_request(e.to_string()),
+                }
+            }
+            "dry_run" => {
+                let text = field.text().await.unwrap_or_default();
+                dry_run = matches!(text.trim(), "true" | "1" | "yes");
+            }
+            _ => {}
+        }
+    }
+
+    let Some((data, from_name)) = file else {
+        return bad_request("file is required".to_string());
+    };
+    let Some(format) = format.or(from_name) else {
+        return bad_request(
+            "format is required for files without a .csv or .xlsx name".to_string(),
+        );
+    };
+
+    let service = get_product_service(&state);
+    match service
+        .import_products(format, &data, dry_run, &claims.id)
+        .await
+    {
+        Ok(report) => (StatusCode::OK, Json(ProductResponse::new(report))).into_response(),
+        Err(e) => bad_request(e.to_string()),
+    }
+}
+
+// GET /admin/products/export?format=csv|xlsx
+pub async fn export_products(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Query(query): Query<ExportQuery>,
+) -> impl IntoResponse {
+    let service = get_product_service(&state);
+    let format = query.format.unwrap_or(SheetFormat::Csv);
+
+    match service.export_products(format).await {
+        Ok(data) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, format.content_type().to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"products.{}\"", format.extension()),
+                ),
+            ],
+            data,
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e.to_string())),
+        )
+            .into_response(),
+    }
+}
+
+// GET /products/search?q=text
 pub async fn search_products(
     State(state): State<ApiState>,
     Query(query): Query<SearchQuery>,
@@ -130,7 +416,15 @@ pub async fn search_products(
     let page_size = query.page_size.unwrap_or(10).clamp(1, 100);
 
     match service.search_products(&query.q, page, page_size).await {
-        Ok(products) => (StatusCode::OK, Json(ProductResponse::new(products))).into_response(),
+        Ok(results) => {
+            // Only first pages count, so paging through results isn't recorded as new searches
+            if page == 1 {
+                let search = search_handler::get_service(&state);
+                let (q, total) = (query.q.clone(), results.total);
+                tokio::spawn(async move { search.record_search(&q, total).await });
+            }
+            (StatusCode::OK, Json(ProductResponse::new(results))).into_response()
+        }
         Err(e) => (
             StatusCode::BAD_REQUEST,
             Json(ErrorResponse::new(e.to_string())),
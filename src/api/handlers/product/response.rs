@@ -1,9 +1,56 @@
+use bigdecimal::BigDecimal;
 use serde::{Deserialize, Serialize};
 
+use crate::core::product::sheet::SheetFormat;
+use crate::core::product::{ProductFilter, ProductKind, ProductSort, ProductStatus};
+
 #[derive(Debug, Deserialize)]
 pub struct ProductQuery {
     pub page: Option<u32>,
     pub page_size: Option<u32>,
+    pub category: Option<String>,
+    pub status: Option<ProductStatus>,
+    pub kind: Option<ProductKind>,
+    pub min_price: Option<BigDecimal>,
+    pub max_price: Option<BigDecimal>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub sort: Option<ProductSort>,
+}
+
+impl ProductQuery {
+    pub fn filter(&self) -> ProductFilter {
+        // Blank parameters (e.g. `?category=`) mean no filter
+        let text = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(ToString::to_string)
+        };
+
+        ProductFilter {
+            category: text(&self.category),
+            status: self.status.clone(),
+            kind: self.kind,
+            min_price: self.min_price.clone(),
+            max_price: self.max_price.clone(),
+            size: text(&self.size),
+            color: text(&self.color),
+            sort: self.sort.unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ArchivedQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    pub format: Option<SheetFormat>,
 }
 
 #[derive(Debug, Deserialize)]
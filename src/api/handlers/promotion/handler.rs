@@ -0,0 +1,95 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::AdminClaims;
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::promotion::{
+    diesel::DieselPromotionRepository, entity::PromotionRequest, service::PromotionService,
+};
+use crate::utils::errors::{Error, ErrorCode};
+
+fn get_service(state: &ApiState) -> PromotionService {
+    let repo = Arc::new(DieselPromotionRepository::new(state.pool.clone()));
+    PromotionService::new(repo)
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        ErrorCode::ResourceAlreadyExists => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+// GET /admin/promotions
+pub async fn list_promotions(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.list_promotions().await {
+        Ok(promotions) => (StatusCode::OK, Json(ApiResponse::ok(promotions))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/promotions
+pub async fn create_promotion(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Json(req): Json<PromotionRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.create_promotion(req).await {
+        Ok(promotion) => (StatusCode::CREATED, Json(ApiResponse::ok(promotion))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /admin/promotions/:id
+pub async fn get_promotion(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(promotion_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.get_promotion(promotion_id).await {
+        Ok(promotion) => (StatusCode::OK, Json(ApiResponse::ok(promotion))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// PUT /admin/promotions/:id
+pub async fn update_promotion(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(promotion_id): Path<i64>,
+    Json(req): Json<PromotionRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.update_promotion(promotion_id, req).await {
+        Ok(promotion) => (StatusCode::OK, Json(ApiResponse::ok(promotion))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// DELETE /admin/promotions/:id
+pub async fn deactivate_promotion(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(promotion_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.deactivate_promotion(promotion_id).await {
+        Ok(promotion) => (StatusCode::OK, Json(ApiResponse::ok(promotion))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
@@ -0,0 +1,120 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::{AdminClaims, Claims};
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::question::{
+    diesel::DieselQuestionRepository,
+    entity::{AnswerRequest, QuestionQuery, QuestionRequest},
+    service::QuestionService,
+};
+use crate::utils::errors::{Error, ErrorCode};
+
+fn get_service(state: &ApiState) -> QuestionService {
+    let repo = Arc::new(DieselQuestionRepository::new(state.pool.clone()));
+    QuestionService::new(repo)
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+// GET /products/:id/questions?page=&page_size=
+pub async fn list_questions(
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+    Query(query): Query<QuestionQuery>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(10).clamp(1, 100);
+
+    match service.list_questions(product_id, page, page_size).await {
+        Ok(questions) => (StatusCode::OK, Json(ApiResponse::ok(questions))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /products/:id/questions
+pub async fn ask_question(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+    Json(req): Json<QuestionRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service
+        .ask_question(product_id, claims.user_id, &req.body)
+        .await
+    {
+        Ok(question) => (StatusCode::CREATED, Json(ApiResponse::ok(question))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /admin/questions
+pub async fn unanswered_questions(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.unanswered_questions().await {
+        Ok(questions) => (StatusCode::OK, Json(ApiResponse::ok(questions))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// PUT /admin/questions/:id/answer
+pub async fn answer_question(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    Path(question_id): Path<i64>,
+    Json(req): Json<AnswerRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service
+        .answer_question(question_id, &req.answer, &claims.id)
+        .await
+    {
+        Ok(question) => (StatusCode::OK, Json(ApiResponse::ok(question))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/questions/:id/hide
+pub async fn hide_question(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    Path(question_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.hide_question(question_id, &claims.id).await {
+        Ok(question) => (StatusCode::OK, Json(ApiResponse::ok(question))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/questions/:id/restore
+pub async fn restore_question(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(question_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.restore_question(question_id).await {
+        Ok(question) => (StatusCode::OK, Json(ApiResponse::ok(question))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
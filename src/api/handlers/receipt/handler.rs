@@ -0,0 +1,66 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::Claims;
+use crate::api::response::ApiError;
+use crate::core::receipt::{
+    diesel::DieselReceiptRepository,
+    entity::{ReceiptQuery, ShopDetails},
+    service::ReceiptService,
+};
+use crate::core::user::entity::Role;
+use crate::utils::errors::{Error, ErrorCode};
+
+fn get_service(state: &ApiState) -> ReceiptService {
+    let repo = Arc::new(DieselReceiptRepository::new(state.pool.clone()));
+    let shop = ShopDetails {
+        name: state.config.shop_name.clone(),
+        address: state.config.shop_address.clone(),
+        tax_id: state.config.shop_tax_id.clone(),
+    };
+    ReceiptService::new(repo, shop, state.config.receipt_font_path.clone())
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+// GET /orders/:id/receipt.pdf?type=receipt|tax_invoice&buyer_name=&tax_id=&address=
+pub async fn get_receipt_pdf(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(order_id): Path<i64>,
+    Query(query): Query<ReceiptQuery>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    let is_admin = claims.role == Role::Admin;
+    match service
+        .render_receipt(order_id, claims.user_id, is_admin, query)
+        .await
+    {
+        Ok((receipt, pdf)) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("inline; filename=\"{}.pdf\"", receipt.receipt_number),
+                ),
+            ],
+            pdf,
+        )
+            .into_response(),
+        Err(err) => error_response(&err),
+    }
+}
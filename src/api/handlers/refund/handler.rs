@@ -0,0 +1,142 @@
+use axum::{
+    Json,
+    extract::{Multipart, Path, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::{AdminClaims, Claims};
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::notification::{
+    diesel::DieselNotificationRepository, service::NotificationService,
+};
+use crate::core::refund::{
+    diesel::DieselRefundRepository, entity::IssueRefundRequest, service::RefundService,
+};
+use crate::core::user::entity::Role;
+use crate::utils::errors::{Error, ErrorCode};
+
+fn get_service(state: &ApiState) -> RefundService {
+    let repo = Arc::new(DieselRefundRepository::new(state.pool.clone()));
+    let notifications = NotificationService::new(Arc::new(DieselNotificationRepository::new(
+        state.pool.clone(),
+    )));
+    RefundService::new(
+        repo,
+        state.storage_service.clone(),
+        notifications,
+        state.payment_provider.clone(),
+    )
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+// POST /admin/payments/:id/refunds
+pub async fn issue_refund(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    Path(payment_id): Path<i64>,
+    Json(req): Json<IssueRefundRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.issue_refund(payment_id, req, &claims.id).await {
+        Ok(refund) => (StatusCode::CREATED, Json(ApiResponse::ok(refund))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /admin/payments/:id/refunds
+pub async fn list_refunds(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(payment_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.list_refunds(payment_id).await {
+        Ok(refunds) => (StatusCode::OK, Json(ApiResponse::ok(refunds))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/refunds/:id/evidence (multipart: slip)
+pub async fn upload_evidence(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(refund_id): Path<i64>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let mut slip: Option<(Vec<u8>, String)> = None;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiError::new(format!("Invalid multipart body: {}", e))),
+                )
+                    .into_response();
+            }
+        };
+
+        if field.name() == Some("slip") {
+            let filename = field.file_name().unwrap_or("slip").to_string();
+            match field.bytes().await {
+                Ok(bytes) => slip = Some((bytes.to_vec(), filename)),
+                Err(e) => {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(ApiError::new(format!("Failed to read file: {}", e))),
+                    )
+                        .into_response();
+                }
+            }
+        }
+    }
+
+    let Some((data, filename)) = slip else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::new("slip is required")),
+        )
+            .into_response();
+    };
+
+    let service = get_service(&state);
+    match Box::pin(service.upload_evidence(refund_id, data, &filename)).await {
+        Ok(refund) => (StatusCode::OK, Json(ApiResponse::ok(refund))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /refunds/:id/evidence
+pub async fn get_evidence(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(refund_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    let is_admin = claims.role == Role::Admin;
+    match service.evidence(refund_id, claims.user_id, is_admin).await {
+        Ok((slip, content_type)) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, content_type),
+                (header::CACHE_CONTROL, "private, no-store".to_string()),
+            ],
+            slip,
+        )
+            .into_response(),
+        Err(err) => error_response(&err),
+    }
+}
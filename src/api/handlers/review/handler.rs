@@ -0,0 +1,164 @@
+use axum::{
+    Json,
+    extract::{Multipart, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::{AdminClaims, Claims};
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::review::{
+    diesel::DieselReviewRepository,
+    entity::{ReviewListQuery, ReviewModerationQuery, ReviewStatus, ReviewSubmission},
+    service::ReviewService,
+};
+use crate::utils::errors::{Error, ErrorCode};
+
+fn get_service(state: &ApiState) -> ReviewService {
+    let repo = Arc::new(DieselReviewRepository::new(state.pool.clone()));
+    ReviewService::new(repo, state.storage_service.clone())
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        ErrorCode::ResourceAlreadyExists => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+fn bad_request(message: impl Into<String>) -> axum::response::Response {
+    (StatusCode::BAD_REQUEST, Json(ApiError::new(message))).into_response()
+}
+
+// GET /products/:id/reviews?page=&page_size=
+pub async fn list_reviews(
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+    Query(query): Query<ReviewListQuery>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(10);
+
+    match service.list_reviews(product_id, page, page_size).await {
+        Ok(reviews) => (StatusCode::OK, Json(ApiResponse::ok(reviews))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /products/:id/reviews (multipart: rating, body, photos)
+pub async fn submit_review(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let mut rating: Option<i16> = None;
+    let mut body: Option<String> = None;
+    let mut photos: Vec<(Vec<u8>, String)> = Vec::new();
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return bad_request(format!("Invalid multipart body: {}", e)),
+        };
+
+        match field.name().unwrap_or_default() {
+            "rating" => match field.text().await {
+                Ok(text) => match text.trim().parse() {
+                    Ok(value) => rating = Some(value),
+                    Err(_) => return bad_request("rating must be a whole number"),
+                },
+                Err(e) => return bad_request(format!("Failed to read rating: {}", e)),
+            },
+            "body" => match field.text().await {
+                Ok(text) => body = Some(text),
+                Err(e) => return bad_request(format!("Failed to read body: {}", e)),
+            },
+            "photos" => {
+                let filename = field.file_name().unwrap_or("photo").to_string();
+                match field.bytes().await {
+                    Ok(bytes) => photos.push((bytes.to_vec(), filename)),
+                    Err(e) => return bad_request(format!("Failed to read file: {}", e)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Some(rating) = rating else {
+        return bad_request("rating is required");
+    };
+
+    let service = get_service(&state);
+    let submission = ReviewSubmission {
+        rating,
+        body,
+        photos,
+    };
+
+    match Box::pin(service.submit_review(product_id, claims.user_id, submission)).await {
+        Ok(review) => (StatusCode::CREATED, Json(ApiResponse::ok(review))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// DELETE /products/:id/reviews/mine
+pub async fn delete_review(
+    claims: Claims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.delete_review(product_id, claims.user_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// GET /admin/reviews?status=
+pub async fn list_for_moderation(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Query(query): Query<ReviewModerationQuery>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    let status = query.status.unwrap_or(ReviewStatus::Pending);
+    match service.list_for_moderation(status).await {
+        Ok(reviews) => (StatusCode::OK, Json(ApiResponse::ok(reviews))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/reviews/:id/approve
+pub async fn approve_review(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    Path(review_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.approve_review(review_id, &claims.id).await {
+        Ok(review) => (StatusCode::OK, Json(ApiResponse::ok(review))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /admin/reviews/:id/hide
+pub async fn hide_review(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    Path(review_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.hide_review(review_id, &claims.id).await {
+        Ok(review) => (StatusCode::OK, Json(ApiResponse::ok(review))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
@@ -0,0 +1,56 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::AdminClaims;
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::search::{
+    diesel::DieselSearchRepository,
+    entity::{SuggestQuery, ZeroResultReportQuery},
+    service::SearchService,
+};
+use crate::utils::errors::{Error, ErrorCode};
+
+pub(crate) fn get_service(state: &ApiState) -> SearchService {
+    let repo = Arc::new(DieselSearchRepository::new(state.pool.clone()));
+    SearchService::new(repo, state.suggestions.clone())
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+// GET /products/suggest?q=
+pub async fn suggest(
+    State(state): State<ApiState>,
+    Query(query): Query<SuggestQuery>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    let limit = query.limit.unwrap_or(5).clamp(1, 10);
+    let suggestions = service.suggest(&query.q, limit);
+    (StatusCode::OK, Json(ApiResponse::ok(suggestions))).into_response()
+}
+
+// GET /admin/search/zero-results
+pub async fn zero_result_report(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Query(query): Query<ZeroResultReportQuery>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    match service.zero_result_report(limit).await {
+        Ok(report) => (StatusCode::OK, Json(ApiResponse::ok(report))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
@@ -0,0 +1,99 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::api::ApiState;
+use crate::api::guards::guard::AdminClaims;
+use crate::api::response::{ApiError, ApiResponse};
+use crate::core::variant::{
+    diesel::DieselVariantRepository,
+    entity::{GenerateMatrixRequest, NewVariant, UpdateVariant},
+    service::VariantService,
+};
+use crate::utils::errors::{Error, ErrorCode};
+
+fn get_service(state: &ApiState) -> VariantService {
+    let repo = Arc::new(DieselVariantRepository::new(state.pool.clone()));
+    VariantService::new(repo)
+}
+
+fn error_response(err: &Error) -> axum::response::Response {
+    let status = match err.code {
+        ErrorCode::ResourceNotFound => StatusCode::NOT_FOUND,
+        ErrorCode::ValidationError => StatusCode::BAD_REQUEST,
+        ErrorCode::ResourceAlreadyExists => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ApiError::new(err.to_string()))).into_response()
+}
+
+// GET /products/:id/variants
+pub async fn list_variants(
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.list_variants(product_id).await {
+        Ok(variants) => (StatusCode::OK, Json(ApiResponse::ok(variants))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /products/:id/variants
+pub async fn create_variant(
+    AdminClaims(claims): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+    Json(req): Json<NewVariant>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.create_variant(product_id, req, &claims.id).await {
+        Ok(variant) => (StatusCode::CREATED, Json(ApiResponse::ok(variant))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// POST /products/:id/variants/matrix
+pub async fn generate_matrix(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path(product_id): Path<i64>,
+    Json(req): Json<GenerateMatrixRequest>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.generate_matrix(product_id, req).await {
+        Ok(matrix) => (StatusCode::CREATED, Json(ApiResponse::ok(matrix))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// PUT /products/:id/variants/:variant_id
+pub async fn update_variant(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path((product_id, variant_id)): Path<(i64, i64)>,
+    Json(req): Json<UpdateVariant>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.update_variant(product_id, variant_id, req).await {
+        Ok(variant) => (StatusCode::OK, Json(ApiResponse::ok(variant))).into_response(),
+        Err(err) => error_response(&err),
+    }
+}
+
+// DELETE /products/:id/variants/:variant_id
+pub async fn delete_variant(
+    AdminClaims(_): AdminClaims,
+    State(state): State<ApiState>,
+    Path((product_id, variant_id)): Path<(i64, i64)>,
+) -> impl IntoResponse {
+    let service = get_service(&state);
+    match service.delete_variant(product_id, variant_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => error_response(&err),
+    }
+}
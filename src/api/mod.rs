@@ -13,9 +13,20 @@ pub mod response;
 use crate::api::errors::handle_404;
 use crate::api::fairings::cors;
 use crate::api::handlers::{
-    cart::handler as cart_handler, favorite::handler as favorite_handler, health,
-    product::handler as product_handler, upload, user::handler as user_handler,
+    affiliation::handler as affiliation_handler, bundle::handler as bundle_handler,
+    cart::handler as cart_handler, category::handler as category_handler,
+    favorite::handler as favorite_handler, health, inventory::handler as inventory_handler,
+    notification::handler as notification_handler, order::handler as order_handler,
+    payment::handler as payment_handler, preorder::handler as preorder_handler,
+    product::handler as product_handler, promotion::handler as promotion_handler,
+    question::handler as question_handler, receipt::handler as receipt_handler,
+    refund::handler as refund_handler, review::handler as review_handler,
+    search::handler as search_handler, upload, user::handler as user_handler,
+    variant::handler as variant_handler,
 };
+use crate::config::AppConfig;
+use crate::core::payment::provider::PaymentProvider;
+use crate::core::search::cache::SuggestionCache;
 use crate::core::user::{
     repository::DieselRepo as UserRepository, service::Service as UserService,
 };
@@ -29,9 +40,18 @@ pub struct ApiState {
     pub pool: DBPool,
     pub user_service: UserService,
     pub storage_service: StorageService,
+    pub config: Arc<AppConfig>,
+    pub payment_provider: Option<Arc<dyn PaymentProvider>>,
+    pub suggestions: SuggestionCache,
 }
 
-pub fn router(pool: &DBPool, storage_service: StorageService) -> Router {
+pub fn router(
+    pool: &DBPool,
+    storage_service: StorageService,
+    config: Arc<AppConfig>,
+    payment_provider: Option<Arc<dyn PaymentProvider>>,
+    suggestions: SuggestionCache,
+) -> Router {
     let user_repo = UserRepository::new(pool.clone());
     let user_service = UserService::new(Arc::new(user_repo));
 
@@ -39,20 +59,15 @@ pub fn router(pool: &DBPool, storage_service: StorageService) -> Router {
         pool: pool.clone(),
         user_service,
         storage_service,
+        config,
+        payment_provider,
+        suggestions,
     };
 
     Router::new()
         .route("/health", get(health::health))
-        .nest(
-            "/products",
-            Router::new()
-                .route("/", get(product_handler::list_products))
-                .route("/", post(product_handler::create_product))
-                .route("/search", get(product_handler::search_products))
-                .route("/:id", get(product_handler::get_product))
-                .route("/:id", put(product_handler::update_product))
-                .route("/:id", delete(product_handler::delete_product)),
-        )
+        .nest("/products", product_routes())
+        .route("/categories", get(category_handler::category_tree))
         .nest(
             "/upload",
             Router::new()
@@ -66,15 +81,212 @@ pub fn router(pool: &DBPool, storage_service: StorageService) -> Router {
                 .route("/register", post(user_handler::register))
                 .route("/login", post(user_handler::login)),
         )
+        .nest(
+            "/users",
+            Router::new()
+                .route("/me/affiliation", get(affiliation_handler::get_affiliation))
+                .route(
+                    "/me/affiliation",
+                    put(affiliation_handler::submit_affiliation),
+                ),
+        )
         .nest(
             "/cart",
-            Router::new().route("/items", put(cart_handler::add_item)),
+            Router::new()
+                .route("/", get(cart_handler::get_cart))
+                .route("/items", put(cart_handler::add_item))
+                .route("/coupon", post(cart_handler::apply_coupon))
+                .route("/coupon/:code", delete(cart_handler::remove_coupon)),
         )
         .nest(
             "/favorites",
             Router::new().route("/", put(favorite_handler::add_favorite)),
         )
+        .nest(
+            "/orders",
+            Router::new()
+                .route("/", post(order_handler::checkout))
+                .route("/:id", get(order_handler::get_order))
+                .route("/:id/promptpay", get(order_handler::get_promptpay))
+                .route("/:id/promptpay/qr", get(order_handler::get_promptpay_qr))
+                .route("/:id/receipt.pdf", get(receipt_handler::get_receipt_pdf))
+                .route("/:id/payments", get(payment_handler::list_payments))
+                .route("/:id/payments", post(payment_handler::submit_payment))
+                .route("/:id/charges", post(payment_handler::create_charge))
+                .layer(DefaultBodyLimit::max(10 * 1024 * 1024)), // 10MB limit for payment slips
+        )
+        .nest(
+            "/payments",
+            Router::new()
+                .route(
+                    "/callbacks/:provider",
+                    post(payment_handler::provider_callback),
+                )
+                .route("/:id/slip", get(payment_handler::get_slip)),
+        )
+        .nest(
+            "/refunds",
+            Router::new().route("/:id/evidence", get(refund_handler::get_evidence)),
+        )
+        .nest(
+            "/notifications",
+            Router::new().route("/", get(notification_handler::list_notifications)),
+        )
+        .nest("/admin", admin_routes())
         .with_state(state)
         .layer(cors::layer())
         .fallback(handle_404)
 }
+
+fn product_routes() -> Router<ApiState> {
+    Router::new()
+        .route("/", get(product_handler::list_products))
+        .route("/", post(product_handler::create_product))
+        .route("/search", get(product_handler::search_products))
+        .route("/suggest", get(search_handler::suggest))
+        .route("/by-slug/:slug", get(product_handler::get_product_by_slug))
+        .route("/:id", get(product_handler::get_product))
+        .route("/:id", put(product_handler::update_product))
+        .route("/:id", delete(product_handler::delete_product))
+        .route("/:id/preorder", get(preorder_handler::get_campaign))
+        .route("/:id/bundle", get(bundle_handler::get_components))
+        .route("/:id/questions", get(question_handler::list_questions))
+        .route("/:id/questions", post(question_handler::ask_question))
+        .route("/:id/reviews", get(review_handler::list_reviews))
+        // 30MB limit for review photos
+        .route(
+            "/:id/reviews",
+            post(review_handler::submit_review).layer(DefaultBodyLimit::max(30 * 1024 * 1024)),
+        )
+        .route("/:id/reviews/mine", delete(review_handler::delete_review))
+        .route("/:id/variants", get(variant_handler::list_variants))
+        .route("/:id/variants", post(variant_handler::create_variant))
+        .route(
+            "/:id/variants/matrix",
+            post(variant_handler::generate_matrix),
+        )
+        .route(
+            "/:id/variants/:variant_id",
+            put(variant_handler::update_variant),
+        )
+        .route(
+            "/:id/variants/:variant_id",
+            delete(variant_handler::delete_variant),
+        )
+}
+
+fn admin_routes() -> Router<ApiState> {
+    Router::new()
+        .nest(
+            "/categories",
+            Router::new()
+                .route("/", post(category_handler::create_category))
+                .route("/:id", put(category_handler::update_category))
+                .route("/:id", delete(category_handler::delete_category)),
+        )
+        .nest(
+            "/inventory",
+            Router::new()
+                .route(
+                    "/variants/:id/movements",
+                    get(inventory_handler::list_movements),
+                )
+                .route(
+                    "/variants/:id/movements",
+                    post(inventory_handler::adjust_stock),
+                )
+                .route("/reconciliation", get(inventory_handler::reconcile)),
+        )
+        .nest(
+            "/payments",
+            Router::new()
+                .route("/", get(payment_handler::review_queue))
+                .route("/refunds-due", get(payment_handler::refunds_due))
+                .route("/statements", post(payment_handler::import_statement))
+                .route("/:id/verify", post(payment_handler::verify_payment))
+                .route("/:id/reject", post(payment_handler::reject_payment))
+                .route("/:id/sync", post(payment_handler::sync_payment))
+                .route("/:id/refunds", get(refund_handler::list_refunds))
+                .route("/:id/refunds", post(refund_handler::issue_refund)),
+        )
+        .nest(
+            "/refunds",
+            Router::new()
+                .route("/:id/evidence", post(refund_handler::upload_evidence))
+                .layer(DefaultBodyLimit::max(10 * 1024 * 1024)), // 10MB limit for refund slips
+        )
+        .nest(
+            "/preorders",
+            Router::new()
+                .route("/:product_id", put(preorder_handler::save_campaign))
+                .route("/:product_id", delete(preorder_handler::remove_campaign))
+                .route("/:product_id/report", get(preorder_handler::report)),
+        )
+        .nest("/products", admin_product_routes())
+        .nest(
+            "/promotions",
+            Router::new()
+                .route("/", get(promotion_handler::list_promotions))
+                .route("/", post(promotion_handler::create_promotion))
+                .route("/:id", get(promotion_handler::get_promotion))
+                .route("/:id", put(promotion_handler::update_promotion))
+                .route("/:id", delete(promotion_handler::deactivate_promotion)),
+        )
+        .nest(
+            "/search",
+            Router::new().route("/zero-results", get(search_handler::zero_result_report)),
+        )
+        .nest(
+            "/affiliations",
+            Router::new()
+                .route("/", get(affiliation_handler::list_affiliations))
+                .route(
+                    "/:user_id/verify",
+                    post(affiliation_handler::verify_affiliation),
+                )
+                .route(
+                    "/:user_id/reject",
+                    post(affiliation_handler::reject_affiliation),
+                ),
+        )
+        .nest(
+            "/reviews",
+            Router::new()
+                .route("/", get(review_handler::list_for_moderation))
+                .route("/:id/approve", post(review_handler::approve_review))
+                .route("/:id/hide", post(review_handler::hide_review)),
+        )
+        .nest(
+            "/questions",
+            Router::new()
+                .route("/", get(question_handler::unanswered_questions))
+                .route("/:id/answer", put(question_handler::answer_question))
+                .route("/:id/hide", post(question_handler::hide_question))
+                .route("/:id/restore", post(question_handler::restore_question)),
+        )
+}
+
+fn admin_product_routes() -> Router<ApiState> {
+    Router::new()
+        .route("/archived", get(product_handler::list_archived_products))
+        // 10MB limit for sheets
+        .route(
+            "/import",
+            post(product_handler::import_products).layer(DefaultBodyLimit::max(10 * 1024 * 1024)),
+        )
+        .route("/export", get(product_handler::export_products))
+        .route("/:id/preview", get(product_handler::preview_product))
+        .route(
+            "/:id/visibility",
+            put(product_handler::set_product_schedule),
+        )
+        .route("/:id/pricing", put(product_handler::set_product_pricing))
+        .route(
+            "/:id/price-history",
+            get(product_handler::get_price_history),
+        )
+        .route("/:id/bundle", put(bundle_handler::set_components))
+        .route("/:id/bundle", delete(bundle_handler::remove_bundle))
+        .route("/:id/restore", post(product_handler::restore_product))
+        .route("/:id", delete(product_handler::purge_product))
+}
@@ -1,9 +1,30 @@
 use std::env;
+use std::str::FromStr;
 
+use bigdecimal::{BigDecimal, Zero};
+
+#[derive(Clone)]
 pub struct AppConfig {
     pub server_addr: String,
     pub database_url: String,
     pub gcs_bucket_name: String,
+    pub gcs_private_bucket_name: String,
+    pub order_payment_timeout_minutes: i64,
+    pub order_expiry_interval_secs: u64,
+    pub promptpay_id: Option<String>,
+    pub public_base_url: String,
+    pub payment_provider: Option<String>,
+    /// Required when `payment_provider` is `mock`
+    pub mock_payment_secret: Option<String>,
+    pub mock_payment_outcome: String,
+    pub mock_payment_delay_secs: u64,
+    pub shop_name: String,
+    pub shop_address: Option<String>,
+    pub shop_tax_id: Option<String>,
+    pub receipt_font_path: String,
+    pub suggestion_refresh_secs: u64,
+    pub product_schedule_interval_secs: u64,
+    pub shipping_fee: BigDecimal,
 }
 
 impl AppConfig {
@@ -13,11 +34,79 @@ impl AppConfig {
             .map_err(|_| anyhow::anyhow!("Missing env var DATABASE_URL"))?;
         let gcs_bucket_name = env::var("GCS_BUCKET_NAME")
             .map_err(|_| anyhow::anyhow!("Missing env var GCS_BUCKET_NAME"))?;
+        let gcs_private_bucket_name = env::var("GCS_PRIVATE_BUCKET_NAME")
+            .map_err(|_| anyhow::anyhow!("Missing env var GCS_PRIVATE_BUCKET_NAME"))?;
+        let order_payment_timeout_minutes = env::var("ORDER_PAYMENT_TIMEOUT_MINUTES")
+            .unwrap_or_else(|_| "1440".to_string())
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid env var ORDER_PAYMENT_TIMEOUT_MINUTES"))?;
+        let order_expiry_interval_secs = env::var("ORDER_EXPIRY_INTERVAL_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid env var ORDER_EXPIRY_INTERVAL_SECS"))?;
+        let promptpay_id = env::var("PROMPTPAY_ID").ok();
+        let public_base_url = env::var("PUBLIC_BASE_URL")
+            .unwrap_or_else(|_| "http://localhost:8080".to_string())
+            .trim_end_matches('/')
+            .to_string();
+        let payment_provider = env::var("PAYMENT_PROVIDER")
+            .ok()
+            .filter(|value| !value.trim().is_empty());
+        let mock_payment_secret = env::var("MOCK_PAYMENT_SECRET")
+            .ok()
+            .filter(|value| !value.trim().is_empty());
+        if payment_provider.as_deref().map(str::trim) == Some("mock")
+            && mock_payment_secret.is_none()
+        {
+            return Err(anyhow::anyhow!(
+                "Missing env var MOCK_PAYMENT_SECRET, required when PAYMENT_PROVIDER=mock"
+            ));
+        }
+        let mock_payment_outcome =
+            env::var("MOCK_PAYMENT_OUTCOME").unwrap_or_else(|_| "success".to_string());
+        let mock_payment_delay_secs = env::var("MOCK_PAYMENT_DELAY_SECS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid env var MOCK_PAYMENT_DELAY_SECS"))?;
+        let shop_name = env::var("SHOP_NAME").unwrap_or_else(|_| "Intania Shop".to_string());
+        let shop_address = env::var("SHOP_ADDRESS").ok();
+        let shop_tax_id = env::var("SHOP_TAX_ID").ok();
+        let receipt_font_path = env::var("RECEIPT_FONT_PATH")
+            .unwrap_or_else(|_| "assets/fonts/Sarabun-Regular.ttf".to_string());
+        let suggestion_refresh_secs = env::var("SUGGESTION_REFRESH_SECS")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid env var SUGGESTION_REFRESH_SECS"))?;
+        let product_schedule_interval_secs = env::var("PRODUCT_SCHEDULE_INTERVAL_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Invalid env var PRODUCT_SCHEDULE_INTERVAL_SECS"))?;
+        let shipping_fee =
+            BigDecimal::from_str(&env::var("SHIPPING_FEE").unwrap_or_else(|_| "0".to_string()))
+                .ok()
+                .filter(|fee| *fee >= BigDecimal::zero())
+                .ok_or_else(|| anyhow::anyhow!("Invalid env var SHIPPING_FEE"))?;
 
         Ok(Self {
             server_addr,
             database_url,
             gcs_bucket_name,
+            gcs_private_bucket_name,
+            order_payment_timeout_minutes,
+            order_expiry_interval_secs,
+            promptpay_id,
+            public_base_url,
+            payment_provider,
+            mock_payment_secret,
+            mock_payment_outcome,
+            mock_payment_delay_secs,
+            shop_name,
+            shop_address,
+            shop_tax_id,
+            receipt_font_path,
+            suggestion_refresh_secs,
+            product_schedule_interval_secs,
+            shipping_fee,
         })
     }
 }
@@ -0,0 +1,175 @@
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::DatabaseErrorKind;
+
+use crate::schema::student_affiliations;
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{AffiliationStatus, StudentAffiliation};
+use super::repository::AffiliationRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = student_affiliations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct AffiliationModel {
+    pub user_id: i64,
+    pub student_id: String,
+    pub status: AffiliationStatus,
+    pub note: Option<String>,
+    pub submitted_at: NaiveDateTime,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<NaiveDateTime>,
+}
+
+impl From<AffiliationModel> for StudentAffiliation {
+    fn from(model: AffiliationModel) -> Self {
+        StudentAffiliation {
+            user_id: model.user_id,
+            student_id: model.student_id,
+            status: model.status,
+            note: model.note,
+            submitted_at: model.submitted_at,
+            reviewed_by: model.reviewed_by,
+            reviewed_at: model.reviewed_at,
+        }
+    }
+}
+
+fn write_error(e: diesel::result::Error) -> Error {
+    match e {
+        diesel::result::Error::NotFound => {
+            Error::with_message(ErrorCode::ResourceNotFound, "No student affiliation found")
+        }
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+            Error::with_message(
+                ErrorCode::ResourceAlreadyExists,
+                "This student ID is already linked to another account",
+            )
+        }
+        _ => e.into(),
+    }
+}
+
+/// Whether the user has a verified student affiliation and so pays member prices
+pub fn is_verified_member(conn: &mut PgConnection, user_id: i64) -> Result<bool, Error> {
+    let verified: i64 = student_affiliations::table
+        .filter(student_affiliations::user_id.eq(user_id))
+        .filter(student_affiliations::status.eq(AffiliationStatus::Verified))
+        .count()
+        .get_result(conn)?;
+
+    Ok(verified > 0)
+}
+
+pub struct DieselAffiliationRepository {
+    pool: DBPool,
+}
+
+impl DieselAffiliationRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AffiliationRepository for DieselAffiliationRepository {
+    async fn find(&self, user_id: i64) -> Result<StudentAffiliation, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let model: AffiliationModel = student_affiliations::table
+            .filter(student_affiliations::user_id.eq(user_id))
+            .select(AffiliationModel::as_select())
+            .first(&mut conn)
+            .map_err(write_error)?;
+
+        Ok(model.into())
+    }
+
+    async fn submit(&self, user_id: i64, student_id: &str) -> Result<StudentAffiliation, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction(|conn| {
+            let existing: Option<AffiliationModel> = student_affiliations::table
+                .filter(student_affiliations::user_id.eq(user_id))
+                .select(AffiliationModel::as_select())
+                .for_update()
+                .first(conn)
+                .optional()?;
+
+            let model: AffiliationModel = match existing {
+                Some(existing) if existing.student_id == student_id => existing,
+                Some(_) => diesel::update(
+                    student_affiliations::table.filter(student_affiliations::user_id.eq(user_id)),
+                )
+                .set((
+                    student_affiliations::student_id.eq(student_id),
+                    student_affiliations::status.eq(AffiliationStatus::Pending),
+                    student_affiliations::note.eq(None::<String>),
+                    student_affiliations::submitted_at.eq(Utc::now().naive_utc()),
+                    student_affiliations::reviewed_by.eq(None::<String>),
+                    student_affiliations::reviewed_at.eq(None::<NaiveDateTime>),
+                ))
+                .returning(AffiliationModel::as_returning())
+                .get_result(conn)
+                .map_err(write_error)?,
+                None => diesel::insert_into(student_affiliations::table)
+                    .values((
+                        student_affiliations::user_id.eq(user_id),
+                        student_affiliations::student_id.eq(student_id),
+                    ))
+                    .returning(AffiliationModel::as_returning())
+                    .get_result(conn)
+                    .map_err(write_error)?,
+            };
+
+            Ok(model.into())
+        })
+    }
+
+    async fn list(&self, status: AffiliationStatus) -> Result<Vec<StudentAffiliation>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let models: Vec<AffiliationModel> = student_affiliations::table
+            .filter(student_affiliations::status.eq(status))
+            .select(AffiliationModel::as_select())
+            .order(student_affiliations::submitted_at.asc())
+            .load(&mut conn)?;
+
+        Ok(models.into_iter().map(Into::into).collect())
+    }
+
+    async fn review(
+        &self,
+        user_id: i64,
+        status: AffiliationStatus,
+        reviewed_by: &str,
+        note: Option<&str>,
+    ) -> Result<StudentAffiliation, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let model: AffiliationModel = diesel::update(
+            student_affiliations::table.filter(student_affiliations::user_id.eq(user_id)),
+        )
+        .set((
+            student_affiliations::status.eq(status),
+            student_affiliations::note.eq(note),
+            student_affiliations::reviewed_by.eq(Some(reviewed_by)),
+            student_affiliations::reviewed_at.eq(Some(Utc::now().naive_utc())),
+        ))
+        .returning(AffiliationModel::as_returning())
+        .get_result(&mut conn)
+        .map_err(write_error)?;
+
+        Ok(model.into())
+    }
+}
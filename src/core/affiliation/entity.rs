@@ -0,0 +1,43 @@
+use chrono::NaiveDateTime;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::AffiliationStatus"]
+pub enum AffiliationStatus {
+    #[db_rename = "PENDING"]
+    Pending,
+    #[db_rename = "VERIFIED"]
+    Verified,
+    #[db_rename = "REJECTED"]
+    Rejected,
+}
+
+/// A customer's claim to be an Intania student. Once an admin verifies it, the customer pays
+/// member prices.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StudentAffiliation {
+    pub user_id: i64,
+    pub student_id: String,
+    pub status: AffiliationStatus,
+    /// Why the claim was rejected
+    pub note: Option<String>,
+    pub submitted_at: NaiveDateTime,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AffiliationRequest {
+    pub student_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RejectAffiliationRequest {
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AffiliationQuery {
+    pub status: Option<AffiliationStatus>,
+}
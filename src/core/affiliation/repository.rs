@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+
+use crate::utils::errors::Error;
+
+use super::entity::{AffiliationStatus, StudentAffiliation};
+
+#[async_trait]
+pub trait AffiliationRepository: Send + Sync {
+    async fn find(&self, user_id: i64) -> Result<StudentAffiliation, Error>;
+    /// Records the claim for review. A different student ID than the one on file starts the
+    /// review over; the same one leaves the claim as it is.
+    async fn submit(&self, user_id: i64, student_id: &str) -> Result<StudentAffiliation, Error>;
+    /// Oldest claims first
+    async fn list(&self, status: AffiliationStatus) -> Result<Vec<StudentAffiliation>, Error>;
+    async fn review(
+        &self,
+        user_id: i64,
+        status: AffiliationStatus,
+        reviewed_by: &str,
+        note: Option<&str>,
+    ) -> Result<StudentAffiliation, Error>;
+}
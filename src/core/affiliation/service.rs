@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{AffiliationStatus, StudentAffiliation};
+use super::repository::AffiliationRepository;
+
+// Chulalongkorn student IDs are ten digits
+const STUDENT_ID_LEN: usize = 10;
+
+#[derive(Clone)]
+pub struct AffiliationService {
+    repo: Arc<dyn AffiliationRepository>,
+}
+
+impl AffiliationService {
+    pub fn new(repo: Arc<dyn AffiliationRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn get_affiliation(&self, user_id: i64) -> Result<StudentAffiliation, Error> {
+        self.repo.find(user_id).await
+    }
+
+    pub async fn submit_affiliation(
+        &self,
+        user_id: i64,
+        student_id: &str,
+    ) -> Result<StudentAffiliation, Error> {
+        let student_id = student_id.trim();
+        if student_id.len() != STUDENT_ID_LEN || !student_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Student ID must be {} digits", STUDENT_ID_LEN),
+            ));
+        }
+
+        self.repo.submit(user_id, student_id).await
+    }
+
+    pub async fn list_affiliations(
+        &self,
+        status: AffiliationStatus,
+    ) -> Result<Vec<StudentAffiliation>, Error> {
+        self.repo.list(status).await
+    }
+
+    pub async fn verify_affiliation(
+        &self,
+        user_id: i64,
+        reviewed_by: &str,
+    ) -> Result<StudentAffiliation, Error> {
+        self.repo
+            .review(user_id, AffiliationStatus::Verified, reviewed_by, None)
+            .await
+    }
+
+    /// Rejecting a verified affiliation also ends member pricing for the customer
+    pub async fn reject_affiliation(
+        &self,
+        user_id: i64,
+        reviewed_by: &str,
+        reason: &str,
+    ) -> Result<StudentAffiliation, Error> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Rejection reason is required",
+            ));
+        }
+
+        self.repo
+            .review(
+                user_id,
+                AffiliationStatus::Rejected,
+                reviewed_by,
+                Some(reason),
+            )
+            .await
+    }
+}
@@ -0,0 +1,518 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+
+use crate::core::product::diesel::VariantModel;
+use crate::core::product::entity::ProductKind;
+use crate::schema::{
+    bundle_components, cart_item_choices, order_item_components, products, variants,
+};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{
+    BundleChoice, BundleComponent, BundleComponentRequest, BundlePart, OrderItemComponent,
+};
+use super::repository::BundleRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = bundle_components)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct ComponentModel {
+    pub component_id: i64,
+    pub product_id: i64,
+    pub variant_id: Option<i64>,
+    pub quantity: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = bundle_components)]
+struct NewComponentModel {
+    pub bundle_id: i64,
+    pub product_id: i64,
+    pub variant_id: Option<i64>,
+    pub quantity: i32,
+    pub position: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = order_item_components)]
+struct NewOrderItemComponentModel {
+    pub order_item_id: i64,
+    pub variant_id: i64,
+    pub quantity: i32,
+}
+
+fn product_kind(conn: &mut PgConnection, product_id: i64) -> Result<ProductKind, Error> {
+    products::table
+        .filter(products::id.eq(product_id))
+        .select(products::kind)
+        .first(conn)
+        .optional()?
+        .ok_or_else(|| Error::with_message(ErrorCode::ResourceNotFound, "Product not found"))
+}
+
+// A bundle's components in order, each with the name of its product
+fn component_rows(
+    conn: &mut PgConnection,
+    bundle_id: i64,
+) -> Result<Vec<(ComponentModel, String)>, Error> {
+    Ok(bundle_components::table
+        .inner_join(products::table.on(products::id.eq(bundle_components::product_id)))
+        .filter(bundle_components::bundle_id.eq(bundle_id))
+        .select((ComponentModel::as_select(), products::name))
+        .order((
+            bundle_components::position.asc(),
+            bundle_components::component_id.asc(),
+        ))
+        .load(conn)?)
+}
+
+/// What a bundle is made of, with the variants that can fill each component. Empty for a
+/// product that isn't a bundle.
+pub fn load_components(
+    conn: &mut PgConnection,
+    bundle_id: i64,
+) -> Result<Vec<BundleComponent>, Error> {
+    let rows = component_rows(conn, bundle_id)?;
+    let product_ids: Vec<i64> = rows.iter().map(|(row, _)| row.product_id).collect();
+    let options: Vec<VariantModel> = variants::table
+        .filter(variants::product_id.eq_any(&product_ids))
+        .select(VariantModel::as_select())
+        .order(variants::variant_id.asc())
+        .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(row, product_name)| BundleComponent {
+            options: options
+                .iter()
+                .filter(|variant| {
+                    variant.product_id == row.product_id
+                        && row.variant_id.is_none_or(|id| id == variant.variant_id)
+                })
+                .cloned()
+                .map(Into::into)
+                .collect(),
+            component_id: row.component_id,
+            product_id: row.product_id,
+            product_name,
+            variant_id: row.variant_id,
+            quantity: row.quantity,
+        })
+        .collect())
+}
+
+/// Checks the choices made when adding a variant to the cart: one for each component of its
+/// bundle that the customer chooses, and none for other products. Returns them sorted by
+/// component.
+pub fn check_choices(
+    conn: &mut PgConnection,
+    variant_id: i64,
+    choices: &[BundleChoice],
+) -> Result<Vec<BundleChoice>, Error> {
+    let bundle_id: i64 = variants::table
+        .filter(variants::variant_id.eq(variant_id))
+        .select(variants::product_id)
+        .first(conn)?;
+    let components = load_components(conn, bundle_id)?;
+    if components.is_empty() && !choices.is_empty() {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Only bundles take choices",
+        ));
+    }
+
+    let mut checked = Vec::with_capacity(choices.len());
+    for component in &components {
+        let mut made = choices
+            .iter()
+            .filter(|choice| choice.component_id == component.component_id);
+        let choice = made.next();
+        if component.variant_id.is_some() {
+            if choice.is_some() {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    format!(
+                        "{} is fixed in this bundle and can't be chosen",
+                        component.product_name
+                    ),
+                ));
+            }
+            continue;
+        }
+
+        let Some(choice) = choice.filter(|_| made.next().is_none()) else {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Choose one variant of {}", component.product_name),
+            ));
+        };
+        if !component
+            .options
+            .iter()
+            .any(|variant| variant.variant_id == choice.variant_id)
+        {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!(
+                    "Variant {} is not a {}",
+                    choice.variant_id, component.product_name
+                ),
+            ));
+        }
+        checked.push(*choice);
+    }
+
+    if checked.len() != choices.len() {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "A choice doesn't belong to a component of this bundle",
+        ));
+    }
+    checked.sort_unstable_by_key(|choice| choice.component_id);
+    Ok(checked)
+}
+
+/// The choices saved with a cart item, sorted by component
+pub fn item_choices(conn: &mut PgConnection, item_id: i64) -> Result<Vec<BundleChoice>, Error> {
+    let rows: Vec<(i64, i64)> = cart_item_choices::table
+        .filter(cart_item_choices::item_id.eq(item_id))
+        .select((
+            cart_item_choices::component_id,
+            cart_item_choices::variant_id,
+        ))
+        .order(cart_item_choices::component_id.asc())
+        .load(conn)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(component_id, variant_id)| BundleChoice {
+            component_id,
+            variant_id,
+        })
+        .collect())
+}
+
+pub fn save_choices(
+    conn: &mut PgConnection,
+    item_id: i64,
+    choices: &[BundleChoice],
+) -> Result<(), Error> {
+    let rows: Vec<_> = choices
+        .iter()
+        .map(|choice| {
+            (
+                cart_item_choices::item_id.eq(item_id),
+                cart_item_choices::component_id.eq(choice.component_id),
+                cart_item_choices::variant_id.eq(choice.variant_id),
+            )
+        })
+        .collect();
+    if !rows.is_empty() {
+        diesel::insert_into(cart_item_choices::table)
+            .values(&rows)
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+/// The variants a cart line of a bundle takes from stock: its set variants and the ones the
+/// customer chose. `None` when a choice is missing, as after the bundle was changed.
+pub fn cart_item_parts(
+    conn: &mut PgConnection,
+    item_id: i64,
+    bundle_id: i64,
+) -> Result<Option<Vec<BundlePart>>, Error> {
+    let rows = component_rows(conn, bundle_id)?;
+    let choices: HashMap<i64, i64> = item_choices(conn, item_id)?
+        .into_iter()
+        .map(|choice| (choice.component_id, choice.variant_id))
+        .collect();
+
+    let mut chosen = Vec::with_capacity(rows.len());
+    for (row, product_name) in rows {
+        let Some(variant_id) = row
+            .variant_id
+            .or_else(|| choices.get(&row.component_id).copied())
+        else {
+            return Ok(None);
+        };
+        chosen.push((row, product_name, variant_id));
+    }
+
+    let variant_ids: Vec<i64> = chosen.iter().map(|(_, _, id)| *id).collect();
+    let options: HashMap<i64, (Option<String>, Option<String>)> = variants::table
+        .filter(variants::variant_id.eq_any(&variant_ids))
+        .select((variants::variant_id, variants::size, variants::color))
+        .load::<(i64, Option<String>, Option<String>)>(conn)?
+        .into_iter()
+        .map(|(variant_id, size, color)| (variant_id, (size, color)))
+        .collect();
+
+    Ok(Some(
+        chosen
+            .into_iter()
+            .map(|(row, product_name, variant_id)| {
+                let (size, color) = options.get(&variant_id).cloned().unwrap_or_default();
+                BundlePart {
+                    component_id: row.component_id,
+                    product_id: row.product_id,
+                    product_name,
+                    variant_id,
+                    size,
+                    color,
+                    quantity: row.quantity,
+                }
+            })
+            .collect(),
+    ))
+}
+
+/// Records the component variants a bundle order line takes, per bundle sold. Parts with
+/// the same variant are merged.
+pub fn record_parts(
+    conn: &mut PgConnection,
+    order_item_id: i64,
+    parts: &[BundlePart],
+) -> Result<Vec<OrderItemComponent>, Error> {
+    let mut components: Vec<OrderItemComponent> = Vec::with_capacity(parts.len());
+    for part in parts {
+        match components
+            .iter_mut()
+            .find(|component| component.variant_id == part.variant_id)
+        {
+            Some(component) => component.quantity += part.quantity,
+            None => components.push(OrderItemComponent {
+                variant_id: part.variant_id,
+                quantity: part.quantity,
+            }),
+        }
+    }
+
+    let rows: Vec<NewOrderItemComponentModel> = components
+        .iter()
+        .map(|component| NewOrderItemComponentModel {
+            order_item_id,
+            variant_id: component.variant_id,
+            quantity: component.quantity,
+        })
+        .collect();
+    if !rows.is_empty() {
+        diesel::insert_into(order_item_components::table)
+            .values(&rows)
+            .execute(conn)?;
+    }
+    Ok(components)
+}
+
+/// The component variants each of the order lines took, per bundle sold
+pub fn order_item_parts(
+    conn: &mut PgConnection,
+    order_item_ids: &[i64],
+) -> Result<HashMap<i64, Vec<OrderItemComponent>>, Error> {
+    let rows: Vec<(i64, i64, i32)> = order_item_components::table
+        .filter(order_item_components::order_item_id.eq_any(order_item_ids))
+        .select((
+            order_item_components::order_item_id,
+            order_item_components::variant_id,
+            order_item_components::quantity,
+        ))
+        .order(order_item_components::variant_id.asc())
+        .load(conn)?;
+
+    let mut parts: HashMap<i64, Vec<OrderItemComponent>> = HashMap::new();
+    for (order_item_id, variant_id, quantity) in rows {
+        parts
+            .entry(order_item_id)
+            .or_default()
+            .push(OrderItemComponent {
+                variant_id,
+                quantity,
+            });
+    }
+    Ok(parts)
+}
+
+/// The stock `quantity` units of an order line took: its own variant, or for a bundle the
+/// component variants it recorded
+pub fn stock_units(
+    conn: &mut PgConnection,
+    order_item_id: i64,
+    variant_id: i64,
+    quantity: i32,
+) -> Result<Vec<(i64, i32)>, Error> {
+    let parts = order_item_parts(conn, &[order_item_id])?
+        .remove(&order_item_id)
+        .unwrap_or_default();
+    if parts.is_empty() {
+        return Ok(vec![(variant_id, quantity)]);
+    }
+
+    Ok(parts
+        .into_iter()
+        .map(|part| (part.variant_id, part.quantity * quantity))
+        .collect())
+}
+
+// Components must be standard products, and a fixed variant must be one of its product's
+fn check_component(
+    conn: &mut PgConnection,
+    bundle_id: i64,
+    component: &BundleComponentRequest,
+) -> Result<(), Error> {
+    if component.product_id == bundle_id {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "A bundle can't contain itself",
+        ));
+    }
+    if product_kind(conn, component.product_id)? == ProductKind::Bundle {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Product {} is a bundle itself", component.product_id),
+        ));
+    }
+
+    let mut query = variants::table
+        .filter(variants::product_id.eq(component.product_id))
+        .into_boxed();
+    if let Some(variant_id) = component.variant_id {
+        query = query.filter(variants::variant_id.eq(variant_id));
+    }
+    let variant_count: i64 = query.count().get_result(conn)?;
+    if variant_count == 0 {
+        let message = match component.variant_id {
+            Some(variant_id) => format!(
+                "Variant {} is not a variant of product {}",
+                variant_id, component.product_id
+            ),
+            None => format!("Product {} has no variants", component.product_id),
+        };
+        return Err(Error::with_message(ErrorCode::ValidationError, message));
+    }
+    Ok(())
+}
+
+pub struct DieselBundleRepository {
+    pool: DBPool,
+}
+
+impl DieselBundleRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl BundleRepository for DieselBundleRepository {
+    async fn components(&self, product_id: i64) -> Result<Vec<BundleComponent>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        product_kind(&mut conn, product_id)?;
+        load_components(&mut conn, product_id)
+    }
+
+    async fn set_components(
+        &self,
+        product_id: i64,
+        components: Vec<BundleComponentRequest>,
+    ) -> Result<Vec<BundleComponent>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction(|conn| {
+            products::table
+                .filter(products::id.eq(product_id))
+                .select(products::id)
+                .for_update()
+                .first::<i64>(conn)
+                .optional()?
+                .ok_or_else(|| {
+                    Error::with_message(ErrorCode::ResourceNotFound, "Product not found")
+                })?;
+
+            let used_in: i64 = bundle_components::table
+                .filter(bundle_components::product_id.eq(product_id))
+                .count()
+                .get_result(conn)?;
+            if used_in > 0 {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "Product is part of another bundle and can't be a bundle itself",
+                ));
+            }
+
+            for component in &components {
+                check_component(conn, product_id, component)?;
+            }
+
+            diesel::delete(
+                bundle_components::table.filter(bundle_components::bundle_id.eq(product_id)),
+            )
+            .execute(conn)?;
+            let rows: Vec<NewComponentModel> = components
+                .iter()
+                .zip(0..)
+                .map(|(component, position)| NewComponentModel {
+                    bundle_id: product_id,
+                    product_id: component.product_id,
+                    variant_id: component.variant_id,
+                    quantity: component.quantity.unwrap_or(1),
+                    position,
+                })
+                .collect();
+            diesel::insert_into(bundle_components::table)
+                .values(&rows)
+                .execute(conn)?;
+
+            diesel::update(products::table.filter(products::id.eq(product_id)))
+                .set(products::kind.eq(ProductKind::Bundle))
+                .execute(conn)?;
+
+            // The bundle is sold through a variant of its own; its stock is never used
+            let own_variants: i64 = variants::table
+                .filter(variants::product_id.eq(product_id))
+                .count()
+                .get_result(conn)?;
+            if own_variants == 0 {
+                diesel::insert_into(variants::table)
+                    .values((
+                        variants::product_id.eq(product_id),
+                        variants::stock_quantity.eq(0),
+                    ))
+                    .execute(conn)?;
+            }
+
+            load_components(conn, product_id)
+        })
+    }
+
+    async fn remove(&self, product_id: i64) -> Result<(), Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction(|conn| {
+            if product_kind(conn, product_id)? != ProductKind::Bundle {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "Product is not a bundle",
+                ));
+            }
+
+            diesel::delete(
+                bundle_components::table.filter(bundle_components::bundle_id.eq(product_id)),
+            )
+            .execute(conn)?;
+            diesel::update(products::table.filter(products::id.eq(product_id)))
+                .set(products::kind.eq(ProductKind::Standard))
+                .execute(conn)?;
+            Ok(())
+        })
+    }
+}
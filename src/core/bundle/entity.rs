@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::product::entity::Variant;
+
+/// A product that goes into a bundle, `quantity` units per bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleComponent {
+    pub component_id: i64,
+    pub product_id: i64,
+    pub product_name: String,
+    /// Set when the bundle always includes this variant; otherwise the customer chooses one
+    /// of `options`
+    pub variant_id: Option<i64>,
+    pub quantity: i32,
+    /// The variants that can fill the component, with their stock
+    pub options: Vec<Variant>,
+}
+
+impl BundleComponent {
+    /// How many bundles the stock of this component's variants can fill
+    pub fn bundles_in_stock(&self) -> i64 {
+        let quantity = i64::from(self.quantity);
+        self.options
+            .iter()
+            .map(|variant| i64::from(variant.stock_quantity.max(0)) / quantity)
+            .sum()
+    }
+}
+
+/// How many bundles the components' stock can make up; a bundle with no components has none
+pub fn bundle_stock(components: &[BundleComponent]) -> i64 {
+    components
+        .iter()
+        .map(BundleComponent::bundles_in_stock)
+        .min()
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BundleComponentRequest {
+    pub product_id: i64,
+    /// Leave out to let the customer choose a variant
+    pub variant_id: Option<i64>,
+    /// Defaults to 1
+    pub quantity: Option<i32>,
+}
+
+/// Replaces a bundle's components as a whole, in the order given
+#[derive(Debug, Clone, Deserialize)]
+pub struct BundleRequest {
+    pub components: Vec<BundleComponentRequest>,
+}
+
+/// A customer's variant for a bundle component they get to choose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleChoice {
+    pub component_id: i64,
+    pub variant_id: i64,
+}
+
+/// A variant a bundle line takes from stock, `quantity` units per bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundlePart {
+    pub component_id: i64,
+    pub product_id: i64,
+    pub product_name: String,
+    pub variant_id: i64,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub quantity: i32,
+}
+
+/// A component variant an order line took from stock, per bundle sold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderItemComponent {
+    pub variant_id: i64,
+    pub quantity: i32,
+}
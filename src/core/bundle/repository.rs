@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+
+use crate::utils::errors::Error;
+
+use super::entity::{BundleComponent, BundleComponentRequest};
+
+#[async_trait]
+pub trait BundleRepository: Send + Sync {
+    async fn components(&self, product_id: i64) -> Result<Vec<BundleComponent>, Error>;
+    /// Makes the product a bundle of the given components, replacing any it had. Carts lose
+    /// the choices made for the old components.
+    async fn set_components(
+        &self,
+        product_id: i64,
+        components: Vec<BundleComponentRequest>,
+    ) -> Result<Vec<BundleComponent>, Error>;
+    /// Turns a bundle back into a standard product
+    async fn remove(&self, product_id: i64) -> Result<(), Error>;
+}
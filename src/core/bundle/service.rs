@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{BundleComponent, BundleRequest};
+use super::repository::BundleRepository;
+
+const MAX_COMPONENTS: usize = 20;
+const MAX_COMPONENT_QUANTITY: i32 = 100;
+
+#[derive(Clone)]
+pub struct BundleService {
+    repo: Arc<dyn BundleRepository>,
+}
+
+impl BundleService {
+    pub fn new(repo: Arc<dyn BundleRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn get_components(&self, product_id: i64) -> Result<Vec<BundleComponent>, Error> {
+        self.repo.components(product_id).await
+    }
+
+    pub async fn set_components(
+        &self,
+        product_id: i64,
+        bundle: BundleRequest,
+    ) -> Result<Vec<BundleComponent>, Error> {
+        if bundle.components.is_empty() || bundle.components.len() > MAX_COMPONENTS {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("A bundle needs 1 to {} components", MAX_COMPONENTS),
+            ));
+        }
+        if bundle.components.iter().any(|component| {
+            component
+                .quantity
+                .is_some_and(|quantity| !(1..=MAX_COMPONENT_QUANTITY).contains(&quantity))
+        }) {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!(
+                    "Component quantity must be between 1 and {}",
+                    MAX_COMPONENT_QUANTITY
+                ),
+            ));
+        }
+
+        self.repo
+            .set_components(product_id, bundle.components)
+            .await
+    }
+
+    pub async fn remove_bundle(&self, product_id: i64) -> Result<(), Error> {
+        self.repo.remove(product_id).await
+    }
+}
@@ -1,12 +1,23 @@
 use async_trait::async_trait;
+use bigdecimal::{BigDecimal, Zero};
+use diesel::dsl::sum;
 use diesel::prelude::*;
 use tracing::error;
 
-use crate::schema::{cart, cart_items, variants};
+use crate::core::affiliation::diesel::is_verified_member;
+use crate::core::bundle::diesel::{cart_item_parts, check_choices, item_choices, save_choices};
+use crate::core::bundle::entity::{BundleChoice, BundlePart};
+use crate::core::preorder::diesel::{ensure_within_quota, preorder_terms};
+use crate::core::product::diesel::{VariantPrices, on_sale, variant_prices};
+use crate::core::product::entity::{ProductKind, UnitPrice};
+use crate::core::promotion::diesel::{candidate_by_code, cart_candidates};
+use crate::core::promotion::discount::apply_promotions;
+use crate::core::promotion::entity::{CandidatePromotion, Discounts, PricedLine};
+use crate::schema::{cart, cart_items, cart_promotions, products, promotions, variants};
 use crate::utils::db::DBPool;
 use crate::utils::errors::{Error, ErrorCode};
 
-use super::entity::CartItem;
+use super::entity::{CartItem, CartLine, CartView};
 use super::repository::CartRepository;
 
 #[derive(Insertable)]
@@ -33,6 +44,186 @@ struct NewCartItemModel {
     pub quantity: Option<i32>,
 }
 
+#[derive(Queryable)]
+struct CartLineRow {
+    pub item_id: i64,
+    pub variant_id: i64,
+    pub product_id: i64,
+    pub product_name: String,
+    pub category_id: Option<i64>,
+    pub sku: Option<String>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub quantity: Option<i32>,
+    pub prices: VariantPrices,
+    pub available: bool,
+    pub kind: ProductKind,
+}
+
+impl CartLineRow {
+    // `parts` is `None` for a bundle missing a choice, which can't be checked out
+    fn into_line(self, member: bool, parts: Option<Vec<BundlePart>>) -> CartLine {
+        let quantity = self.quantity.unwrap_or(0);
+        let (unit_price, price_tier) = UnitPrice::from(self.prices).resolve(member);
+        CartLine {
+            item_id: self.item_id,
+            variant_id: self.variant_id,
+            product_id: self.product_id,
+            product_name: self.product_name,
+            category_id: self.category_id,
+            sku: self.sku,
+            size: self.size,
+            color: self.color,
+            quantity,
+            line_total: &unit_price * BigDecimal::from(quantity),
+            unit_price,
+            price_tier,
+            discount: BigDecimal::zero(),
+            available: self.available && parts.is_some(),
+            parts: parts.unwrap_or_default(),
+        }
+    }
+}
+
+pub fn user_cart_id(conn: &mut PgConnection, user_id: i64) -> Result<Option<i64>, Error> {
+    Ok(cart::table
+        .filter(cart::user_id.eq(user_id))
+        .select(cart::cart_id)
+        .first(conn)
+        .optional()?)
+}
+
+// Whether the cart's owner pays member prices
+fn cart_owner_is_member(conn: &mut PgConnection, cart_id: i64) -> Result<bool, Error> {
+    let user_id: i64 = cart::table
+        .filter(cart::cart_id.eq(cart_id))
+        .select(cart::user_id)
+        .first(conn)?;
+    is_verified_member(conn, user_id)
+}
+
+/// The cart's lines priced at what each unit sells for now to the cart's owner, in the order
+/// they were added
+pub fn cart_lines(conn: &mut PgConnection, cart_id: i64) -> Result<Vec<CartLine>, Error> {
+    let member = cart_owner_is_member(conn, cart_id)?;
+    let rows: Vec<CartLineRow> = cart_items::table
+        .inner_join(variants::table.inner_join(products::table))
+        .filter(cart_items::cart_id.eq(cart_id))
+        .filter(cart_items::quantity.gt(0))
+        .select((
+            cart_items::item_id,
+            cart_items::variant_id,
+            products::id,
+            products::name,
+            products::category_id,
+            variants::sku,
+            variants::size,
+            variants::color,
+            cart_items::quantity,
+            variant_prices(),
+            on_sale(),
+            products::kind,
+        ))
+        .order(cart_items::item_id.asc())
+        .load(conn)?;
+
+    rows.into_iter()
+        .map(|row| {
+            let parts = match row.kind {
+                ProductKind::Bundle => cart_item_parts(conn, row.item_id, row.product_id)?,
+                ProductKind::Standard => Some(Vec::new()),
+            };
+            Ok(row.into_line(member, parts))
+        })
+        .collect()
+}
+
+/// Prices the codes against the lines and fills in each line's share of the discount
+pub fn apply_coupons(lines: &mut [CartLine], candidates: &[CandidatePromotion]) -> Discounts {
+    let priced: Vec<PricedLine> = lines
+        .iter()
+        .map(|line| PricedLine {
+            product_id: line.product_id,
+            category_id: line.category_id,
+            amount: line.line_total.clone(),
+        })
+        .collect();
+
+    let discounts = apply_promotions(candidates, &priced);
+    for (line, discount) in lines.iter_mut().zip(&discounts.line_discounts) {
+        line.discount = discount.clone();
+    }
+    discounts
+}
+
+fn into_view(items: Vec<CartLine>, discounts: Discounts) -> CartView {
+    let subtotal: BigDecimal = items.iter().map(|line| &line.line_total).sum();
+    CartView {
+        total: &subtotal - &discounts.total,
+        subtotal,
+        discount: discounts.total,
+        free_shipping: discounts.free_shipping,
+        coupons: discounts.coupons,
+        items,
+    }
+}
+
+fn load_view(conn: &mut PgConnection, cart_id: i64, user_id: i64) -> Result<CartView, Error> {
+    let mut lines = cart_lines(conn, cart_id)?;
+    let candidates = cart_candidates(conn, cart_id, user_id, false)?;
+    let discounts = apply_coupons(&mut lines, &candidates);
+    Ok(into_view(lines, discounts))
+}
+
+// Rejects a code that would share the cart with a code that doesn't stack
+fn check_stacking(
+    candidate: &CandidatePromotion,
+    applied: &[CandidatePromotion],
+) -> Result<(), Error> {
+    if applied.is_empty() {
+        return Ok(());
+    }
+    if !candidate.promotion.stackable {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "This code can't be combined with other codes",
+        ));
+    }
+    if let Some(other) = applied.iter().find(|other| !other.promotion.stackable) {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!(
+                "Code {} can't be combined with other codes",
+                other.promotion.code
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// The cart's line for the variant with the same bundle choices, if any. A bundle with other
+// choices goes on a line of its own.
+fn find_item(
+    conn: &mut PgConnection,
+    cart_id: i64,
+    variant_id: i64,
+    choices: &[BundleChoice],
+) -> Result<Option<CartItemModel>, Error> {
+    let items: Vec<CartItemModel> = cart_items::table
+        .filter(cart_items::cart_id.eq(cart_id))
+        .filter(cart_items::variant_id.eq(variant_id))
+        .select(CartItemModel::as_select())
+        .order(cart_items::item_id.asc())
+        .load(conn)?;
+
+    for item in items {
+        if item_choices(conn, item.item_id)? == choices {
+            return Ok(Some(item));
+        }
+    }
+    Ok(None)
+}
+
 pub struct DieselCartRepository {
     pool: DBPool,
 }
@@ -43,13 +234,14 @@ impl DieselCartRepository {
     }
 }
 
-impl From<CartItemModel> for CartItem {
-    fn from(m: CartItemModel) -> Self {
+impl CartItemModel {
+    fn into_item(self, unit_price: BigDecimal) -> CartItem {
         CartItem {
-            item_id: m.item_id,
-            cart_id: m.cart_id,
-            variant_id: m.variant_id,
-            quantity: m.quantity.unwrap_or(0),
+            item_id: self.item_id,
+            cart_id: self.cart_id,
+            variant_id: self.variant_id,
+            quantity: self.quantity.unwrap_or(0),
+            unit_price,
         }
     }
 }
@@ -91,6 +283,7 @@ impl CartRepository for DieselCartRepository {
         cart_id_val: i64,
         variant_id_val: i64,
         quantity: i32,
+        choices: &[BundleChoice],
     ) -> Result<CartItem, Error> {
         if quantity <= 0 {
             return Err(Error::with_message(
@@ -103,11 +296,13 @@ impl CartRepository for DieselCartRepository {
             Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
         })?;
 
-        // Ensure variant exists
-        let _variant_exists: i64 = variants::table
+        // Ensure variant exists and its product is on sale
+        let prices: VariantPrices = variants::table
+            .inner_join(products::table)
             .filter(variants::variant_id.eq(variant_id_val))
-            .select(variants::variant_id)
-            .first::<i64>(&mut conn)
+            .filter(on_sale())
+            .select(variant_prices())
+            .first(&mut conn)
             .map_err(|e| if let diesel::result::Error::NotFound = e {
                 error!(error = %e, variant_id = variant_id_val, "Variant not found");
                 Error::with_message(ErrorCode::ResourceNotFound, "Variant not found")
@@ -115,59 +310,155 @@ impl CartRepository for DieselCartRepository {
                 error!(error = %e, variant_id = variant_id_val, "Database error while checking variant");
                 Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e))
             })?;
+        let member = cart_owner_is_member(&mut conn, cart_id_val)?;
+        let (unit_price, _) = UnitPrice::from(prices).resolve(member);
+
+        let choices = check_choices(&mut conn, variant_id_val, choices)?;
+        let existing = find_item(&mut conn, cart_id_val, variant_id_val, &choices)?;
+
+        // Pre-orders must be open, and the cart can't hold more than the quota has left
+        if let Some((product_id, _)) = preorder_terms(&mut conn, variant_id_val)? {
+            let in_cart: Option<i64> = cart_items::table
+                .filter(cart_items::cart_id.eq(cart_id_val))
+                .filter(cart_items::variant_id.eq(variant_id_val))
+                .select(sum(cart_items::quantity))
+                .first(&mut conn)?;
+            ensure_within_quota(
+                &mut conn,
+                product_id,
+                in_cart.unwrap_or(0) + i64::from(quantity),
+            )?;
+        }
 
-        // Check if item exists
-        let existing: Result<CartItemModel, diesel::result::Error> = cart_items::table
-            .filter(cart_items::cart_id.eq(cart_id_val))
-            .filter(cart_items::variant_id.eq(variant_id_val))
-            .select(CartItemModel::as_select())
-            .first(&mut conn);
-
-        match existing {
-            Ok(current) => {
-                let new_qty = current.quantity.unwrap_or(0) + quantity;
-                let updated: CartItemModel = diesel::update(
-                    cart_items::table.filter(cart_items::item_id.eq(current.item_id)),
+        if let Some(current) = existing {
+            let new_qty = current.quantity.unwrap_or(0) + quantity;
+            let updated: CartItemModel = diesel::update(
+                cart_items::table.filter(cart_items::item_id.eq(current.item_id)),
+            )
+            .set(cart_items::quantity.eq(Some(new_qty)))
+            .returning(CartItemModel::as_returning())
+            .get_result(&mut conn)
+            .map_err(|e| {
+                error!(error = %e, item_id = current.item_id, "Failed to update cart item quantity");
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to update item: {}", e),
                 )
-                .set(cart_items::quantity.eq(Some(new_qty)))
-                .returning(CartItemModel::as_returning())
-                .get_result(&mut conn)
-                .map_err(|e| {
-                    error!(error = %e, item_id = current.item_id, "Failed to update cart item quantity");
-                    Error::with_message(
-                        ErrorCode::DatabaseError,
-                        format!("Failed to update item: {}", e),
-                    )
-                })?;
-                Ok(updated.into())
-            }
-            Err(diesel::result::Error::NotFound) => {
-                // Insert new item
-                let new_item = NewCartItemModel {
-                    cart_id: cart_id_val,
-                    variant_id: variant_id_val,
-                    quantity: Some(quantity),
-                };
+            })?;
+            return Ok(updated.into_item(unit_price));
+        }
+
+        // Insert new item, with the choices made for a bundle
+        let new_item = NewCartItemModel {
+            cart_id: cart_id_val,
+            variant_id: variant_id_val,
+            quantity: Some(quantity),
+        };
+        let created: CartItemModel = conn
+            .transaction(|conn| {
                 let created: CartItemModel = diesel::insert_into(cart_items::table)
                     .values(&new_item)
                     .returning(CartItemModel::as_returning())
-                    .get_result(&mut conn)
-                    .map_err(|e| {
-                        error!(error = %e, cart_id = cart_id_val, variant_id = variant_id_val, "Failed to add cart item");
-                        Error::with_message(
-                            ErrorCode::DatabaseError,
-                            format!("Failed to add item: {}", e),
-                        )
-                    })?;
-                Ok(created.into())
-            }
-            Err(e) => {
-                error!(error = %e, cart_id = cart_id_val, variant_id = variant_id_val, "Failed to query cart item");
-                Err(Error::with_message(
+                    .get_result(conn)?;
+                save_choices(conn, created.item_id, &choices)?;
+                Ok::<_, Error>(created)
+            })
+            .map_err(|e| {
+                error!(error = %e, cart_id = cart_id_val, variant_id = variant_id_val, "Failed to add cart item");
+                Error::with_message(
                     ErrorCode::DatabaseError,
-                    format!("Failed to query cart item: {}", e),
-                ))
+                    format!("Failed to add item: {}", e),
+                )
+            })?;
+        Ok(created.into_item(unit_price))
+    }
+
+    async fn view(&self, user_id: i64) -> Result<CartView, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        match user_cart_id(&mut conn, user_id)? {
+            Some(cart_id) => load_view(&mut conn, cart_id, user_id),
+            None => Ok(into_view(Vec::new(), apply_promotions(&[], &[]))),
+        }
+    }
+
+    async fn apply_coupon(
+        &self,
+        cart_id: i64,
+        user_id: i64,
+        code: &str,
+    ) -> Result<CartView, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction::<_, Error, _>(|conn| {
+            let candidate = candidate_by_code(conn, code, user_id)?;
+            let mut candidates = cart_candidates(conn, cart_id, user_id, false)?;
+            if candidates
+                .iter()
+                .any(|applied| applied.promotion.promotion_id == candidate.promotion.promotion_id)
+            {
+                return Err(Error::with_message(
+                    ErrorCode::ResourceAlreadyExists,
+                    "This code is already applied to the cart",
+                ));
             }
+            check_stacking(&candidate, &candidates)?;
+
+            let promotion_id = candidate.promotion.promotion_id;
+            candidates.push(candidate);
+            let mut lines = cart_lines(conn, cart_id)?;
+            let discounts = apply_coupons(&mut lines, &candidates);
+            if let Some(problem) = discounts
+                .coupons
+                .last()
+                .and_then(|coupon| coupon.problem.clone())
+            {
+                return Err(Error::with_message(ErrorCode::ValidationError, problem));
+            }
+
+            diesel::insert_into(cart_promotions::table)
+                .values((
+                    cart_promotions::cart_id.eq(cart_id),
+                    cart_promotions::promotion_id.eq(promotion_id),
+                ))
+                .execute(conn)?;
+
+            Ok(into_view(lines, discounts))
+        })
+    }
+
+    async fn remove_coupon(&self, user_id: i64, code: &str) -> Result<CartView, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let not_applied = || {
+            Error::with_message(
+                ErrorCode::ResourceNotFound,
+                "This code isn't applied to the cart",
+            )
+        };
+        let cart_id = user_cart_id(&mut conn, user_id)?.ok_or_else(not_applied)?;
+        let removed = diesel::delete(
+            cart_promotions::table
+                .filter(cart_promotions::cart_id.eq(cart_id))
+                .filter(
+                    cart_promotions::promotion_id.eq_any(
+                        promotions::table
+                            .filter(promotions::code.eq(code))
+                            .select(promotions::promotion_id),
+                    ),
+                ),
+        )
+        .execute(&mut conn)?;
+        if removed == 0 {
+            return Err(not_applied());
         }
+
+        load_view(&mut conn, cart_id, user_id)
     }
 }
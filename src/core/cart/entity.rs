@@ -1,18 +1,28 @@
+use bigdecimal::BigDecimal;
 use serde::{Deserialize, Serialize};
 
+use crate::core::bundle::entity::{BundleChoice, BundlePart};
+use crate::core::product::entity::PriceTier;
+use crate::core::promotion::entity::AppliedCoupon;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CartItem {
     pub item_id: i64,
     pub cart_id: i64,
     pub variant_id: i64,
     pub quantity: i32,
+    /// What a unit sells for now to the cart's owner, sale and member price included.
+    /// Checkout charges the price at that time.
+    pub unit_price: BigDecimal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AddToCartRequest {
-    pub user_id: i64,
     pub variant_id: i64,
     pub quantity: i32,
+    /// A variant for each bundle component the customer chooses
+    #[serde(default)]
+    pub choices: Vec<BundleChoice>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,3 +30,40 @@ pub struct AddToCartResponse {
     pub item: CartItem,
     pub message: String,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CartLine {
+    pub item_id: i64,
+    pub variant_id: i64,
+    pub product_id: i64,
+    pub product_name: String,
+    pub category_id: Option<i64>,
+    pub sku: Option<String>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub quantity: i32,
+    /// What a unit sells for now to the cart's owner, sale and member price included
+    pub unit_price: BigDecimal,
+    /// Which price `unit_price` is
+    pub price_tier: PriceTier,
+    pub line_total: BigDecimal,
+    /// The line's share of the coupon discounts
+    pub discount: BigDecimal,
+    /// False once the product is taken off sale, or a bundle is missing a choice after it
+    /// changed; checkout is refused until the line is removed
+    pub available: bool,
+    /// The variants a bundle takes from stock, per bundle; empty for other products
+    pub parts: Vec<BundlePart>,
+}
+
+/// The cart priced as checkout would charge it, before shipping
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CartView {
+    pub items: Vec<CartLine>,
+    pub coupons: Vec<AppliedCoupon>,
+    pub subtotal: BigDecimal,
+    pub discount: BigDecimal,
+    pub total: BigDecimal,
+    /// A coupon waives the shipping fee of a delivered order
+    pub free_shipping: bool,
+}
@@ -1,8 +1,9 @@
 use async_trait::async_trait;
 
+use crate::core::bundle::entity::BundleChoice;
 use crate::utils::errors::Error;
 
-use super::entity::CartItem;
+use super::entity::{CartItem, CartView};
 
 #[async_trait]
 pub trait CartRepository: Send + Sync {
@@ -12,5 +13,13 @@ pub trait CartRepository: Send + Sync {
         cart_id: i64,
         variant_id: i64,
         quantity: i32,
+        choices: &[BundleChoice],
     ) -> Result<CartItem, Error>;
+    /// The user's cart with its coupons applied; empty if they have no cart yet.
+    async fn view(&self, user_id: i64) -> Result<CartView, Error>;
+    /// Applies a code to the cart. `code` must already be normalized. Fails when the code
+    /// can't be redeemed or takes nothing off the cart as it is.
+    async fn apply_coupon(&self, cart_id: i64, user_id: i64, code: &str)
+    -> Result<CartView, Error>;
+    async fn remove_coupon(&self, user_id: i64, code: &str) -> Result<CartView, Error>;
 }
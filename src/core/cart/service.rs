@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
+use crate::core::promotion::service::normalize_code;
 use crate::utils::errors::{Error, ErrorCode};
 
-use super::entity::{AddToCartRequest, AddToCartResponse, CartItem};
+use super::entity::{AddToCartRequest, AddToCartResponse, CartItem, CartView};
 use super::repository::CartRepository;
 
 pub struct CartService {
@@ -14,21 +15,40 @@ impl CartService {
         Self { repo }
     }
 
-    pub async fn add_to_cart(&self, req: AddToCartRequest) -> Result<AddToCartResponse, Error> {
+    pub async fn add_to_cart(
+        &self,
+        user_id: i64,
+        req: AddToCartRequest,
+    ) -> Result<AddToCartResponse, Error> {
         if req.quantity <= 0 {
             return Err(Error::with_message(
                 ErrorCode::ValidationError,
                 "Quantity must be greater than 0",
             ));
         }
-        let cart_id = self.repo.get_or_create_cart_id(req.user_id).await?;
+        let cart_id = self.repo.get_or_create_cart_id(user_id).await?;
         let item: CartItem = self
             .repo
-            .add_or_increment_item(cart_id, req.variant_id, req.quantity)
+            .add_or_increment_item(cart_id, req.variant_id, req.quantity, &req.choices)
             .await?;
         Ok(AddToCartResponse {
             item,
             message: "Item added to cart".to_string(),
         })
     }
+
+    pub async fn get_cart(&self, user_id: i64) -> Result<CartView, Error> {
+        self.repo.view(user_id).await
+    }
+
+    pub async fn apply_coupon(&self, user_id: i64, code: &str) -> Result<CartView, Error> {
+        let code = normalize_code(code)?;
+        let cart_id = self.repo.get_or_create_cart_id(user_id).await?;
+        self.repo.apply_coupon(cart_id, user_id, &code).await
+    }
+
+    pub async fn remove_coupon(&self, user_id: i64, code: &str) -> Result<CartView, Error> {
+        let code = normalize_code(code)?;
+        self.repo.remove_coupon(user_id, &code).await
+    }
 }
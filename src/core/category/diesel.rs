@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+use diesel::result::DatabaseErrorKind;
+use tracing::error;
+
+use crate::core::product::diesel::on_sale;
+use crate::schema::{categories, category_slug_redirects, products};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+use crate::utils::slug::slugify;
+
+use super::entity::{Category, NewCategory, UpdateCategory};
+use super::repository::CategoryRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = categories)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct CategoryModel {
+    pub category_id: i64,
+    pub name: String,
+    pub slug: String,
+    pub parent_id: Option<i64>,
+    pub display_order: i32,
+    pub image_url: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = categories)]
+struct NewCategoryModel {
+    pub name: String,
+    pub slug: String,
+    pub parent_id: Option<i64>,
+    pub display_order: i32,
+    pub image_url: Option<String>,
+}
+
+impl From<CategoryModel> for Category {
+    fn from(m: CategoryModel) -> Self {
+        Category {
+            category_id: m.category_id,
+            name: m.name,
+            slug: m.slug,
+            parent_id: m.parent_id,
+            display_order: m.display_order,
+            image_url: m.image_url,
+            created_at: m.created_at,
+            updated_at: m.updated_at,
+        }
+    }
+}
+
+fn write_error(e: diesel::result::Error) -> Error {
+    match e {
+        diesel::result::Error::NotFound => {
+            Error::with_message(ErrorCode::ResourceNotFound, "Category not found")
+        }
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+            Error::with_message(
+                ErrorCode::ResourceAlreadyExists,
+                "Category slug is already in use",
+            )
+        }
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+            Error::with_message(ErrorCode::ValidationError, "Parent category not found")
+        }
+        _ => e.into(),
+    }
+}
+
+/// Name of the category, which products keep a copy of for search.
+/// An unknown id is a validation error since it comes from the request.
+pub fn category_name(conn: &mut PgConnection, category_id: i64) -> Result<String, Error> {
+    categories::table
+        .filter(categories::category_id.eq(category_id))
+        .select(categories::name)
+        .first(conn)
+        .optional()?
+        .ok_or_else(|| Error::with_message(ErrorCode::ValidationError, "Category not found"))
+}
+
+/// Ids of the category with the given slug and all of its descendants; empty if there is no
+/// such category. A slug the category used before still finds it.
+pub fn subtree_ids(conn: &mut PgConnection, slug: &str) -> Result<Vec<i64>, Error> {
+    let current: Option<i64> = categories::table
+        .filter(categories::slug.eq(slug))
+        .select(categories::category_id)
+        .first(conn)
+        .optional()?;
+    let root = match current {
+        Some(root) => Some(root),
+        None => category_slug_redirects::table
+            .filter(category_slug_redirects::slug.eq(slug))
+            .select(category_slug_redirects::category_id)
+            .first(conn)
+            .optional()?,
+    };
+
+    match root {
+        Some(root) => with_descendants(conn, &[root]),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// The given category ids followed by all of their descendants, each listed once. Ids that
+/// don't exist are kept as they are.
+pub fn with_descendants(conn: &mut PgConnection, roots: &[i64]) -> Result<Vec<i64>, Error> {
+    if roots.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let rows: Vec<(i64, Option<i64>)> = categories::table
+        .select((categories::category_id, categories::parent_id))
+        .load(conn)?;
+
+    let mut children: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (id, parent_id) in &rows {
+        if let Some(parent_id) = parent_id {
+            children.entry(*parent_id).or_default().push(*id);
+        }
+    }
+
+    let mut ids: Vec<i64> = Vec::with_capacity(roots.len());
+    for root in roots {
+        if !ids.contains(root) {
+            ids.push(*root);
+        }
+    }
+    let mut next = 0;
+    while let Some(id) = ids.get(next).copied() {
+        for child in children.get(&id).into_iter().flatten() {
+            if !ids.contains(child) {
+                ids.push(*child);
+            }
+        }
+        next += 1;
+    }
+    Ok(ids)
+}
+
+// A slug is taken when another category uses it now or redirects from it
+fn slug_taken(conn: &mut PgConnection, slug: &str, category_id: i64) -> Result<bool, Error> {
+    let current: i64 = categories::table
+        .filter(categories::slug.eq(slug))
+        .filter(categories::category_id.ne(category_id))
+        .count()
+        .get_result(conn)?;
+    let redirected: i64 = category_slug_redirects::table
+        .filter(category_slug_redirects::slug.eq(slug))
+        .filter(category_slug_redirects::category_id.ne(category_id))
+        .count()
+        .get_result(conn)?;
+    Ok(current > 0 || redirected > 0)
+}
+
+// Moves a category to a new slug, keeping the old one as a redirect
+fn change_slug(
+    conn: &mut PgConnection,
+    category_id: i64,
+    old_slug: &str,
+    new_slug: &str,
+) -> Result<(), Error> {
+    diesel::insert_into(category_slug_redirects::table)
+        .values((
+            category_slug_redirects::slug.eq(old_slug),
+            category_slug_redirects::category_id.eq(category_id),
+        ))
+        .on_conflict(category_slug_redirects::slug)
+        .do_update()
+        .set(category_slug_redirects::category_id.eq(category_id))
+        .execute(conn)?;
+
+    // Going back to an earlier slug makes it current again rather than a redirect
+    diesel::delete(category_slug_redirects::table)
+        .filter(category_slug_redirects::slug.eq(new_slug))
+        .execute(conn)?;
+
+    Ok(())
+}
+
+// Rejects a parent that is the category itself or one of its descendants
+fn check_parent(conn: &mut PgConnection, category_id: i64, parent_id: i64) -> Result<(), Error> {
+    let parents: HashMap<i64, Option<i64>> = categories::table
+        .select((categories::category_id, categories::parent_id))
+        .load::<(i64, Option<i64>)>(conn)?
+        .into_iter()
+        .collect();
+
+    if !parents.contains_key(&parent_id) {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Parent category not found",
+        ));
+    }
+
+    let mut current = Some(parent_id);
+    while let Some(id) = current {
+        if id == category_id {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "A category cannot be moved under itself or one of its subcategories",
+            ));
+        }
+        current = parents.get(&id).copied().flatten();
+    }
+    Ok(())
+}
+
+pub struct DieselCategoryRepository {
+    pool: DBPool,
+}
+
+impl DieselCategoryRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CategoryRepository for DieselCategoryRepository {
+    async fn find_all(&self) -> Result<Vec<Category>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: Vec<CategoryModel> = categories::table
+            .select(CategoryModel::as_select())
+            .order((categories::display_order.asc(), categories::name.asc()))
+            .load(&mut conn)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn count_products(&self) -> Result<Vec<(i64, i64)>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let counts: Vec<(Option<i64>, i64)> = products::table
+            .filter(products::category_id.is_not_null())
+            .filter(on_sale())
+            .group_by(products::category_id)
+            .select((products::category_id, count_star()))
+            .load(&mut conn)?;
+
+        Ok(counts
+            .into_iter()
+            .filter_map(|(category_id, count)| category_id.map(|id| (id, count)))
+            .collect())
+    }
+
+    async fn create(&self, new_category: NewCategory, slug: String) -> Result<Category, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let created: CategoryModel = diesel::insert_into(categories::table)
+            .values(&NewCategoryModel {
+                name: new_category.name,
+                slug,
+                parent_id: new_category.parent_id,
+                display_order: new_category.display_order,
+                image_url: new_category.image_url,
+            })
+            .returning(CategoryModel::as_returning())
+            .get_result(&mut conn)
+            .map_err(write_error)?;
+
+        Ok(created.into())
+    }
+
+    async fn update(&self, category_id: i64, update: UpdateCategory) -> Result<Category, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction::<_, Error, _>(|conn| {
+            // Two categories moved under each other at the same time could each pass the
+            // cycle check, so hierarchy changes are serialised
+            diesel::sql_query("LOCK TABLE categories IN SHARE ROW EXCLUSIVE MODE").execute(conn)?;
+
+            if let Some(parent_id) = update.parent_id {
+                check_parent(conn, category_id, parent_id)?;
+            }
+
+            let target = categories::table.filter(categories::category_id.eq(category_id));
+            let old_slug: String = target
+                .select(categories::slug)
+                .first(conn)
+                .map_err(write_error)?;
+            if let Some(slug) = update.slug.as_ref().filter(|slug| **slug != old_slug) {
+                change_slug(conn, category_id, &old_slug, slug)?;
+            }
+
+            let updated: CategoryModel = match update.slug {
+                Some(slug) => diesel::update(target)
+                    .set((
+                        categories::name.eq(&update.name),
+                        categories::slug.eq(slug),
+                        categories::parent_id.eq(update.parent_id),
+                        categories::display_order.eq(update.display_order),
+                        categories::image_url.eq(&update.image_url),
+                        categories::updated_at.eq(Utc::now().naive_utc()),
+                    ))
+                    .returning(CategoryModel::as_returning())
+                    .get_result(conn),
+                None => diesel::update(target)
+                    .set((
+                        categories::name.eq(&update.name),
+                        categories::parent_id.eq(update.parent_id),
+                        categories::display_order.eq(update.display_order),
+                        categories::image_url.eq(&update.image_url),
+                        categories::updated_at.eq(Utc::now().naive_utc()),
+                    ))
+                    .returning(CategoryModel::as_returning())
+                    .get_result(conn),
+            }
+            .map_err(write_error)?;
+
+            diesel::update(products::table.filter(products::category_id.eq(category_id)))
+                .set(products::category.eq(&updated.name))
+                .execute(conn)?;
+
+            Ok(updated.into())
+        })
+    }
+
+    async fn delete(&self, category_id: i64, reassign_to: Option<i64>) -> Result<(), Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction::<_, Error, _>(|conn| {
+            categories::table
+                .filter(categories::category_id.eq(category_id))
+                .select(categories::category_id)
+                .for_update()
+                .first::<i64>(conn)
+                .map_err(write_error)?;
+
+            let children: i64 = categories::table
+                .filter(categories::parent_id.eq(category_id))
+                .count()
+                .get_result(conn)?;
+            if children > 0 {
+                return Err(Error::with_message(
+                    ErrorCode::ResourceAlreadyExists,
+                    "Category has subcategories; move or delete them first",
+                ));
+            }
+
+            let in_category = products::table.filter(products::category_id.eq(category_id));
+            if let Some(target_id) = reassign_to {
+                let name = category_name(conn, target_id)?;
+                diesel::update(in_category)
+                    .set((
+                        products::category_id.eq(target_id),
+                        products::category.eq(name),
+                    ))
+                    .execute(conn)?;
+            } else {
+                let count: i64 = in_category.count().get_result(conn)?;
+                if count > 0 {
+                    return Err(Error::with_message(
+                        ErrorCode::ResourceAlreadyExists,
+                        format!(
+                            "Category has {} products; pass reassign_to to move them",
+                            count
+                        ),
+                    ));
+                }
+            }
+
+            diesel::delete(categories::table.filter(categories::category_id.eq(category_id)))
+                .execute(conn)?;
+
+            Ok(())
+        })
+        .map_err(|e| {
+            if matches!(e.code, ErrorCode::DatabaseError) {
+                error!(error = %e, category_id, "Failed to delete category");
+            }
+            e
+        })
+    }
+
+    async fn romanise_slugs(&self) -> Result<usize, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: Vec<(i64, String, String)> = categories::table
+            .select((categories::category_id, categories::name, categories::slug))
+            .order(categories::category_id.asc())
+            .load(&mut conn)?;
+
+        let mut changed = 0;
+        for (category_id, name, old_slug) in rows {
+            let base = [slugify(&old_slug), slugify(&name)]
+                .into_iter()
+                .find(|slug| !slug.is_empty())
+                .unwrap_or_else(|| "category".to_string());
+            if base == old_slug {
+                continue;
+            }
+
+            conn.transaction::<_, Error, _>(|conn| {
+                let mut slug = base.clone();
+                let mut suffix = 2;
+                while slug_taken(conn, &slug, category_id)? {
+                    slug = format!("{}-{}", base, suffix);
+                    suffix += 1;
+                }
+
+                change_slug(conn, category_id, &old_slug, &slug)?;
+                diesel::update(categories::table.filter(categories::category_id.eq(category_id)))
+                    .set(categories::slug.eq(&slug))
+                    .execute(conn)?;
+                Ok(())
+            })?;
+            changed += 1;
+        }
+
+        Ok(changed)
+    }
+}
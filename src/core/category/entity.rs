@@ -0,0 +1,55 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Category {
+    pub category_id: i64,
+    pub name: String,
+    pub slug: String,
+    pub parent_id: Option<i64>,
+    pub display_order: i32,
+    pub image_url: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryNode {
+    pub category_id: i64,
+    pub name: String,
+    pub slug: String,
+    pub display_order: i32,
+    pub image_url: Option<String>,
+    /// Products in this category and everything below it
+    pub product_count: i64,
+    pub children: Vec<CategoryNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewCategory {
+    pub name: String,
+    /// Derived from the name when not given
+    pub slug: Option<String>,
+    pub parent_id: Option<i64>,
+    #[serde(default)]
+    pub display_order: i32,
+    pub image_url: Option<String>,
+}
+
+// Replaces the category's attributes; a missing parent_id moves it to the top level.
+// The slug is kept when left out so existing links keep working.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCategory {
+    pub name: String,
+    pub slug: Option<String>,
+    pub parent_id: Option<i64>,
+    #[serde(default)]
+    pub display_order: i32,
+    pub image_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteCategoryQuery {
+    /// Category to move the deleted category's products into
+    pub reassign_to: Option<i64>,
+}
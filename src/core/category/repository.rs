@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+
+use crate::utils::errors::Error;
+
+use super::entity::{Category, NewCategory, UpdateCategory};
+
+#[async_trait]
+pub trait CategoryRepository: Send + Sync {
+    async fn find_all(&self) -> Result<Vec<Category>, Error>;
+    /// Number of on-sale products directly in each category that has any.
+    async fn count_products(&self) -> Result<Vec<(i64, i64)>, Error>;
+    async fn create(&self, new_category: NewCategory, slug: String) -> Result<Category, Error>;
+    async fn update(&self, category_id: i64, update: UpdateCategory) -> Result<Category, Error>;
+    async fn delete(&self, category_id: i64, reassign_to: Option<i64>) -> Result<(), Error>;
+    /// Replaces slugs that aren't already romanised, such as Thai slugs from before
+    /// romanisation, keeping the old ones as redirects. Returns how many changed.
+    async fn romanise_slugs(&self) -> Result<usize, Error>;
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::utils::errors::{Error, ErrorCode};
+use crate::utils::slug::{MAX_SLUG_LEN, slugify};
+
+use super::entity::{Category, CategoryNode, NewCategory, UpdateCategory};
+use super::repository::CategoryRepository;
+
+const MAX_NAME_LEN: usize = 100;
+const MAX_IMAGE_URL_LEN: usize = 255;
+
+#[derive(Clone)]
+pub struct CategoryService {
+    repo: Arc<dyn CategoryRepository>,
+}
+
+impl CategoryService {
+    pub fn new(repo: Arc<dyn CategoryRepository>) -> Self {
+        Self { repo }
+    }
+
+    /// All categories as a tree, siblings in display order.
+    pub async fn category_tree(&self) -> Result<Vec<CategoryNode>, Error> {
+        let categories = self.repo.find_all().await?;
+        let counts: HashMap<i64, i64> = self.repo.count_products().await?.into_iter().collect();
+
+        let mut children: HashMap<Option<i64>, Vec<Category>> = HashMap::new();
+        for category in categories {
+            children
+                .entry(category.parent_id)
+                .or_default()
+                .push(category);
+        }
+
+        Ok(build_nodes(None, &mut children, &counts))
+    }
+
+    pub async fn create_category(&self, mut new_category: NewCategory) -> Result<Category, Error> {
+        new_category.name = validate_name(&new_category.name)?;
+        new_category.image_url = validate_image_url(new_category.image_url)?;
+        let slug = category_slug(new_category.slug.as_deref().unwrap_or(&new_category.name))?;
+
+        self.repo.create(new_category, slug).await
+    }
+
+    pub async fn update_category(
+        &self,
+        category_id: i64,
+        mut update: UpdateCategory,
+    ) -> Result<Category, Error> {
+        update.name = validate_name(&update.name)?;
+        update.image_url = validate_image_url(update.image_url)?;
+        update.slug = update.slug.as_deref().map(category_slug).transpose()?;
+
+        if update.parent_id == Some(category_id) {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "A category cannot be its own parent",
+            ));
+        }
+
+        self.repo.update(category_id, update).await
+    }
+
+    pub async fn delete_category(
+        &self,
+        category_id: i64,
+        reassign_to: Option<i64>,
+    ) -> Result<(), Error> {
+        if reassign_to == Some(category_id) {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Products cannot be reassigned to the category being deleted",
+            ));
+        }
+
+        self.repo.delete(category_id, reassign_to).await
+    }
+
+    pub async fn romanise_slugs(&self) -> Result<usize, Error> {
+        self.repo.romanise_slugs().await
+    }
+}
+
+fn build_nodes(
+    parent_id: Option<i64>,
+    children: &mut HashMap<Option<i64>, Vec<Category>>,
+    counts: &HashMap<i64, i64>,
+) -> Vec<CategoryNode> {
+    let Some(categories) = children.remove(&parent_id) else {
+        return Vec::new();
+    };
+
+    categories
+        .into_iter()
+        .map(|category| {
+            let nodes = build_nodes(Some(category.category_id), children, counts);
+            let own = counts.get(&category.category_id).copied().unwrap_or(0);
+            CategoryNode {
+                product_count: own + nodes.iter().map(|node| node.product_count).sum::<i64>(),
+                category_id: category.category_id,
+                name: category.name,
+                slug: category.slug,
+                display_order: category.display_order,
+                image_url: category.image_url,
+                children: nodes,
+            }
+        })
+        .collect()
+}
+
+fn validate_name(name: &str) -> Result<String, Error> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Category name is required",
+        ));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Category name must be {} characters or fewer", MAX_NAME_LEN),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_image_url(image_url: Option<String>) -> Result<Option<String>, Error> {
+    let image_url = image_url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    if image_url
+        .as_ref()
+        .is_some_and(|url| url.len() > MAX_IMAGE_URL_LEN)
+    {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!(
+                "Image URL must be {} characters or fewer",
+                MAX_IMAGE_URL_LEN
+            ),
+        ));
+    }
+    Ok(image_url)
+}
+
+// A slug for a category, from the chosen slug or else its name
+fn category_slug(text: &str) -> Result<String, Error> {
+    if text.chars().count() > MAX_SLUG_LEN {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Slug must be {} characters or fewer", MAX_SLUG_LEN),
+        ));
+    }
+
+    let slug = slugify(text);
+    if slug.is_empty() {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Slug must contain letters or digits",
+        ));
+    }
+    Ok(slug)
+}
@@ -0,0 +1,241 @@
+use async_trait::async_trait;
+use diesel::dsl::sum;
+use diesel::prelude::*;
+use tracing::error;
+
+use crate::schema::{inventory_movements, variants};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{InventoryMovement, InventoryReason, NewInventoryMovement, StockDrift};
+use super::repository::InventoryRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = inventory_movements)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct InventoryMovementModel {
+    pub movement_id: i64,
+    pub variant_id: i64,
+    pub quantity_change: i32,
+    pub reason: InventoryReason,
+    pub order_id: Option<i64>,
+    pub note: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = inventory_movements)]
+struct NewInventoryMovementModel {
+    pub variant_id: i64,
+    pub quantity_change: i32,
+    pub reason: InventoryReason,
+    pub order_id: Option<i64>,
+    pub note: Option<String>,
+    pub created_by: Option<String>,
+}
+
+impl From<InventoryMovementModel> for InventoryMovement {
+    fn from(m: InventoryMovementModel) -> Self {
+        InventoryMovement {
+            movement_id: m.movement_id,
+            variant_id: m.variant_id,
+            quantity_change: m.quantity_change,
+            reason: m.reason,
+            order_id: m.order_id,
+            note: m.note,
+            created_by: m.created_by,
+            created_at: m.created_at,
+        }
+    }
+}
+
+impl From<NewInventoryMovement> for NewInventoryMovementModel {
+    fn from(m: NewInventoryMovement) -> Self {
+        NewInventoryMovementModel {
+            variant_id: m.variant_id,
+            quantity_change: m.quantity_change,
+            reason: m.reason,
+            order_id: m.order_id,
+            note: m.note,
+            created_by: m.created_by,
+        }
+    }
+}
+
+/// Writes a ledger entry and moves the variant's stock balance by the same amount.
+///
+/// Must run inside a transaction: a movement that would take stock below zero returns an
+/// error after the balance was already updated, relying on the rollback to undo it.
+pub fn apply_movement(
+    conn: &mut PgConnection,
+    movement: NewInventoryMovement,
+) -> Result<(InventoryMovement, i32), Error> {
+    let variant_id = movement.variant_id;
+
+    let balance: i32 = diesel::update(variants::table.filter(variants::variant_id.eq(variant_id)))
+        .set(variants::stock_quantity.eq(variants::stock_quantity + movement.quantity_change))
+        .returning(variants::stock_quantity)
+        .get_result(conn)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => {
+                Error::with_message(ErrorCode::ResourceNotFound, "Variant not found")
+            }
+            _ => Error::with_message(
+                ErrorCode::DatabaseError,
+                format!("Failed to update stock: {}", e),
+            ),
+        })?;
+
+    if balance < 0 {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Insufficient stock for variant {}", variant_id),
+        ));
+    }
+
+    let new_row: NewInventoryMovementModel = movement.into();
+    let created: InventoryMovementModel = diesel::insert_into(inventory_movements::table)
+        .values(&new_row)
+        .returning(InventoryMovementModel::as_returning())
+        .get_result(conn)
+        .map_err(|e| {
+            error!(error = %e, variant_id, "Failed to record inventory movement");
+            Error::with_message(
+                ErrorCode::DatabaseError,
+                format!("Failed to record inventory movement: {}", e),
+            )
+        })?;
+
+    Ok((created.into(), balance))
+}
+
+pub struct DieselInventoryRepository {
+    pool: DBPool,
+}
+
+impl DieselInventoryRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl InventoryRepository for DieselInventoryRepository {
+    async fn record(
+        &self,
+        movement: NewInventoryMovement,
+    ) -> Result<(InventoryMovement, i32), Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction(|conn| apply_movement(conn, movement))
+    }
+
+    async fn stock_of(&self, variant_id: i64) -> Result<i32, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        variants::table
+            .filter(variants::variant_id.eq(variant_id))
+            .select(variants::stock_quantity)
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    Error::with_message(ErrorCode::ResourceNotFound, "Variant not found")
+                }
+                _ => {
+                    Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e))
+                }
+            })
+    }
+
+    async fn find_by_variant(
+        &self,
+        variant_id: i64,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<InventoryMovement>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: Vec<InventoryMovementModel> = inventory_movements::table
+            .filter(inventory_movements::variant_id.eq(variant_id))
+            .select(InventoryMovementModel::as_select())
+            .order(inventory_movements::movement_id.desc())
+            .offset(offset)
+            .limit(limit)
+            .load(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to fetch inventory movements: {}", e),
+                )
+            })?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn count_by_variant(&self, variant_id: i64) -> Result<i64, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        inventory_movements::table
+            .filter(inventory_movements::variant_id.eq(variant_id))
+            .count()
+            .get_result(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to count inventory movements: {}", e),
+                )
+            })
+    }
+
+    async fn find_drift(&self) -> Result<Vec<StockDrift>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: Vec<(i64, i64, i32, Option<i64>)> = variants::table
+            .left_join(inventory_movements::table)
+            .group_by((
+                variants::variant_id,
+                variants::product_id,
+                variants::stock_quantity,
+            ))
+            .select((
+                variants::variant_id,
+                variants::product_id,
+                variants::stock_quantity,
+                sum(inventory_movements::quantity_change.nullable()),
+            ))
+            .order(variants::variant_id.asc())
+            .load(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to reconcile inventory: {}", e),
+                )
+            })?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(variant_id, product_id, stock_quantity, ledger)| {
+                let ledger_quantity = ledger.unwrap_or(0);
+                let drift = i64::from(stock_quantity) - ledger_quantity;
+                (drift != 0).then_some(StockDrift {
+                    variant_id,
+                    product_id,
+                    stock_quantity,
+                    ledger_quantity,
+                    drift,
+                })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,79 @@
+use chrono::NaiveDateTime;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::InventoryReason"]
+pub enum InventoryReason {
+    #[db_rename = "RESTOCK"]
+    Restock,
+    #[db_rename = "SALE"]
+    Sale,
+    #[db_rename = "CANCELLATION"]
+    Cancellation,
+    #[db_rename = "ADJUSTMENT"]
+    Adjustment,
+    #[db_rename = "RETURN"]
+    Return,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryMovement {
+    pub movement_id: i64,
+    pub variant_id: i64,
+    pub quantity_change: i32,
+    pub reason: InventoryReason,
+    pub order_id: Option<i64>,
+    pub note: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewInventoryMovement {
+    pub variant_id: i64,
+    pub quantity_change: i32,
+    pub reason: InventoryReason,
+    pub order_id: Option<i64>,
+    pub note: Option<String>,
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockAdjustmentRequest {
+    pub quantity_change: i32,
+    pub reason: InventoryReason,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockAdjustmentResponse {
+    pub movement: InventoryMovement,
+    pub stock_quantity: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MovementHistory {
+    pub variant_id: i64,
+    pub stock_quantity: i32,
+    pub movements: Vec<InventoryMovement>,
+    pub total: i64,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+// A variant whose stored stock no longer matches the sum of its ledger entries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockDrift {
+    pub variant_id: i64,
+    pub product_id: i64,
+    pub stock_quantity: i32,
+    pub ledger_quantity: i64,
+    pub drift: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MovementQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
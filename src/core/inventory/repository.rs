@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+
+use crate::utils::errors::Error;
+
+use super::entity::{InventoryMovement, NewInventoryMovement, StockDrift};
+
+#[async_trait]
+pub trait InventoryRepository: Send + Sync {
+    /// Appends a movement and updates the variant's stock balance. Returns the new balance.
+    async fn record(
+        &self,
+        movement: NewInventoryMovement,
+    ) -> Result<(InventoryMovement, i32), Error>;
+    async fn stock_of(&self, variant_id: i64) -> Result<i32, Error>;
+    async fn find_by_variant(
+        &self,
+        variant_id: i64,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<InventoryMovement>, Error>;
+    async fn count_by_variant(&self, variant_id: i64) -> Result<i64, Error>;
+    async fn find_drift(&self) -> Result<Vec<StockDrift>, Error>;
+}
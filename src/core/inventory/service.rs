@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{
+    InventoryReason, MovementHistory, NewInventoryMovement, StockAdjustmentRequest,
+    StockAdjustmentResponse, StockDrift,
+};
+use super::repository::InventoryRepository;
+
+#[derive(Clone)]
+pub struct InventoryService {
+    repo: Arc<dyn InventoryRepository>,
+}
+
+impl InventoryService {
+    pub fn new(repo: Arc<dyn InventoryRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn adjust_stock(
+        &self,
+        variant_id: i64,
+        req: StockAdjustmentRequest,
+        created_by: String,
+    ) -> Result<StockAdjustmentResponse, Error> {
+        if variant_id <= 0 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Invalid variant ID",
+            ));
+        }
+
+        if req.quantity_change == 0 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Quantity change cannot be zero",
+            ));
+        }
+
+        match req.reason {
+            // Sales and cancellations are recorded by the order flow itself
+            InventoryReason::Sale | InventoryReason::Cancellation => {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "Sale and cancellation movements are recorded automatically",
+                ));
+            }
+            InventoryReason::Restock | InventoryReason::Return if req.quantity_change < 0 => {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "Restock and return movements must add stock",
+                ));
+            }
+            _ => {}
+        }
+
+        let (movement, stock_quantity) = self
+            .repo
+            .record(NewInventoryMovement {
+                variant_id,
+                quantity_change: req.quantity_change,
+                reason: req.reason,
+                order_id: None,
+                note: req.note,
+                created_by: Some(created_by),
+            })
+            .await?;
+
+        Ok(StockAdjustmentResponse {
+            movement,
+            stock_quantity,
+        })
+    }
+
+    pub async fn movement_history(
+        &self,
+        variant_id: i64,
+        page: u32,
+        page_size: u32,
+    ) -> Result<MovementHistory, Error> {
+        if page_size == 0 || page_size > 100 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Page size must be between 1 and 100",
+            ));
+        }
+
+        let stock_quantity = self.repo.stock_of(variant_id).await?;
+
+        let offset = i64::from(page.saturating_sub(1) * page_size);
+        let limit = i64::from(page_size);
+
+        let movements = self.repo.find_by_variant(variant_id, offset, limit).await?;
+        let total = self.repo.count_by_variant(variant_id).await?;
+
+        Ok(MovementHistory {
+            variant_id,
+            stock_quantity,
+            movements,
+            total,
+            page,
+            page_size,
+        })
+    }
+
+    pub async fn reconcile(&self) -> Result<Vec<StockDrift>, Error> {
+        self.repo.find_drift().await
+    }
+}
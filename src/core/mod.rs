@@ -1,4 +1,19 @@
+pub mod affiliation;
+pub mod bundle;
 pub mod cart;
+pub mod category;
 pub mod favorite;
+pub mod inventory;
+pub mod notification;
+pub mod order;
+pub mod payment;
+pub mod preorder;
 pub mod product;
+pub mod promotion;
+pub mod question;
+pub mod receipt;
+pub mod refund;
+pub mod review;
+pub mod search;
 pub mod user;
+pub mod variant;
@@ -0,0 +1,113 @@
+use async_trait::async_trait;
+use diesel::prelude::*;
+use tracing::error;
+
+use crate::schema::notifications;
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{NewNotification, Notification, NotificationType};
+use super::repository::NotificationRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = notifications)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct NotificationModel {
+    pub notification_id: i64,
+    pub user_id: i64,
+    pub order_id: Option<i64>,
+    pub notification_type: NotificationType,
+    pub message: String,
+    pub is_read: bool,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = notifications)]
+struct NewNotificationModel {
+    pub user_id: i64,
+    pub order_id: Option<i64>,
+    pub notification_type: NotificationType,
+    pub message: String,
+}
+
+impl From<NotificationModel> for Notification {
+    fn from(m: NotificationModel) -> Self {
+        Notification {
+            notification_id: m.notification_id,
+            user_id: m.user_id,
+            order_id: m.order_id,
+            notification_type: m.notification_type,
+            message: m.message,
+            is_read: m.is_read,
+            created_at: m.created_at,
+        }
+    }
+}
+
+impl From<NewNotification> for NewNotificationModel {
+    fn from(n: NewNotification) -> Self {
+        NewNotificationModel {
+            user_id: n.user_id,
+            order_id: n.order_id,
+            notification_type: n.notification_type,
+            message: n.message,
+        }
+    }
+}
+
+pub struct DieselNotificationRepository {
+    pool: DBPool,
+}
+
+impl DieselNotificationRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl NotificationRepository for DieselNotificationRepository {
+    async fn create(&self, new_notification: NewNotification) -> Result<Notification, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let user_id = new_notification.user_id;
+        let new_row: NewNotificationModel = new_notification.into();
+
+        let created: NotificationModel = diesel::insert_into(notifications::table)
+            .values(&new_row)
+            .returning(NotificationModel::as_returning())
+            .get_result(&mut conn)
+            .map_err(|e| {
+                error!(error = %e, user_id, "Failed to create notification");
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to create notification: {}", e),
+                )
+            })?;
+
+        Ok(created.into())
+    }
+
+    async fn find_by_user(&self, user_id: i64) -> Result<Vec<Notification>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: Vec<NotificationModel> = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .select(NotificationModel::as_select())
+            .order(notifications::created_at.desc())
+            .load(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to fetch notifications: {}", e),
+                )
+            })?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
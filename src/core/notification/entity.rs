@@ -0,0 +1,35 @@
+use chrono::NaiveDateTime;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::NotificationType"]
+pub enum NotificationType {
+    #[db_rename = "ORDER_EXPIRED"]
+    OrderExpired,
+    #[db_rename = "PAYMENT_VERIFIED"]
+    PaymentVerified,
+    #[db_rename = "PAYMENT_REJECTED"]
+    PaymentRejected,
+    #[db_rename = "REFUND_ISSUED"]
+    RefundIssued,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub notification_id: i64,
+    pub user_id: i64,
+    pub order_id: Option<i64>,
+    pub notification_type: NotificationType,
+    pub message: String,
+    pub is_read: bool,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewNotification {
+    pub user_id: i64,
+    pub order_id: Option<i64>,
+    pub notification_type: NotificationType,
+    pub message: String,
+}
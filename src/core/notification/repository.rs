@@ -0,0 +1,11 @@
+use async_trait::async_trait;
+
+use crate::utils::errors::Error;
+
+use super::entity::{NewNotification, Notification};
+
+#[async_trait]
+pub trait NotificationRepository: Send + Sync {
+    async fn create(&self, new_notification: NewNotification) -> Result<Notification, Error>;
+    async fn find_by_user(&self, user_id: i64) -> Result<Vec<Notification>, Error>;
+}
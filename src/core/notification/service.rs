@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use crate::utils::errors::Error;
+
+use super::entity::{NewNotification, Notification};
+use super::repository::NotificationRepository;
+
+#[derive(Clone)]
+pub struct NotificationService {
+    repo: Arc<dyn NotificationRepository>,
+}
+
+impl NotificationService {
+    pub fn new(repo: Arc<dyn NotificationRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn notify(&self, new_notification: NewNotification) -> Result<Notification, Error> {
+        self.repo.create(new_notification).await
+    }
+
+    pub async fn list_for_user(&self, user_id: i64) -> Result<Vec<Notification>, Error> {
+        self.repo.find_by_user(user_id).await
+    }
+}
@@ -0,0 +1,506 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use bigdecimal::{BigDecimal, Zero};
+use chrono::NaiveDateTime;
+use diesel::dsl::{exists, not};
+use diesel::prelude::*;
+use tracing::error;
+
+use crate::core::bundle::diesel::{order_item_parts, record_parts, stock_units};
+use crate::core::cart::diesel::{apply_coupons, cart_lines, user_cart_id};
+use crate::core::cart::entity::CartLine;
+use crate::core::inventory::diesel::apply_movement;
+use crate::core::inventory::entity::{InventoryReason, NewInventoryMovement};
+use crate::core::payment::entity::PaymentStatus;
+use crate::core::preorder::diesel::{ensure_within_quota, preorder_terms};
+use crate::core::preorder::entity::{PreorderPaymentMode, PreorderTerms};
+use crate::core::product::diesel::on_sale;
+use crate::core::product::entity::PriceTier;
+use crate::core::promotion::diesel::{cart_candidates, order_promotions, redeem};
+use crate::core::promotion::entity::Discounts;
+use crate::schema::{cart, cart_items, order_items, orders, payments, products, variants};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{
+    DeliveryType, ExpiredOrder, NewOrder, Order, OrderDetail, OrderItem, OrderStatus,
+};
+use super::repository::OrderRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = orders)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct OrderModel {
+    pub order_id: i64,
+    pub user_id: i64,
+    pub total_amount: Option<BigDecimal>,
+    pub order_status: OrderStatus,
+    pub delivery_type: Option<DeliveryType>,
+    pub shipping_address: Option<String>,
+    pub tracking_number: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub payment_deadline: Option<NaiveDateTime>,
+    pub cancelled_at: Option<NaiveDateTime>,
+    pub deposit_amount: Option<BigDecimal>,
+    pub discount_amount: BigDecimal,
+    pub shipping_fee: BigDecimal,
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = order_items)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct OrderItemModel {
+    pub order_item_id: i64,
+    pub order_id: i64,
+    pub variant_id: i64,
+    pub quantity: Option<i32>,
+    pub unit_price: Option<BigDecimal>,
+    pub preorder: bool,
+    pub discount_amount: BigDecimal,
+    pub price_tier: PriceTier,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = orders)]
+struct NewOrderModel {
+    pub user_id: i64,
+    pub total_amount: Option<BigDecimal>,
+    pub order_status: OrderStatus,
+    pub delivery_type: Option<DeliveryType>,
+    pub shipping_address: Option<String>,
+    pub payment_deadline: Option<NaiveDateTime>,
+    pub deposit_amount: Option<BigDecimal>,
+    pub discount_amount: BigDecimal,
+    pub shipping_fee: BigDecimal,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = order_items)]
+struct NewOrderItemModel {
+    pub order_id: i64,
+    pub variant_id: i64,
+    pub quantity: Option<i32>,
+    pub unit_price: Option<BigDecimal>,
+    pub preorder: bool,
+    pub discount_amount: BigDecimal,
+    pub price_tier: PriceTier,
+}
+
+impl From<OrderModel> for Order {
+    fn from(m: OrderModel) -> Self {
+        Order {
+            order_id: m.order_id,
+            user_id: m.user_id,
+            total_amount: m.total_amount,
+            order_status: m.order_status,
+            delivery_type: m.delivery_type,
+            shipping_address: m.shipping_address,
+            tracking_number: m.tracking_number,
+            created_at: m.created_at,
+            payment_deadline: m.payment_deadline,
+            cancelled_at: m.cancelled_at,
+            deposit_amount: m.deposit_amount,
+            discount_amount: m.discount_amount,
+            shipping_fee: m.shipping_fee,
+        }
+    }
+}
+
+impl From<OrderItemModel> for OrderItem {
+    fn from(m: OrderItemModel) -> Self {
+        OrderItem {
+            order_item_id: m.order_item_id,
+            order_id: m.order_id,
+            variant_id: m.variant_id,
+            quantity: m.quantity.unwrap_or(0),
+            unit_price: m.unit_price,
+            preorder: m.preorder,
+            discount_amount: m.discount_amount,
+            price_tier: m.price_tier,
+            components: Vec::new(),
+        }
+    }
+}
+
+pub struct DieselOrderRepository {
+    pool: DBPool,
+}
+
+impl DieselOrderRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+// Products taken off sale (archived or unpublished) can't be bought even if they were
+// added to the cart earlier
+fn ensure_cart_available(conn: &mut PgConnection, user_id: i64) -> Result<(), Error> {
+    let archived_lines: i64 = cart::table
+        .inner_join(cart_items::table.inner_join(variants::table.inner_join(products::table)))
+        .filter(cart::user_id.eq(user_id))
+        .filter(not(on_sale()))
+        .count()
+        .get_result(conn)?;
+    if archived_lines > 0 {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Some items in the cart are no longer available",
+        ));
+    }
+
+    Ok(())
+}
+
+// Pre-order terms for each cart line, `None` for lines sold from stock. Fails when a campaign
+// isn't open or the cart would take a product past its quota.
+fn cart_preorder_terms(
+    conn: &mut PgConnection,
+    lines: &[CartLine],
+) -> Result<Vec<Option<PreorderTerms>>, Error> {
+    let mut terms = Vec::with_capacity(lines.len());
+    let mut per_product: HashMap<i64, i64> = HashMap::new();
+    for line in lines {
+        let line_terms = preorder_terms(conn, line.variant_id)?;
+        if let Some((product_id, _)) = &line_terms {
+            *per_product.entry(*product_id).or_default() += i64::from(line.quantity);
+        }
+        terms.push(line_terms.map(|(_, terms)| terms));
+    }
+
+    for (product_id, quantity) in per_product {
+        ensure_within_quota(conn, product_id, quantity)?;
+    }
+
+    Ok(terms)
+}
+
+// Writes the order's lines and takes their stock: a bundle's component variants, or the
+// line's own variant. Fails the whole checkout if any variant ran out meanwhile. Pre-orders
+// are made to order, so they take no stock.
+fn insert_items(
+    conn: &mut PgConnection,
+    order_id: i64,
+    lines: &[CartLine],
+    preorders: &[Option<PreorderTerms>],
+) -> Result<Vec<OrderItem>, Error> {
+    let mut items = Vec::with_capacity(lines.len());
+    for (line, terms) in lines.iter().zip(preorders) {
+        let item: OrderItemModel = diesel::insert_into(order_items::table)
+            .values(&NewOrderItemModel {
+                order_id,
+                variant_id: line.variant_id,
+                quantity: Some(line.quantity),
+                unit_price: Some(line.unit_price.clone()),
+                preorder: terms.is_some(),
+                discount_amount: line.discount.clone(),
+                price_tier: line.price_tier,
+            })
+            .returning(OrderItemModel::as_returning())
+            .get_result(conn)?;
+        let components = record_parts(conn, item.order_item_id, &line.parts)?;
+
+        if !item.preorder {
+            let units: Vec<(i64, i32)> = if components.is_empty() {
+                vec![(line.variant_id, line.quantity)]
+            } else {
+                components
+                    .iter()
+                    .map(|part| (part.variant_id, part.quantity * line.quantity))
+                    .collect()
+            };
+            for (variant_id, quantity) in units {
+                apply_movement(
+                    conn,
+                    NewInventoryMovement {
+                        variant_id,
+                        quantity_change: -quantity,
+                        reason: InventoryReason::Sale,
+                        order_id: Some(order_id),
+                        note: None,
+                        created_by: None,
+                    },
+                )?;
+            }
+        }
+
+        items.push(OrderItem {
+            components,
+            ..item.into()
+        });
+    }
+    Ok(items)
+}
+
+// Prices the cart's codes under a lock on their promotions, so usage limits hold across
+// concurrent checkouts. Fails if any code can no longer be redeemed.
+fn checkout_discounts(
+    conn: &mut PgConnection,
+    cart_id: i64,
+    user_id: i64,
+    lines: &mut [CartLine],
+) -> Result<Discounts, Error> {
+    let candidates = cart_candidates(conn, cart_id, user_id, true)?;
+    let discounts = apply_coupons(lines, &candidates);
+    if let Some(coupon) = discounts
+        .coupons
+        .iter()
+        .find(|coupon| coupon.problem.is_some())
+    {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!(
+                "Coupon {} can't be used: {}",
+                coupon.code,
+                coupon.problem.as_deref().unwrap_or_default()
+            ),
+        ));
+    }
+
+    Ok(discounts)
+}
+
+// What confirms the order when it has deposit pre-order lines: their deposits plus the
+// discounted price of everything else and shipping. `None` when the whole total is due.
+fn deposit_due(
+    lines: &[CartLine],
+    preorders: &[Option<PreorderTerms>],
+    shipping_fee: &BigDecimal,
+) -> Option<BigDecimal> {
+    let has_deposit = preorders
+        .iter()
+        .flatten()
+        .any(|terms| terms.payment_mode == PreorderPaymentMode::Deposit);
+    if !has_deposit {
+        return None;
+    }
+
+    let items: BigDecimal = lines
+        .iter()
+        .zip(preorders)
+        .map(|(line, terms)| {
+            let amount = &line.line_total - &line.discount;
+            match terms {
+                Some(terms) => terms.due_now(&amount),
+                None => amount,
+            }
+        })
+        .sum();
+    Some(items + shipping_fee)
+}
+
+#[async_trait]
+impl OrderRepository for DieselOrderRepository {
+    async fn find_by_id_with_items(&self, order_id: i64) -> Result<OrderDetail, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let order: OrderModel = orders::table
+            .filter(orders::order_id.eq(order_id))
+            .select(OrderModel::as_select())
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    Error::with_message(ErrorCode::ResourceNotFound, "Order not found")
+                }
+                _ => {
+                    Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e))
+                }
+            })?;
+
+        let items: Vec<OrderItemModel> = order_items::table
+            .filter(order_items::order_id.eq(order_id))
+            .select(OrderItemModel::as_select())
+            .order(order_items::order_item_id.asc())
+            .load(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to fetch order items: {}", e),
+                )
+            })?;
+
+        let promotions = order_promotions(&mut conn, order_id)?;
+        let item_ids: Vec<i64> = items.iter().map(|item| item.order_item_id).collect();
+        let mut parts = order_item_parts(&mut conn, &item_ids)?;
+
+        Ok(OrderDetail {
+            order: order.into(),
+            items: items
+                .into_iter()
+                .map(|item| OrderItem {
+                    components: parts.remove(&item.order_item_id).unwrap_or_default(),
+                    ..item.into()
+                })
+                .collect(),
+            promotions,
+            promptpay: None,
+        })
+    }
+
+    async fn create_from_cart(&self, new_order: NewOrder) -> Result<OrderDetail, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let user_id = new_order.user_id;
+        conn.transaction::<_, Error, _>(|conn| {
+            ensure_cart_available(conn, user_id)?;
+
+            // Units are charged at what they sell for now, sale and member prices included
+            let cart_id = user_cart_id(conn, user_id)?;
+            let mut lines = match cart_id {
+                Some(cart_id) => cart_lines(conn, cart_id)?,
+                None => Vec::new(),
+            };
+            let Some(cart_id) = cart_id.filter(|_| !lines.is_empty()) else {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "Cart is empty",
+                ));
+            };
+
+            if lines.iter().any(|line| !line.available) {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "Some bundles in the cart are missing a choice",
+                ));
+            }
+
+            let preorders = cart_preorder_terms(conn, &lines)?;
+            let discounts = checkout_discounts(conn, cart_id, user_id, &mut lines)?;
+
+            let shipping_fee =
+                if new_order.delivery_type == DeliveryType::Shipping && !discounts.free_shipping {
+                    new_order.shipping_fee
+                } else {
+                    BigDecimal::zero()
+                };
+            let subtotal: BigDecimal = lines.iter().map(|line| &line.line_total).sum();
+
+            let order: OrderModel = diesel::insert_into(orders::table)
+                .values(&NewOrderModel {
+                    user_id,
+                    total_amount: Some(subtotal - &discounts.total + &shipping_fee),
+                    order_status: OrderStatus::PendingPayment,
+                    delivery_type: Some(new_order.delivery_type),
+                    shipping_address: new_order.shipping_address,
+                    payment_deadline: Some(new_order.payment_deadline),
+                    deposit_amount: deposit_due(&lines, &preorders, &shipping_fee),
+                    discount_amount: discounts.total.clone(),
+                    shipping_fee,
+                })
+                .returning(OrderModel::as_returning())
+                .get_result(conn)?;
+
+            let items = insert_items(conn, order.order_id, &lines, &preorders)?;
+
+            let promotions = redeem(conn, order.order_id, cart_id, &discounts)?;
+            diesel::delete(cart_items::table.filter(cart_items::cart_id.eq(cart_id)))
+                .execute(conn)?;
+
+            Ok(OrderDetail {
+                order: order.into(),
+                items,
+                promotions,
+                promptpay: None,
+            })
+        })
+        .map_err(|e| {
+            error!(error = %e, user_id, "Failed to check out cart");
+            e
+        })
+    }
+
+    async fn expire_overdue(
+        &self,
+        now: NaiveDateTime,
+        created_before: NaiveDateTime,
+        charges_before: NaiveDateTime,
+        limit: i64,
+    ) -> Result<Vec<ExpiredOrder>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction::<_, Error, _>(|conn| {
+            // SKIP LOCKED lets several server instances run the job without double-cancelling
+            let overdue: Vec<(i64, i64)> = orders::table
+                .filter(orders::order_status.eq(OrderStatus::PendingPayment))
+                .filter(
+                    orders::payment_deadline.lt(now).or(orders::payment_deadline
+                        .is_null()
+                        .and(orders::created_at.lt(created_before))),
+                )
+                // A slip submitted before the deadline is still waiting for an admin, and a
+                // gateway charge may still succeed until the provider gives up on it
+                .filter(not(exists(
+                    payments::table
+                        .filter(payments::order_id.eq(orders::order_id))
+                        .filter(payments::payment_status.eq(PaymentStatus::Pending))
+                        .filter(
+                            payments::provider
+                                .is_null()
+                                .or(payments::created_at.ge(charges_before)),
+                        ),
+                )))
+                .select((orders::order_id, orders::user_id))
+                .order(orders::order_id.asc())
+                .limit(limit)
+                .for_update()
+                .skip_locked()
+                .load(conn)?;
+
+            let mut expired = Vec::with_capacity(overdue.len());
+            for (order_id, user_id) in overdue {
+                diesel::update(orders::table.filter(orders::order_id.eq(order_id)))
+                    .set((
+                        orders::order_status.eq(OrderStatus::Cancelled),
+                        orders::cancelled_at.eq(Some(now)),
+                    ))
+                    .execute(conn)?;
+
+                let items: Vec<(i64, i64, Option<i32>)> = order_items::table
+                    .filter(order_items::order_id.eq(order_id))
+                    .filter(order_items::preorder.eq(false))
+                    .select((
+                        order_items::order_item_id,
+                        order_items::variant_id,
+                        order_items::quantity,
+                    ))
+                    .load(conn)?;
+
+                for (order_item_id, variant_id, quantity) in items {
+                    let quantity = quantity.unwrap_or(0);
+                    if quantity <= 0 {
+                        continue;
+                    }
+                    for (variant_id, quantity) in
+                        stock_units(conn, order_item_id, variant_id, quantity)?
+                    {
+                        apply_movement(
+                            conn,
+                            NewInventoryMovement {
+                                variant_id,
+                                quantity_change: quantity,
+                                reason: InventoryReason::Cancellation,
+                                order_id: Some(order_id),
+                                note: Some("Payment deadline passed".to_string()),
+                                created_by: None,
+                            },
+                        )?;
+                    }
+                }
+
+                expired.push(ExpiredOrder { order_id, user_id });
+            }
+
+            Ok(expired)
+        })
+        .map_err(|e| {
+            error!(error = %e, "Failed to expire overdue orders");
+            e
+        })
+    }
+}
@@ -0,0 +1,149 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::core::bundle::entity::OrderItemComponent;
+use crate::core::payment::entity::GatewayCharge;
+use crate::core::product::entity::PriceTier;
+use crate::core::promotion::entity::OrderPromotion;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::OrderStatus"]
+pub enum OrderStatus {
+    #[db_rename = "CART"]
+    Cart,
+    #[db_rename = "PENDING_PAYMENT"]
+    PendingPayment,
+    #[db_rename = "CONFIRMED"]
+    Confirmed,
+    #[db_rename = "SHIPPING"]
+    Shipping,
+    #[db_rename = "COMPLETED"]
+    Completed,
+    #[db_rename = "CANCELLED"]
+    Cancelled,
+    #[db_rename = "PARTIALLY_REFUNDED"]
+    PartiallyRefunded,
+    #[db_rename = "REFUNDED"]
+    Refunded,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::DeliveryType"]
+pub enum DeliveryType {
+    #[db_rename = "PICKUP"]
+    Pickup,
+    #[db_rename = "SHIPPING"]
+    Shipping,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Order {
+    pub order_id: i64,
+    pub user_id: i64,
+    pub total_amount: Option<BigDecimal>,
+    pub order_status: OrderStatus,
+    pub delivery_type: Option<DeliveryType>,
+    pub shipping_address: Option<String>,
+    pub tracking_number: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub payment_deadline: Option<NaiveDateTime>,
+    pub cancelled_at: Option<NaiveDateTime>,
+    /// Due to confirm an order with deposit pre-order lines; the rest of the total is paid
+    /// as a balance afterwards
+    pub deposit_amount: Option<BigDecimal>,
+    /// Taken off the items by coupons
+    pub discount_amount: BigDecimal,
+    /// Charged for delivery, after any free shipping coupon
+    pub shipping_fee: BigDecimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderItem {
+    pub order_item_id: i64,
+    pub order_id: i64,
+    pub variant_id: i64,
+    pub quantity: i32,
+    pub unit_price: Option<BigDecimal>,
+    /// Sold under a pre-order campaign rather than from stock
+    pub preorder: bool,
+    /// The line's share of the order discount, for all of its units
+    pub discount_amount: BigDecimal,
+    /// Whether `unit_price` was the regular, sale or member price
+    pub price_tier: PriceTier,
+    /// The component variants a bundle line took from stock, per bundle; empty for other
+    /// products
+    pub components: Vec<OrderItemComponent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderDetail {
+    pub order: Order,
+    pub items: Vec<OrderItem>,
+    /// Coupon codes redeemed at checkout
+    pub promotions: Vec<OrderPromotion>,
+    /// Present while the order awaits payment and the shop has a `PromptPay` ID
+    pub promptpay: Option<PromptPayQr>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptPayQr {
+    pub order_id: i64,
+    pub amount: BigDecimal,
+    pub payload: String,
+    pub qr_svg: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum QrImageFormat {
+    #[default]
+    Png,
+    Svg,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QrImageQuery {
+    pub format: Option<QrImageFormat>,
+}
+
+// An order cancelled by the expiry job, with enough context to notify its owner
+#[derive(Debug, Clone)]
+pub struct ExpiredOrder {
+    pub order_id: i64,
+    pub user_id: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum PaymentMethod {
+    /// Bank transfer or `PromptPay`, confirmed with a slip
+    #[default]
+    Transfer,
+    /// Online payment through the configured provider
+    Gateway,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckoutRequest {
+    pub delivery_type: DeliveryType,
+    pub shipping_address: Option<String>,
+    pub payment_method: Option<PaymentMethod>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewOrder {
+    pub user_id: i64,
+    pub delivery_type: DeliveryType,
+    pub shipping_address: Option<String>,
+    pub payment_deadline: NaiveDateTime,
+    /// Charged when the order is delivered, unless a coupon waives it
+    pub shipping_fee: BigDecimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckoutResponse {
+    pub order: OrderDetail,
+    /// Set when the customer chose to pay online
+    pub charge: Option<GatewayCharge>,
+}
@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+use crate::utils::errors::Error;
+
+use super::entity::{ExpiredOrder, NewOrder, OrderDetail};
+
+#[async_trait]
+pub trait OrderRepository: Send + Sync {
+    async fn find_by_id_with_items(&self, order_id: i64) -> Result<OrderDetail, Error>;
+    /// Turns the user's cart into an order awaiting payment at current product prices, takes
+    /// the stock and empties the cart, all in one transaction.
+    async fn create_from_cart(&self, new_order: NewOrder) -> Result<OrderDetail, Error>;
+    /// Cancels up to `limit` unpaid orders whose deadline is before `now` and puts their stock
+    /// back. Orders without a stored deadline expire once they were created before
+    /// `created_before`. Orders with a slip awaiting review, or with a gateway charge opened
+    /// after `charges_before` that the provider has not settled yet, are left alone, and rows
+    /// already locked by another instance are skipped.
+    async fn expire_overdue(
+        &self,
+        now: NaiveDateTime,
+        created_before: NaiveDateTime,
+        charges_before: NaiveDateTime,
+        limit: i64,
+    ) -> Result<Vec<ExpiredOrder>, Error>;
+}
@@ -0,0 +1,194 @@
+use std::sync::Arc;
+
+use bigdecimal::BigDecimal;
+use chrono::{Duration, Utc};
+use tracing::error;
+
+use crate::core::notification::entity::{NewNotification, NotificationType};
+use crate::core::notification::service::NotificationService;
+use crate::utils::errors::{Error, ErrorCode};
+use crate::utils::promptpay;
+
+use super::entity::{
+    CheckoutRequest, DeliveryType, NewOrder, Order, OrderDetail, OrderStatus, PromptPayQr,
+};
+use super::repository::OrderRepository;
+
+const EXPIRY_BATCH_SIZE: i64 = 100;
+/// How long a provider may take to settle a gateway charge before its order can expire
+const GATEWAY_CHARGE_TIMEOUT_MINUTES: i64 = 60;
+
+#[derive(Clone)]
+pub struct OrderService {
+    repo: Arc<dyn OrderRepository>,
+    notifications: NotificationService,
+    payment_timeout: Duration,
+    promptpay_id: Option<String>,
+    shipping_fee: BigDecimal,
+}
+
+impl OrderService {
+    pub fn new(
+        repo: Arc<dyn OrderRepository>,
+        notifications: NotificationService,
+        payment_timeout: Duration,
+        promptpay_id: Option<String>,
+        shipping_fee: BigDecimal,
+    ) -> Self {
+        Self {
+            repo,
+            notifications,
+            payment_timeout,
+            promptpay_id,
+            shipping_fee,
+        }
+    }
+
+    /// Only the order owner or an admin may see it
+    pub async fn get_order(
+        &self,
+        order_id: i64,
+        user_id: i64,
+        is_admin: bool,
+    ) -> Result<OrderDetail, Error> {
+        if order_id <= 0 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Invalid order ID",
+            ));
+        }
+
+        let mut detail = self.repo.find_by_id_with_items(order_id).await?;
+        if detail.order.user_id != user_id && !is_admin {
+            return Err(Error::with_message(
+                ErrorCode::ResourceNotFound,
+                "Order not found",
+            ));
+        }
+
+        // Orders placed before deadlines were stored fall back to the configured timeout
+        if detail.order.order_status == OrderStatus::PendingPayment
+            && detail.order.payment_deadline.is_none()
+        {
+            detail.order.payment_deadline = Some(detail.order.created_at + self.payment_timeout);
+        }
+
+        detail.promptpay = self.build_promptpay(&detail.order).ok();
+
+        Ok(detail)
+    }
+
+    pub async fn checkout(&self, user_id: i64, req: CheckoutRequest) -> Result<OrderDetail, Error> {
+        let shipping_address = req
+            .shipping_address
+            .map(|address| address.trim().to_string())
+            .filter(|address| !address.is_empty());
+
+        if req.delivery_type == DeliveryType::Shipping && shipping_address.is_none() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Shipping address is required for delivery",
+            ));
+        }
+
+        let mut detail = self
+            .repo
+            .create_from_cart(NewOrder {
+                user_id,
+                delivery_type: req.delivery_type,
+                shipping_address,
+                payment_deadline: Utc::now().naive_utc() + self.payment_timeout,
+                shipping_fee: self.shipping_fee.clone(),
+            })
+            .await?;
+
+        detail.promptpay = self.build_promptpay(&detail.order).ok();
+
+        Ok(detail)
+    }
+
+    pub async fn get_promptpay(
+        &self,
+        order_id: i64,
+        user_id: i64,
+        is_admin: bool,
+    ) -> Result<PromptPayQr, Error> {
+        let detail = self.get_order(order_id, user_id, is_admin).await?;
+        self.build_promptpay(&detail.order)
+    }
+
+    fn build_promptpay(&self, order: &Order) -> Result<PromptPayQr, Error> {
+        let Some(promptpay_id) = self.promptpay_id.as_deref() else {
+            return Err(Error::with_message(
+                ErrorCode::InternalError,
+                "PromptPay is not configured",
+            ));
+        };
+
+        if order.order_status != OrderStatus::PendingPayment {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Order is not awaiting payment",
+            ));
+        }
+
+        let Some(amount) = order
+            .deposit_amount
+            .clone()
+            .or_else(|| order.total_amount.clone())
+        else {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Order has no total amount",
+            ));
+        };
+
+        let payload = promptpay::payload(promptpay_id, Some(&amount))?;
+        let qr_svg = promptpay::render_svg(&payload)?;
+
+        Ok(PromptPayQr {
+            order_id: order.order_id,
+            amount,
+            payload,
+            qr_svg,
+        })
+    }
+
+    /// Cancels every overdue unpaid order and notifies its owner. Returns how many were expired.
+    pub async fn expire_overdue_orders(&self) -> Result<usize, Error> {
+        let now = Utc::now().naive_utc();
+        let created_before = now - self.payment_timeout;
+        let charges_before = now - Duration::minutes(GATEWAY_CHARGE_TIMEOUT_MINUTES);
+        let mut total = 0;
+
+        loop {
+            let expired = self
+                .repo
+                .expire_overdue(now, created_before, charges_before, EXPIRY_BATCH_SIZE)
+                .await?;
+            let batch_len = expired.len();
+            total += batch_len;
+
+            for order in expired {
+                let notification = NewNotification {
+                    user_id: order.user_id,
+                    order_id: Some(order.order_id),
+                    notification_type: NotificationType::OrderExpired,
+                    message: format!(
+                        "Order #{} was cancelled because payment was not received in time",
+                        order.order_id
+                    ),
+                };
+                if let Err(e) = self.notifications.notify(notification).await {
+                    error!(error = %e, order_id = order.order_id, "Failed to notify expired order");
+                }
+            }
+
+            if i64::try_from(batch_len).unwrap_or(i64::MAX) < EXPIRY_BATCH_SIZE {
+                break;
+            }
+        }
+
+        Ok(total)
+    }
+}
@@ -0,0 +1,766 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use tracing::{error, warn};
+
+use crate::core::order::entity::OrderStatus;
+use crate::schema::{orders, payment_events, payments, statement_entries};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{
+    NewGatewayPayment, NewPayment, Payment, PaymentReview, PaymentStatus, RecordedEntry,
+    ReviewedPayment,
+};
+use super::provider::ChargeStatus;
+use super::repository::PaymentRepository;
+use super::statement::{StatementEntry, StatementFormat};
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = payments)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct PaymentModel {
+    pub payment_id: i64,
+    pub order_id: i64,
+    pub amount_paid: Option<BigDecimal>,
+    pub slip_object: Option<String>,
+    pub payment_status: PaymentStatus,
+    pub created_at: NaiveDateTime,
+    pub transferred_at: Option<NaiveDateTime>,
+    pub submitted_by: Option<i64>,
+    pub rejection_reason: Option<String>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub provider: Option<String>,
+    pub provider_reference: Option<String>,
+    pub needs_refund: bool,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = payments)]
+struct NewPaymentModel {
+    pub order_id: i64,
+    pub amount_paid: Option<BigDecimal>,
+    pub slip_object: Option<String>,
+    pub payment_status: PaymentStatus,
+    pub transferred_at: Option<NaiveDateTime>,
+    pub submitted_by: Option<i64>,
+    pub provider: Option<String>,
+    pub provider_reference: Option<String>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = payment_events)]
+struct NewPaymentEventModel<'a> {
+    pub provider: &'a str,
+    pub event_id: &'a str,
+    pub payment_id: i64,
+}
+
+impl From<PaymentModel> for Payment {
+    fn from(m: PaymentModel) -> Self {
+        Payment {
+            payment_id: m.payment_id,
+            order_id: m.order_id,
+            amount_paid: m.amount_paid,
+            slip_object: m.slip_object,
+            payment_status: m.payment_status,
+            transferred_at: m.transferred_at,
+            submitted_by: m.submitted_by,
+            rejection_reason: m.rejection_reason,
+            reviewed_by: m.reviewed_by,
+            reviewed_at: m.reviewed_at,
+            provider: m.provider,
+            provider_reference: m.provider_reference,
+            created_at: m.created_at,
+            needs_refund: m.needs_refund,
+        }
+    }
+}
+
+impl From<NewPayment> for NewPaymentModel {
+    fn from(p: NewPayment) -> Self {
+        NewPaymentModel {
+            order_id: p.order_id,
+            amount_paid: Some(p.amount_paid),
+            slip_object: Some(p.slip_object),
+            payment_status: PaymentStatus::Pending,
+            transferred_at: Some(p.transferred_at),
+            submitted_by: Some(p.submitted_by),
+            provider: None,
+            provider_reference: None,
+        }
+    }
+}
+
+impl From<NewGatewayPayment> for NewPaymentModel {
+    fn from(p: NewGatewayPayment) -> Self {
+        NewPaymentModel {
+            order_id: p.order_id,
+            amount_paid: Some(p.amount_paid),
+            slip_object: None,
+            payment_status: PaymentStatus::Pending,
+            transferred_at: None,
+            submitted_by: Some(p.submitted_by),
+            provider: Some(p.provider),
+            provider_reference: Some(p.provider_reference),
+        }
+    }
+}
+
+fn check_payable(
+    conn: &mut PgConnection,
+    order_id: i64,
+    user_id: i64,
+) -> Result<Option<BigDecimal>, Error> {
+    let (owner_id, status, deadline, total, deposit): (
+        i64,
+        OrderStatus,
+        Option<NaiveDateTime>,
+        Option<BigDecimal>,
+        Option<BigDecimal>,
+    ) = orders::table
+        .filter(orders::order_id.eq(order_id))
+        .select((
+            orders::user_id,
+            orders::order_status,
+            orders::payment_deadline,
+            orders::total_amount,
+            orders::deposit_amount,
+        ))
+        .for_update()
+        .first(conn)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => {
+                Error::with_message(ErrorCode::ResourceNotFound, "Order not found")
+            }
+            _ => Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e)),
+        })?;
+
+    // Other users' orders are reported as missing rather than forbidden
+    if owner_id != user_id {
+        return Err(Error::with_message(
+            ErrorCode::ResourceNotFound,
+            "Order not found",
+        ));
+    }
+
+    if awaits_balance(status, deposit.as_ref()) {
+        return check_balance_payable(conn, order_id, total);
+    }
+
+    if status != OrderStatus::PendingPayment {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Order is not awaiting payment",
+        ));
+    }
+
+    if deadline.is_some_and(|deadline| deadline < Utc::now().naive_utc()) {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Payment deadline has passed",
+        ));
+    }
+
+    let open_payments: i64 = payments::table
+        .filter(payments::order_id.eq(order_id))
+        .filter(payments::payment_status.eq_any([PaymentStatus::Pending, PaymentStatus::Verified]))
+        .count()
+        .get_result(conn)?;
+
+    if open_payments > 0 {
+        return Err(Error::with_message(
+            ErrorCode::ResourceAlreadyExists,
+            "A payment for this order is already pending review or verified",
+        ));
+    }
+
+    // An order with deposit pre-order lines is confirmed by its deposit
+    Ok(deposit.or(total))
+}
+
+// A confirmed order whose deposit was paid still takes payments towards its balance
+fn awaits_balance(status: OrderStatus, deposit: Option<&BigDecimal>) -> bool {
+    status == OrderStatus::Confirmed && deposit.is_some()
+}
+
+// The balance left after the verified payments, with no payment pending review
+fn check_balance_payable(
+    conn: &mut PgConnection,
+    order_id: i64,
+    total: Option<BigDecimal>,
+) -> Result<Option<BigDecimal>, Error> {
+    let pending: i64 = payments::table
+        .filter(payments::order_id.eq(order_id))
+        .filter(payments::payment_status.eq(PaymentStatus::Pending))
+        .count()
+        .get_result(conn)?;
+
+    if pending > 0 {
+        return Err(Error::with_message(
+            ErrorCode::ResourceAlreadyExists,
+            "A payment for this order is already pending review",
+        ));
+    }
+
+    let paid: Option<BigDecimal> = payments::table
+        .filter(payments::order_id.eq(order_id))
+        .filter(payments::payment_status.eq(PaymentStatus::Verified))
+        .select(diesel::dsl::sum(payments::amount_paid))
+        .first(conn)?;
+
+    let balance = total.map(|total| total - paid.unwrap_or_default());
+    if balance
+        .as_ref()
+        .is_none_or(|balance| balance <= &BigDecimal::from(0))
+    {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Order is already paid in full",
+        ));
+    }
+
+    Ok(balance)
+}
+
+fn insert_payment(conn: &mut PgConnection, new_row: &NewPaymentModel) -> Result<Payment, Error> {
+    let created: PaymentModel = diesel::insert_into(payments::table)
+        .values(new_row)
+        .returning(PaymentModel::as_returning())
+        .get_result(conn)
+        .map_err(|e| {
+            error!(error = %e, order_id = new_row.order_id, "Failed to create payment");
+            Error::with_message(
+                ErrorCode::DatabaseError,
+                format!("Failed to create payment: {}", e),
+            )
+        })?;
+
+    Ok(created.into())
+}
+
+// Locks a payment that is still awaiting review, along with its order's owner and status
+fn lock_pending(
+    conn: &mut PgConnection,
+    payment_id: i64,
+) -> Result<(PaymentModel, i64, OrderStatus), Error> {
+    let payment: PaymentModel = payments::table
+        .filter(payments::payment_id.eq(payment_id))
+        .select(PaymentModel::as_select())
+        .for_update()
+        .first(conn)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => {
+                Error::with_message(ErrorCode::ResourceNotFound, "Payment not found")
+            }
+            _ => Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e)),
+        })?;
+
+    if payment.payment_status != PaymentStatus::Pending {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Payment has already been reviewed",
+        ));
+    }
+
+    if payment.provider.is_some() {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Gateway payments are settled by their provider",
+        ));
+    }
+
+    let (user_id, status): (i64, OrderStatus) = orders::table
+        .filter(orders::order_id.eq(payment.order_id))
+        .select((orders::user_id, orders::order_status))
+        .for_update()
+        .first(conn)?;
+
+    Ok((payment, user_id, status))
+}
+
+// Marks a pending payment as verified and confirms its order, within the caller's transaction
+fn verify_pending(
+    conn: &mut PgConnection,
+    payment_id: i64,
+    reviewed_by: &str,
+) -> Result<ReviewedPayment, Error> {
+    let (payment, user_id, order_status) = lock_pending(conn, payment_id)?;
+
+    let deposit: Option<BigDecimal> = orders::table
+        .filter(orders::order_id.eq(payment.order_id))
+        .select(orders::deposit_amount)
+        .first(conn)?;
+    let balance_payment = awaits_balance(order_status, deposit.as_ref());
+
+    if order_status != OrderStatus::PendingPayment && !balance_payment {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Order is no longer awaiting payment",
+        ));
+    }
+
+    let updated: PaymentModel =
+        diesel::update(payments::table.filter(payments::payment_id.eq(payment_id)))
+            .set((
+                payments::payment_status.eq(PaymentStatus::Verified),
+                payments::reviewed_by.eq(Some(reviewed_by)),
+                payments::reviewed_at.eq(Some(Utc::now().naive_utc())),
+            ))
+            .returning(PaymentModel::as_returning())
+            .get_result(conn)?;
+
+    if !balance_payment {
+        diesel::update(orders::table.filter(orders::order_id.eq(payment.order_id)))
+            .set(orders::order_status.eq(OrderStatus::Confirmed))
+            .execute(conn)?;
+    }
+
+    Ok(ReviewedPayment {
+        payment: updated.into(),
+        user_id,
+    })
+}
+
+type ReviewRows = Vec<(
+    PaymentModel,
+    i64,
+    Option<BigDecimal>,
+    Option<BigDecimal>,
+    OrderStatus,
+)>;
+
+fn into_reviews(rows: ReviewRows) -> Vec<PaymentReview> {
+    rows.into_iter()
+        .map(|(payment, user_id, order_total, deposit, order_status)| {
+            // A deposit order is paid as its deposit, then as the rest of the total
+            let expected = match (&deposit, &order_total) {
+                (Some(deposit), _) if order_status == OrderStatus::PendingPayment => {
+                    Some(deposit.clone())
+                }
+                (Some(deposit), Some(total)) => Some(total - deposit),
+                _ => order_total.clone(),
+            };
+            let amount_mismatch = payment.amount_paid != expected;
+            PaymentReview {
+                payment: payment.into(),
+                user_id,
+                order_total,
+                amount_mismatch,
+            }
+        })
+        .collect()
+}
+
+pub struct DieselPaymentRepository {
+    pool: DBPool,
+}
+
+impl DieselPaymentRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PaymentRepository for DieselPaymentRepository {
+    async fn ensure_payable(
+        &self,
+        order_id: i64,
+        user_id: i64,
+    ) -> Result<Option<BigDecimal>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction(|conn| check_payable(conn, order_id, user_id))
+    }
+
+    async fn create_pending(&self, new_payment: NewPayment) -> Result<Payment, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let order_id = new_payment.order_id;
+        conn.transaction(|conn| {
+            check_payable(conn, order_id, new_payment.submitted_by)?;
+            insert_payment(conn, &new_payment.into())
+        })
+    }
+
+    async fn create_gateway_payment(
+        &self,
+        new_payment: NewGatewayPayment,
+    ) -> Result<Payment, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let order_id = new_payment.order_id;
+        conn.transaction(|conn| {
+            check_payable(conn, order_id, new_payment.submitted_by)?;
+            insert_payment(conn, &new_payment.into())
+        })
+    }
+
+    async fn find_by_id(&self, payment_id: i64) -> Result<Payment, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        payments::table
+            .filter(payments::payment_id.eq(payment_id))
+            .select(PaymentModel::as_select())
+            .first::<PaymentModel>(&mut conn)
+            .map(Into::into)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    Error::with_message(ErrorCode::ResourceNotFound, "Payment not found")
+                }
+                _ => {
+                    Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e))
+                }
+            })
+    }
+
+    async fn find_with_owner(&self, payment_id: i64) -> Result<(Payment, i64), Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        payments::table
+            .inner_join(orders::table)
+            .filter(payments::payment_id.eq(payment_id))
+            .select((PaymentModel::as_select(), orders::user_id))
+            .first::<(PaymentModel, i64)>(&mut conn)
+            .map(|(model, owner_id)| (model.into(), owner_id))
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    Error::with_message(ErrorCode::ResourceNotFound, "Payment not found")
+                }
+                _ => {
+                    Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e))
+                }
+            })
+    }
+
+    async fn find_by_order(&self, order_id: i64, user_id: i64) -> Result<Vec<Payment>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let owner_id: i64 = orders::table
+            .filter(orders::order_id.eq(order_id))
+            .select(orders::user_id)
+            .first(&mut conn)
+            .optional()?
+            .filter(|owner_id| *owner_id == user_id)
+            .ok_or_else(|| Error::with_message(ErrorCode::ResourceNotFound, "Order not found"))?;
+
+        let rows: Vec<PaymentModel> = payments::table
+            .filter(payments::order_id.eq(order_id))
+            .select(PaymentModel::as_select())
+            .order(payments::payment_id.desc())
+            .load(&mut conn)
+            .map_err(|e| {
+                error!(error = %e, order_id, owner_id, "Failed to fetch payments");
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to fetch payments: {}", e),
+                )
+            })?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn find_by_status(&self, status: PaymentStatus) -> Result<Vec<PaymentReview>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: ReviewRows = payments::table
+            .inner_join(orders::table)
+            .filter(payments::payment_status.eq(status))
+            .filter(payments::provider.is_null())
+            .select((
+                PaymentModel::as_select(),
+                orders::user_id,
+                orders::total_amount,
+                orders::deposit_amount,
+                orders::order_status,
+            ))
+            .order(payments::created_at.asc())
+            .load(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to fetch payments: {}", e),
+                )
+            })?;
+
+        Ok(into_reviews(rows))
+    }
+
+    async fn find_needing_refund(&self) -> Result<Vec<PaymentReview>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: ReviewRows = payments::table
+            .inner_join(orders::table)
+            .filter(payments::needs_refund.eq(true))
+            .select((
+                PaymentModel::as_select(),
+                orders::user_id,
+                orders::total_amount,
+                orders::deposit_amount,
+                orders::order_status,
+            ))
+            .order(payments::created_at.asc())
+            .load(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to fetch payments: {}", e),
+                )
+            })?;
+
+        Ok(into_reviews(rows))
+    }
+
+    async fn apply_provider_event(
+        &self,
+        provider: &str,
+        event_id: &str,
+        reference: &str,
+        status: ChargeStatus,
+    ) -> Result<Option<ReviewedPayment>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction(|conn| {
+            let payment: PaymentModel = payments::table
+                .filter(payments::provider.eq(provider))
+                .filter(payments::provider_reference.eq(reference))
+                .select(PaymentModel::as_select())
+                .for_update()
+                .first(conn)
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => {
+                        Error::with_message(ErrorCode::ResourceNotFound, "Payment not found")
+                    }
+                    _ => Error::with_message(
+                        ErrorCode::DatabaseError,
+                        format!("Database error: {}", e),
+                    ),
+                })?;
+
+            let recorded = diesel::insert_into(payment_events::table)
+                .values(&NewPaymentEventModel {
+                    provider,
+                    event_id,
+                    payment_id: payment.payment_id,
+                })
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+
+            if recorded == 0 || payment.payment_status != PaymentStatus::Pending {
+                return Ok(None);
+            }
+
+            let now = Utc::now().naive_utc();
+            let mut updated: PaymentModel = match status {
+                ChargeStatus::Pending => return Ok(None),
+                ChargeStatus::Succeeded => diesel::update(
+                    payments::table.filter(payments::payment_id.eq(payment.payment_id)),
+                )
+                .set((
+                    payments::payment_status.eq(PaymentStatus::Verified),
+                    payments::transferred_at.eq(Some(now)),
+                    payments::reviewed_by.eq(Some(provider)),
+                    payments::reviewed_at.eq(Some(now)),
+                ))
+                .returning(PaymentModel::as_returning())
+                .get_result(conn)?,
+                ChargeStatus::Failed => diesel::update(
+                    payments::table.filter(payments::payment_id.eq(payment.payment_id)),
+                )
+                .set((
+                    payments::payment_status.eq(PaymentStatus::Rejected),
+                    payments::rejection_reason.eq(Some("Declined by payment provider")),
+                    payments::reviewed_by.eq(Some(provider)),
+                    payments::reviewed_at.eq(Some(now)),
+                ))
+                .returning(PaymentModel::as_returning())
+                .get_result(conn)?,
+            };
+
+            let (user_id, order_status, deposit): (i64, OrderStatus, Option<BigDecimal>) =
+                orders::table
+                    .filter(orders::order_id.eq(payment.order_id))
+                    .select((
+                        orders::user_id,
+                        orders::order_status,
+                        orders::deposit_amount,
+                    ))
+                    .for_update()
+                    .first(conn)?;
+
+            if status == ChargeStatus::Succeeded {
+                if order_status == OrderStatus::PendingPayment {
+                    diesel::update(orders::table.filter(orders::order_id.eq(payment.order_id)))
+                        .set(orders::order_status.eq(OrderStatus::Confirmed))
+                        .execute(conn)?;
+                } else if !awaits_balance(order_status, deposit.as_ref()) {
+                    // The money was taken, so keep the payment and flag it for an admin to refund
+                    warn!(
+                        payment_id = payment.payment_id,
+                        order_id = payment.order_id,
+                        ?order_status,
+                        "Gateway payment succeeded for an order no longer awaiting payment"
+                    );
+                    updated = diesel::update(
+                        payments::table.filter(payments::payment_id.eq(payment.payment_id)),
+                    )
+                    .set(payments::needs_refund.eq(true))
+                    .returning(PaymentModel::as_returning())
+                    .get_result(conn)?;
+                }
+            }
+
+            Ok(Some(ReviewedPayment {
+                payment: updated.into(),
+                user_id,
+            }))
+        })
+    }
+
+    async fn verify(&self, payment_id: i64, reviewed_by: &str) -> Result<ReviewedPayment, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction(|conn| verify_pending(conn, payment_id, reviewed_by))
+    }
+
+    async fn reject(
+        &self,
+        payment_id: i64,
+        reviewed_by: &str,
+        reason: &str,
+    ) -> Result<ReviewedPayment, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction(|conn| {
+            let (_, user_id, _) = lock_pending(conn, payment_id)?;
+
+            let updated: PaymentModel =
+                diesel::update(payments::table.filter(payments::payment_id.eq(payment_id)))
+                    .set((
+                        payments::payment_status.eq(PaymentStatus::Rejected),
+                        payments::rejection_reason.eq(Some(reason)),
+                        payments::reviewed_by.eq(Some(reviewed_by)),
+                        payments::reviewed_at.eq(Some(Utc::now().naive_utc())),
+                    ))
+                    .returning(PaymentModel::as_returning())
+                    .get_result(conn)?;
+
+            Ok(ReviewedPayment {
+                payment: updated.into(),
+                user_id,
+            })
+        })
+    }
+
+    async fn record_statement(
+        &self,
+        format: StatementFormat,
+        entries: &[StatementEntry],
+    ) -> Result<Vec<RecordedEntry>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction(|conn| {
+            let mut occurrences: HashMap<(NaiveDateTime, BigDecimal, String), i16> = HashMap::new();
+            let mut recorded = Vec::with_capacity(entries.len());
+
+            for entry in entries {
+                let description = entry.description.clone().unwrap_or_default();
+                let occurrence = occurrences
+                    .entry((
+                        entry.transferred_at,
+                        entry.amount.normalized(),
+                        description.clone(),
+                    ))
+                    .or_default();
+                *occurrence += 1;
+
+                let key = statement_entries::bank
+                    .eq(format.as_str())
+                    .and(statement_entries::transferred_at.eq(entry.transferred_at))
+                    .and(statement_entries::amount.eq(&entry.amount))
+                    .and(statement_entries::description.eq(&description))
+                    .and(statement_entries::occurrence.eq(*occurrence));
+
+                diesel::insert_into(statement_entries::table)
+                    .values((
+                        statement_entries::bank.eq(format.as_str()),
+                        statement_entries::transferred_at.eq(entry.transferred_at),
+                        statement_entries::amount.eq(&entry.amount),
+                        statement_entries::description.eq(&description),
+                        statement_entries::occurrence.eq(*occurrence),
+                    ))
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+
+                let (entry_id, payment_id) = statement_entries::table
+                    .filter(key)
+                    .select((statement_entries::entry_id, statement_entries::payment_id))
+                    .first(conn)?;
+                recorded.push(RecordedEntry {
+                    entry_id,
+                    payment_id,
+                });
+            }
+
+            Ok(recorded)
+        })
+    }
+
+    async fn verify_with_entry(
+        &self,
+        payment_id: i64,
+        entry_id: i64,
+        reviewed_by: &str,
+    ) -> Result<ReviewedPayment, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction(|conn| {
+            let linked = diesel::update(
+                statement_entries::table
+                    .filter(statement_entries::entry_id.eq(entry_id))
+                    .filter(statement_entries::payment_id.is_null()),
+            )
+            .set(statement_entries::payment_id.eq(Some(payment_id)))
+            .execute(conn)?;
+            if linked == 0 {
+                return Err(Error::with_message(
+                    ErrorCode::ResourceAlreadyExists,
+                    "This deposit has already been matched to a payment",
+                ));
+            }
+
+            verify_pending(conn, payment_id, reviewed_by)
+        })
+    }
+}
@@ -0,0 +1,127 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+use super::reconciliation::EntryMatch;
+use super::statement::StatementEntry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::PaymentStatus"]
+pub enum PaymentStatus {
+    #[db_rename = "PENDING"]
+    Pending,
+    #[db_rename = "VERIFIED"]
+    Verified,
+    #[db_rename = "REJECTED"]
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payment {
+    pub payment_id: i64,
+    pub order_id: i64,
+    pub amount_paid: Option<BigDecimal>,
+    /// Where the slip is kept in the private bucket; served by `GET /payments/:id/slip`
+    pub slip_object: Option<String>,
+    pub payment_status: PaymentStatus,
+    pub transferred_at: Option<NaiveDateTime>,
+    pub submitted_by: Option<i64>,
+    pub rejection_reason: Option<String>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<NaiveDateTime>,
+    /// Gateway that took the payment; `None` for transfer slips
+    pub provider: Option<String>,
+    pub provider_reference: Option<String>,
+    pub created_at: NaiveDateTime,
+    /// Set when a gateway charge succeeded for an order no longer awaiting payment; cleared
+    /// once the payment is fully refunded
+    pub needs_refund: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewPayment {
+    pub order_id: i64,
+    pub amount_paid: BigDecimal,
+    pub slip_object: String,
+    pub transferred_at: NaiveDateTime,
+    pub submitted_by: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewGatewayPayment {
+    pub order_id: i64,
+    pub amount_paid: BigDecimal,
+    pub provider: String,
+    pub provider_reference: String,
+    pub submitted_by: i64,
+}
+
+// A gateway payment together with where the customer finishes paying
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayCharge {
+    pub payment: Payment,
+    pub redirect_url: Option<String>,
+}
+
+// Slip submitted by a customer, before it is stored
+#[derive(Debug, Clone)]
+pub struct PaymentSubmission {
+    pub amount_paid: BigDecimal,
+    pub transferred_at: NaiveDateTime,
+    pub slip_data: Vec<u8>,
+    pub slip_filename: String,
+}
+
+// A payment in the admin review queue with the order it pays for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentReview {
+    pub payment: Payment,
+    pub user_id: i64,
+    pub order_total: Option<BigDecimal>,
+    /// Set when the claimed amount differs from the order total
+    pub amount_mismatch: bool,
+}
+
+// Outcome of an admin decision, with the order owner to notify
+#[derive(Debug, Clone)]
+pub struct ReviewedPayment {
+    pub payment: Payment,
+    pub user_id: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectPaymentRequest {
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PaymentQueueQuery {
+    pub status: Option<PaymentStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementImportReport {
+    pub rows: usize,
+    /// Deposits an earlier import already matched to a payment; they are not matched again
+    pub already_matched: Vec<StatementEntry>,
+    pub verified: Vec<EntryMatch>,
+    pub ambiguous: Vec<EntryMatch>,
+    pub unmatched: Vec<EntryMatch>,
+    /// Matches that could not be verified, e.g. because the order was cancelled meanwhile
+    pub failed: Vec<FailedVerification>,
+}
+
+/// A statement deposit as saved by an import
+#[derive(Debug, Clone, Copy)]
+pub struct RecordedEntry {
+    pub entry_id: i64,
+    /// The payment the deposit verified, if any
+    pub payment_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedVerification {
+    pub result: EntryMatch,
+    pub error: String,
+}
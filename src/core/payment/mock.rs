@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bigdecimal::{BigDecimal, Zero};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tracing::{error, info};
+
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::provider::{CallbackEvent, Charge, ChargeStatus, PaymentProvider, ProviderRefund};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// What the mock gateway does with every charge once its delay has passed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MockOutcome {
+    Success,
+    Failure,
+    /// The charge never settles and no callback is sent
+    Pending,
+}
+
+impl FromStr for MockOutcome {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "success" => Ok(MockOutcome::Success),
+            "failure" => Ok(MockOutcome::Failure),
+            "pending" => Ok(MockOutcome::Pending),
+            _ => Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Unsupported mock payment outcome: {}", s),
+            )),
+        }
+    }
+}
+
+struct MockCharge {
+    amount: BigDecimal,
+    status: ChargeStatus,
+    refunded: BigDecimal,
+    /// Refund references by idempotency key
+    refunds: HashMap<String, String>,
+}
+
+/// In-memory gateway for local development. Charges settle after a fixed delay and the result
+/// is posted back to our own callback endpoint, signed like a real provider would.
+#[derive(Clone)]
+pub struct MockProvider {
+    secret: String,
+    outcome: MockOutcome,
+    delay: Duration,
+    callback_url: String,
+    charges: Arc<Mutex<HashMap<String, MockCharge>>>,
+    client: reqwest::Client,
+}
+
+impl MockProvider {
+    pub fn new(
+        secret: String,
+        outcome: MockOutcome,
+        delay: Duration,
+        callback_url: String,
+    ) -> Self {
+        Self {
+            secret,
+            outcome,
+            delay,
+            callback_url,
+            charges: Arc::new(Mutex::new(HashMap::new())),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn sign(&self, body: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn settle(&self, reference: String) {
+        let status = match self.outcome {
+            MockOutcome::Success => ChargeStatus::Succeeded,
+            MockOutcome::Failure => ChargeStatus::Failed,
+            MockOutcome::Pending => return,
+        };
+
+        let provider = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(provider.delay).await;
+
+            if let Some(charge) = provider.charges.lock().unwrap().get_mut(&reference) {
+                charge.status = status;
+            }
+
+            let event = CallbackEvent {
+                event_id: format!("evt_{}", uuid::Uuid::new_v4().simple()),
+                reference: reference.clone(),
+                status,
+            };
+            let body = match serde_json::to_vec(&event) {
+                Ok(body) => body,
+                Err(e) => {
+                    error!(error = %e, reference, "Failed to encode mock callback");
+                    return;
+                }
+            };
+            let signature = provider.sign(&body);
+
+            match provider
+                .client
+                .post(&provider.callback_url)
+                .header("Content-Type", "application/json")
+                .header(provider.signature_header(), signature)
+                .body(body)
+                .send()
+                .await
+            {
+                Ok(response) => {
+                    info!(reference, status = %response.status(), "Sent mock payment callback");
+                }
+                Err(e) => error!(error = %e, reference, "Failed to send mock payment callback"),
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl PaymentProvider for MockProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    fn signature_header(&self) -> &'static str {
+        "X-Signature"
+    }
+
+    async fn create_charge(&self, order_id: i64, amount: &BigDecimal) -> Result<Charge, Error> {
+        let reference = format!("mock_{}", uuid::Uuid::new_v4().simple());
+        self.charges.lock().unwrap().insert(
+            reference.clone(),
+            MockCharge {
+                amount: amount.clone(),
+                status: ChargeStatus::Pending,
+                refunded: BigDecimal::zero(),
+                refunds: HashMap::new(),
+            },
+        );
+        info!(order_id, reference, "Created mock charge");
+
+        self.settle(reference.clone());
+
+        Ok(Charge {
+            reference,
+            status: ChargeStatus::Pending,
+            redirect_url: None,
+        })
+    }
+
+    fn verify_callback(
+        &self,
+        signature: Option<&str>,
+        body: &[u8],
+    ) -> Result<CallbackEvent, Error> {
+        let invalid = || Error::with_message(ErrorCode::InvalidCredentials, "Invalid signature");
+
+        let signature =
+            hex::decode(signature.ok_or_else(invalid)?.trim()).map_err(|_| invalid())?;
+        let mut mac = HmacSha256::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(body);
+        mac.verify_slice(&signature).map_err(|_| invalid())?;
+
+        serde_json::from_slice(body).map_err(|e| {
+            Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Invalid callback body: {}", e),
+            )
+        })
+    }
+
+    async fn query_status(&self, reference: &str) -> Result<ChargeStatus, Error> {
+        self.charges
+            .lock()
+            .unwrap()
+            .get(reference)
+            .map(|charge| charge.status)
+            .ok_or_else(|| Error::with_message(ErrorCode::ResourceNotFound, "Charge not found"))
+    }
+
+    async fn refund(
+        &self,
+        reference: &str,
+        amount: &BigDecimal,
+        idempotency_key: &str,
+    ) -> Result<ProviderRefund, Error> {
+        let mut charges = self.charges.lock().unwrap();
+        let charge = charges
+            .get_mut(reference)
+            .ok_or_else(|| Error::with_message(ErrorCode::ResourceNotFound, "Charge not found"))?;
+
+        if let Some(refund_reference) = charge.refunds.get(idempotency_key) {
+            return Ok(ProviderRefund {
+                reference: refund_reference.clone(),
+                status: ChargeStatus::Succeeded,
+            });
+        }
+
+        if charge.status != ChargeStatus::Succeeded {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Only settled charges can be refunded",
+            ));
+        }
+        if &charge.refunded + amount > charge.amount {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Refund exceeds the charged amount",
+            ));
+        }
+
+        charge.refunded += amount;
+        let refund_reference = format!("mock_refund_{}", uuid::Uuid::new_v4().simple());
+        charge
+            .refunds
+            .insert(idempotency_key.to_string(), refund_reference.clone());
+        Ok(ProviderRefund {
+            reference: refund_reference,
+            status: ChargeStatus::Succeeded,
+        })
+    }
+}
@@ -0,0 +1,8 @@
+pub mod diesel;
+pub mod entity;
+pub mod mock;
+pub mod provider;
+pub mod reconciliation;
+pub mod repository;
+pub mod service;
+pub mod statement;
@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppConfig;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::mock::MockProvider;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChargeStatus {
+    Pending,
+    Succeeded,
+    Failed,
+}
+
+/// A charge opened at the provider for one order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Charge {
+    /// The provider's own ID for the charge
+    pub reference: String,
+    pub status: ChargeStatus,
+    /// Where the customer completes the payment, for providers with a hosted page
+    pub redirect_url: Option<String>,
+}
+
+/// A verified callback from the provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallbackEvent {
+    /// Stable across redeliveries of the same event, used to ignore replays
+    pub event_id: String,
+    pub reference: String,
+    pub status: ChargeStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderRefund {
+    pub reference: String,
+    pub status: ChargeStatus,
+}
+
+/// An online payment gateway. Orders only ever see `payments` rows, so swapping the provider
+/// does not touch order code.
+#[async_trait]
+pub trait PaymentProvider: Send + Sync {
+    /// Short identifier stored in `payments.provider` and used in the callback URL
+    fn name(&self) -> &'static str;
+    /// Header carrying the callback signature
+    fn signature_header(&self) -> &'static str;
+    async fn create_charge(&self, order_id: i64, amount: &BigDecimal) -> Result<Charge, Error>;
+    /// Checks the signature of a callback body and decodes it. Unsigned or tampered callbacks
+    /// are rejected with `InvalidCredentials`.
+    fn verify_callback(&self, signature: Option<&str>, body: &[u8])
+    -> Result<CallbackEvent, Error>;
+    async fn query_status(&self, reference: &str) -> Result<ChargeStatus, Error>;
+    /// Refunds part or all of a charge. Calls with the same `idempotency_key` refund once and
+    /// return the same refund. A `ValidationError` or `ResourceNotFound` means the provider
+    /// turned the refund down; any other error leaves the outcome unknown.
+    async fn refund(
+        &self,
+        reference: &str,
+        amount: &BigDecimal,
+        idempotency_key: &str,
+    ) -> Result<ProviderRefund, Error>;
+}
+
+/// Builds the provider selected by `PAYMENT_PROVIDER`, or `None` when online payment is off.
+pub fn from_config(cfg: &AppConfig) -> Result<Option<Arc<dyn PaymentProvider>>, Error> {
+    match cfg.payment_provider.as_deref().map(str::trim) {
+        None => Ok(None),
+        Some("mock") => {
+            let secret = cfg.mock_payment_secret.clone().ok_or_else(|| {
+                Error::with_message(
+                    ErrorCode::ValidationError,
+                    "The mock payment provider needs MOCK_PAYMENT_SECRET",
+                )
+            })?;
+            let provider = MockProvider::new(
+                secret,
+                cfg.mock_payment_outcome.parse()?,
+                std::time::Duration::from_secs(cfg.mock_payment_delay_secs),
+                format!("{}/payments/callbacks/mock", cfg.public_base_url),
+            );
+            Ok(Some(Arc::new(provider)))
+        }
+        Some(other) => Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Unknown payment provider: {}", other),
+        )),
+    }
+}
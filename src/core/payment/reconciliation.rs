@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use bigdecimal::BigDecimal;
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use super::statement::StatementEntry;
+
+/// A payment awaiting review, reduced to what statement matching needs
+#[derive(Debug, Clone)]
+pub struct PendingTransfer {
+    pub payment_id: i64,
+    pub order_id: i64,
+    pub amount_paid: Option<BigDecimal>,
+    pub transferred_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum EntryMatch {
+    Matched {
+        entry: StatementEntry,
+        payment_id: i64,
+        order_id: i64,
+    },
+    Ambiguous {
+        entry: StatementEntry,
+        candidate_payment_ids: Vec<i64>,
+    },
+    Unmatched {
+        entry: StatementEntry,
+    },
+}
+
+/// Pairs statement deposits with pending payments of the same amount whose claimed transfer
+/// time is within `window` of the deposit.
+///
+/// Only one-to-one pairings count as matched: a deposit with several candidate payments, or a
+/// payment claimed by several deposits, is reported as ambiguous for a human to resolve.
+pub fn match_entries(
+    entries: Vec<StatementEntry>,
+    pending: &[PendingTransfer],
+    window: Duration,
+) -> Vec<EntryMatch> {
+    let candidates: Vec<Vec<&PendingTransfer>> = entries
+        .iter()
+        .map(|entry| {
+            pending
+                .iter()
+                .filter(|p| p.amount_paid.as_ref() == Some(&entry.amount))
+                .filter(|p| {
+                    p.transferred_at
+                        .is_some_and(|at| (at - entry.transferred_at).abs() <= window)
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut claims: HashMap<i64, usize> = HashMap::new();
+    for found in &candidates {
+        for payment in found {
+            *claims.entry(payment.payment_id).or_default() += 1;
+        }
+    }
+
+    entries
+        .into_iter()
+        .zip(candidates)
+        .map(|(entry, found)| match found.as_slice() {
+            [] => EntryMatch::Unmatched { entry },
+            [payment] if claims.get(&payment.payment_id) == Some(&1) => EntryMatch::Matched {
+                entry,
+                payment_id: payment.payment_id,
+                order_id: payment.order_id,
+            },
+            _ => EntryMatch::Ambiguous {
+                entry,
+                candidate_payment_ids: found.iter().map(|p| p.payment_id).collect(),
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::core::payment::statement::{StatementFormat, parser_for};
+
+    const KBANK: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/statements/kbank.csv"
+    ));
+    const SCB: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/statements/scb.csv"
+    ));
+
+    fn at(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn pending(payment_id: i64, amount: &str, transferred_at: &str) -> PendingTransfer {
+        PendingTransfer {
+            payment_id,
+            order_id: payment_id * 10,
+            amount_paid: Some(BigDecimal::from_str(amount).unwrap()),
+            transferred_at: Some(at(transferred_at)),
+        }
+    }
+
+    fn entry(amount: &str, transferred_at: &str) -> StatementEntry {
+        StatementEntry {
+            line: 1,
+            transferred_at: at(transferred_at),
+            amount: BigDecimal::from_str(amount).unwrap(),
+            description: None,
+        }
+    }
+
+    // (line, outcome) pairs, where the outcome is the matched payment, the candidates of an
+    // ambiguous entry or nothing for an unmatched one
+    fn outcomes(results: &[EntryMatch]) -> Vec<(usize, &'static str, Vec<i64>)> {
+        results
+            .iter()
+            .map(|result| match result {
+                EntryMatch::Matched {
+                    entry, payment_id, ..
+                } => (entry.line, "matched", vec![*payment_id]),
+                EntryMatch::Ambiguous {
+                    entry,
+                    candidate_payment_ids,
+                } => (entry.line, "ambiguous", candidate_payment_ids.clone()),
+                EntryMatch::Unmatched { entry } => (entry.line, "unmatched", Vec::new()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn kbank_statement_against_pending_payments() {
+        let entries = parser_for(StatementFormat::Kbank).parse(KBANK).unwrap();
+        let pending = [
+            pending(1, "1250", "2025-12-01 09:20:00"),
+            pending(2, "350.00", "2025-12-01 10:00:00"),
+            pending(3, "350.00", "2025-12-01 10:10:00"),
+        ];
+
+        let results = match_entries(entries, &pending, Duration::minutes(30));
+
+        assert_eq!(
+            outcomes(&results),
+            vec![
+                (6, "matched", vec![1]),
+                (7, "ambiguous", vec![2, 3]),
+                // Same amount as payments 2 and 3, but a day later
+                (9, "unmatched", vec![]),
+                (10, "unmatched", vec![]),
+            ]
+        );
+        let EntryMatch::Matched { order_id, .. } = &results[0] else {
+            panic!("expected a match");
+        };
+        assert_eq!(*order_id, 10);
+    }
+
+    #[test]
+    fn payment_claimed_by_two_deposits_is_ambiguous() {
+        let entries = parser_for(StatementFormat::Scb).parse(SCB).unwrap();
+        let pending = [
+            pending(4, "500.00", "2025-12-03 14:05:00"),
+            pending(5, "2000.50", "2025-12-03 16:15:00"),
+        ];
+
+        let results = match_entries(entries, &pending, Duration::minutes(30));
+
+        assert_eq!(
+            outcomes(&results),
+            vec![
+                (2, "ambiguous", vec![4]),
+                (3, "ambiguous", vec![4]),
+                (4, "matched", vec![5]),
+            ]
+        );
+    }
+
+    #[test]
+    fn window_edge_is_inclusive() {
+        let window = Duration::minutes(30);
+        let entries = vec![entry("100.00", "2025-12-01 12:00:00")];
+
+        let before = [pending(1, "100.00", "2025-12-01 11:30:00")];
+        let after = [pending(1, "100.00", "2025-12-01 12:30:00")];
+        assert_eq!(
+            outcomes(&match_entries(entries.clone(), &before, window)),
+            vec![(1, "matched", vec![1])]
+        );
+        assert_eq!(
+            outcomes(&match_entries(entries.clone(), &after, window)),
+            vec![(1, "matched", vec![1])]
+        );
+
+        let outside = [pending(1, "100.00", "2025-12-01 12:30:01")];
+        assert_eq!(
+            outcomes(&match_entries(entries, &outside, window)),
+            vec![(1, "unmatched", vec![])]
+        );
+    }
+
+    #[test]
+    fn payments_without_amount_or_time_never_match() {
+        let entries = vec![entry("100.00", "2025-12-01 12:00:00")];
+        let mut no_time = pending(1, "100.00", "2025-12-01 12:00:00");
+        no_time.transferred_at = None;
+        let mut no_amount = pending(2, "100.00", "2025-12-01 12:00:00");
+        no_amount.amount_paid = None;
+
+        let results = match_entries(entries, &[no_time, no_amount], Duration::minutes(30));
+
+        assert_eq!(outcomes(&results), vec![(1, "unmatched", vec![])]);
+    }
+}
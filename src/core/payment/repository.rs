@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+
+use crate::utils::errors::Error;
+
+use super::entity::{
+    NewGatewayPayment, NewPayment, Payment, PaymentReview, PaymentStatus, RecordedEntry,
+    ReviewedPayment,
+};
+use super::provider::ChargeStatus;
+use super::statement::{StatementEntry, StatementFormat};
+
+#[async_trait]
+pub trait PaymentRepository: Send + Sync {
+    /// Checks that `user_id` may pay for the order right now: the order is theirs, awaits
+    /// payment, and has no payment pending review or already verified. Returns the amount due,
+    /// which is the deposit for orders with deposit pre-orders. Once such an order is
+    /// confirmed, its remaining balance is payable the same way.
+    async fn ensure_payable(
+        &self,
+        order_id: i64,
+        user_id: i64,
+    ) -> Result<Option<BigDecimal>, Error>;
+    /// Repeats the `ensure_payable` checks under a row lock and inserts a `PENDING` payment.
+    async fn create_pending(&self, new_payment: NewPayment) -> Result<Payment, Error>;
+    /// Same as `create_pending`, for a charge already opened at a payment provider.
+    async fn create_gateway_payment(
+        &self,
+        new_payment: NewGatewayPayment,
+    ) -> Result<Payment, Error>;
+    async fn find_by_id(&self, payment_id: i64) -> Result<Payment, Error>;
+    /// The payment together with the ID of the customer whose order it pays for
+    async fn find_with_owner(&self, payment_id: i64) -> Result<(Payment, i64), Error>;
+    async fn find_by_order(&self, order_id: i64, user_id: i64) -> Result<Vec<Payment>, Error>;
+    /// Slip payments in the given status; gateway payments are settled by their provider.
+    async fn find_by_status(&self, status: PaymentStatus) -> Result<Vec<PaymentReview>, Error>;
+    /// Payments flagged with `needs_refund`, oldest first.
+    async fn find_needing_refund(&self) -> Result<Vec<PaymentReview>, Error>;
+    /// Applies a provider's charge status to the payment it refers to. Each `event_id` is
+    /// applied once; replays and events for payments that are no longer pending return `None`.
+    /// A success for an order no longer awaiting payment is still recorded, and the payment
+    /// is flagged with `needs_refund`.
+    async fn apply_provider_event(
+        &self,
+        provider: &str,
+        event_id: &str,
+        reference: &str,
+        status: ChargeStatus,
+    ) -> Result<Option<ReviewedPayment>, Error>;
+    /// Marks a pending payment as verified and confirms its order; a balance payment leaves
+    /// the already confirmed order as it is.
+    async fn verify(&self, payment_id: i64, reviewed_by: &str) -> Result<ReviewedPayment, Error>;
+    async fn reject(
+        &self,
+        payment_id: i64,
+        reviewed_by: &str,
+        reason: &str,
+    ) -> Result<ReviewedPayment, Error>;
+    /// Saves the statement's deposits, keeping the ones an earlier import already saved, and
+    /// returns them in the same order.
+    async fn record_statement(
+        &self,
+        format: StatementFormat,
+        entries: &[StatementEntry],
+    ) -> Result<Vec<RecordedEntry>, Error>;
+    /// Verifies the payment as `verify` does and links the deposit to it, failing if the
+    /// deposit has meanwhile been linked to another payment.
+    async fn verify_with_entry(
+        &self,
+        payment_id: i64,
+        entry_id: i64,
+        reviewed_by: &str,
+    ) -> Result<ReviewedPayment, Error>;
+}
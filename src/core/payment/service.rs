@@ -0,0 +1,418 @@
+use std::sync::Arc;
+
+use bigdecimal::{BigDecimal, Zero};
+use tracing::error;
+
+use crate::core::notification::entity::{NewNotification, NotificationType};
+use crate::core::notification::service::NotificationService;
+use crate::utils::errors::{Error, ErrorCode};
+use crate::utils::storage::StorageService;
+
+use super::entity::{
+    FailedVerification, GatewayCharge, NewGatewayPayment, NewPayment, Payment, PaymentReview,
+    PaymentStatus, PaymentSubmission, ReviewedPayment, StatementImportReport,
+};
+use super::provider::PaymentProvider;
+use super::reconciliation::{EntryMatch, PendingTransfer, match_entries};
+use super::repository::PaymentRepository;
+use super::statement::{StatementFormat, parser_for};
+
+// Slips show bank account details, so they go to the private bucket
+const SLIP_FOLDER: &str = "payment-slips";
+const MAX_SLIP_SIZE: usize = 10 * 1024 * 1024;
+
+#[derive(Clone)]
+pub struct PaymentService {
+    repo: Arc<dyn PaymentRepository>,
+    storage: StorageService,
+    notifications: NotificationService,
+    provider: Option<Arc<dyn PaymentProvider>>,
+}
+
+impl PaymentService {
+    pub fn new(
+        repo: Arc<dyn PaymentRepository>,
+        storage: StorageService,
+        notifications: NotificationService,
+        provider: Option<Arc<dyn PaymentProvider>>,
+    ) -> Self {
+        Self {
+            repo,
+            storage,
+            notifications,
+            provider,
+        }
+    }
+
+    fn provider(&self) -> Result<&Arc<dyn PaymentProvider>, Error> {
+        self.provider.as_ref().ok_or_else(|| {
+            Error::with_message(
+                ErrorCode::ValidationError,
+                "Online payment is not available",
+            )
+        })
+    }
+
+    /// Opens a charge for the full order total at the configured provider.
+    pub async fn create_charge(&self, order_id: i64, user_id: i64) -> Result<GatewayCharge, Error> {
+        let provider = self.provider()?;
+
+        let Some(amount) = self.repo.ensure_payable(order_id, user_id).await? else {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Order has no total amount",
+            ));
+        };
+
+        let charge = provider.create_charge(order_id, &amount).await?;
+        let payment = self
+            .repo
+            .create_gateway_payment(NewGatewayPayment {
+                order_id,
+                amount_paid: amount,
+                provider: provider.name().to_string(),
+                provider_reference: charge.reference.clone(),
+                submitted_by: user_id,
+            })
+            .await
+            .inspect_err(|e| {
+                error!(error = %e, order_id, reference = charge.reference, "Charge opened but payment not recorded");
+            })?;
+
+        Ok(GatewayCharge {
+            payment,
+            redirect_url: charge.redirect_url,
+        })
+    }
+
+    /// Verifies and applies a provider callback. Replayed callbacks succeed without effect.
+    pub async fn handle_callback(
+        &self,
+        provider_name: &str,
+        signature: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), Error> {
+        let provider = self
+            .provider
+            .as_ref()
+            .filter(|provider| provider.name() == provider_name)
+            .ok_or_else(|| {
+                Error::with_message(ErrorCode::ResourceNotFound, "Unknown payment provider")
+            })?;
+
+        let event = provider.verify_callback(signature, body)?;
+        let reviewed = self
+            .repo
+            .apply_provider_event(
+                provider.name(),
+                &event.event_id,
+                &event.reference,
+                event.status,
+            )
+            .await?;
+
+        if let Some(reviewed) = reviewed {
+            self.notify_settled(&reviewed).await;
+        }
+        Ok(())
+    }
+
+    /// Asks the provider for the current state of a pending gateway payment, for callbacks
+    /// that never arrived.
+    pub async fn sync_payment(&self, payment_id: i64) -> Result<Payment, Error> {
+        let provider = self.provider()?;
+        let payment = self.repo.find_by_id(payment_id).await?;
+
+        let Some(reference) = payment
+            .provider_reference
+            .as_deref()
+            .filter(|_| payment.provider.as_deref() == Some(provider.name()))
+        else {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Payment was not made through the configured provider",
+            ));
+        };
+
+        if payment.payment_status != PaymentStatus::Pending {
+            return Ok(payment);
+        }
+
+        let status = provider.query_status(reference).await?;
+        // Keyed by outcome so repeated syncs with the same answer are recorded once
+        let event_id = format!("status-query:{}:{:?}", reference, status);
+        match self
+            .repo
+            .apply_provider_event(provider.name(), &event_id, reference, status)
+            .await?
+        {
+            Some(reviewed) => {
+                self.notify_settled(&reviewed).await;
+                Ok(reviewed.payment)
+            }
+            None => self.repo.find_by_id(payment_id).await,
+        }
+    }
+
+    async fn notify_settled(&self, reviewed: &ReviewedPayment) {
+        let order_id = reviewed.payment.order_id;
+        let (notification_type, message) = match reviewed.payment.payment_status {
+            PaymentStatus::Verified if reviewed.payment.needs_refund => (
+                NotificationType::PaymentVerified,
+                format!(
+                    "Your payment for order #{} arrived after the order was closed and will be refunded",
+                    order_id
+                ),
+            ),
+            PaymentStatus::Verified => (
+                NotificationType::PaymentVerified,
+                format!("Your payment for order #{} was successful", order_id),
+            ),
+            PaymentStatus::Rejected => (
+                NotificationType::PaymentRejected,
+                format!("Your payment for order #{} was declined", order_id),
+            ),
+            PaymentStatus::Pending => return,
+        };
+        self.notify(reviewed, notification_type, message).await;
+    }
+
+    pub async fn submit_payment(
+        &self,
+        order_id: i64,
+        user_id: i64,
+        submission: PaymentSubmission,
+    ) -> Result<Payment, Error> {
+        if order_id <= 0 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Invalid order ID",
+            ));
+        }
+
+        if submission.amount_paid <= BigDecimal::zero() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Amount paid must be greater than 0",
+            ));
+        }
+
+        if submission.slip_data.is_empty() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Payment slip is required",
+            ));
+        }
+
+        if submission.slip_data.len() > MAX_SLIP_SIZE {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Payment slip must be 10MB or smaller",
+            ));
+        }
+
+        let mime = mime_guess::from_path(&submission.slip_filename).first_or_octet_stream();
+        if mime.type_() != mime_guess::mime::IMAGE {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Payment slip must be an image",
+            ));
+        }
+
+        // Fail fast before uploading anything for an order that cannot be paid
+        self.repo.ensure_payable(order_id, user_id).await?;
+
+        let slip_object = Box::pin(self.storage.upload_private_file(
+            submission.slip_data,
+            &submission.slip_filename,
+            SLIP_FOLDER,
+        ))
+        .await
+        .map_err(|e| {
+            error!(error = %e, order_id, "Failed to upload payment slip");
+            Error::with_message(ErrorCode::InternalError, "Failed to upload payment slip")
+        })?;
+
+        self.repo
+            .create_pending(NewPayment {
+                order_id,
+                amount_paid: submission.amount_paid,
+                slip_object,
+                transferred_at: submission.transferred_at,
+                submitted_by: user_id,
+            })
+            .await
+    }
+
+    pub async fn list_payments(&self, order_id: i64, user_id: i64) -> Result<Vec<Payment>, Error> {
+        self.repo.find_by_order(order_id, user_id).await
+    }
+
+    /// The payment's slip and its content type, for the customer who paid and admins
+    pub async fn slip(
+        &self,
+        payment_id: i64,
+        user_id: i64,
+        is_admin: bool,
+    ) -> Result<(Vec<u8>, String), Error> {
+        let (payment, owner_id) = self.repo.find_with_owner(payment_id).await?;
+        let slip_object = payment
+            .slip_object
+            .filter(|_| owner_id == user_id || is_admin)
+            .ok_or_else(|| Error::with_message(ErrorCode::ResourceNotFound, "Slip not found"))?;
+
+        Box::pin(self.storage.read_private_file(&slip_object))
+            .await
+            .map_err(|e| {
+                error!(error = %e, payment_id, "Failed to read payment slip");
+                Error::with_message(ErrorCode::InternalError, "Failed to read payment slip")
+            })
+    }
+
+    pub async fn review_queue(&self, status: PaymentStatus) -> Result<Vec<PaymentReview>, Error> {
+        self.repo.find_by_status(status).await
+    }
+
+    /// Gateway payments that succeeded after their order stopped awaiting payment
+    pub async fn refunds_due(&self) -> Result<Vec<PaymentReview>, Error> {
+        self.repo.find_needing_refund().await
+    }
+
+    pub async fn verify_payment(
+        &self,
+        payment_id: i64,
+        reviewed_by: &str,
+    ) -> Result<Payment, Error> {
+        let reviewed = self.repo.verify(payment_id, reviewed_by).await?;
+        self.notify_verified(&reviewed).await;
+        Ok(reviewed.payment)
+    }
+
+    pub async fn reject_payment(
+        &self,
+        payment_id: i64,
+        reviewed_by: &str,
+        reason: &str,
+    ) -> Result<Payment, Error> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Rejection reason is required",
+            ));
+        }
+
+        let reviewed = self.repo.reject(payment_id, reviewed_by, reason).await?;
+        let message = format!(
+            "Your payment for order #{} was rejected: {}",
+            reviewed.payment.order_id, reason
+        );
+        self.notify(&reviewed, NotificationType::PaymentRejected, message)
+            .await;
+        Ok(reviewed.payment)
+    }
+
+    /// Matches a bank statement against pending payments and verifies every unambiguous match.
+    ///
+    /// Deposits are saved as they are imported, so one that already verified a payment is
+    /// skipped when an overlapping statement is imported again.
+    pub async fn import_statement(
+        &self,
+        format: StatementFormat,
+        data: &[u8],
+        window: chrono::Duration,
+        reviewed_by: &str,
+    ) -> Result<StatementImportReport, Error> {
+        let entries = parser_for(format).parse(data)?;
+        let rows = entries.len();
+        let recorded = self.repo.record_statement(format, &entries).await?;
+
+        let mut report = StatementImportReport {
+            rows,
+            already_matched: Vec::new(),
+            verified: Vec::new(),
+            ambiguous: Vec::new(),
+            unmatched: Vec::new(),
+            failed: Vec::new(),
+        };
+
+        let mut fresh = Vec::with_capacity(entries.len());
+        let mut entry_ids = Vec::with_capacity(entries.len());
+        for (entry, recorded) in entries.into_iter().zip(recorded) {
+            if recorded.payment_id.is_some() {
+                report.already_matched.push(entry);
+            } else {
+                fresh.push(entry);
+                entry_ids.push(recorded.entry_id);
+            }
+        }
+
+        let pending: Vec<PendingTransfer> = self
+            .repo
+            .find_by_status(PaymentStatus::Pending)
+            .await?
+            .into_iter()
+            .map(|review| PendingTransfer {
+                payment_id: review.payment.payment_id,
+                order_id: review.payment.order_id,
+                amount_paid: review.payment.amount_paid,
+                transferred_at: review.payment.transferred_at,
+            })
+            .collect();
+
+        for (result, entry_id) in match_entries(fresh, &pending, window)
+            .into_iter()
+            .zip(entry_ids)
+        {
+            match result {
+                EntryMatch::Matched { payment_id, .. } => {
+                    match self
+                        .repo
+                        .verify_with_entry(payment_id, entry_id, reviewed_by)
+                        .await
+                    {
+                        Ok(reviewed) => {
+                            self.notify_verified(&reviewed).await;
+                            report.verified.push(result);
+                        }
+                        Err(e) => report.failed.push(FailedVerification {
+                            result,
+                            error: e.to_string(),
+                        }),
+                    }
+                }
+                EntryMatch::Ambiguous { .. } => report.ambiguous.push(result),
+                EntryMatch::Unmatched { .. } => report.unmatched.push(result),
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn notify_verified(&self, reviewed: &ReviewedPayment) {
+        let message = format!(
+            "Your payment for order #{} has been verified",
+            reviewed.payment.order_id
+        );
+        self.notify(reviewed, NotificationType::PaymentVerified, message)
+            .await;
+    }
+
+    // The review decision is already committed, so a failed notification is only logged
+    async fn notify(
+        &self,
+        reviewed: &ReviewedPayment,
+        notification_type: NotificationType,
+        message: String,
+    ) {
+        let notification = NewNotification {
+            user_id: reviewed.user_id,
+            order_id: Some(reviewed.payment.order_id),
+            notification_type,
+            message,
+        };
+        if let Err(e) = self.notifications.notify(notification).await {
+            error!(error = %e, payment_id = reviewed.payment.payment_id, "Failed to send payment notification");
+        }
+    }
+}
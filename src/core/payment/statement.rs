@@ -0,0 +1,348 @@
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::errors::{Error, ErrorCode};
+
+/// An incoming transfer read from a bank statement export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementEntry {
+    /// 1-based line number in the uploaded file, for reporting back to the admin
+    pub line: usize,
+    pub transferred_at: NaiveDateTime,
+    pub amount: BigDecimal,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementFormat {
+    Kbank,
+    Scb,
+}
+
+impl StatementFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatementFormat::Kbank => "kbank",
+            StatementFormat::Scb => "scb",
+        }
+    }
+}
+
+impl FromStr for StatementFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "kbank" => Ok(StatementFormat::Kbank),
+            "scb" => Ok(StatementFormat::Scb),
+            _ => Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Unsupported statement format: {}", s),
+            )),
+        }
+    }
+}
+
+pub trait StatementParser: Send + Sync {
+    /// Returns the deposits in the statement; withdrawals are skipped.
+    fn parse(&self, data: &[u8]) -> Result<Vec<StatementEntry>, Error>;
+}
+
+pub fn parser_for(format: StatementFormat) -> Box<dyn StatementParser> {
+    match format {
+        StatementFormat::Kbank => Box::new(KbankCsvParser),
+        StatementFormat::Scb => Box::new(ScbCsvParser),
+    }
+}
+
+/// Kasikornbank (K PLUS / K-BIZ) CSV export.
+///
+/// The file starts with account details; the transaction table begins at the header row with
+/// `Date`, `Time`, `Deposit` and `Description` columns, and dates are written `dd-mm-yy`.
+pub struct KbankCsvParser;
+
+impl StatementParser for KbankCsvParser {
+    fn parse(&self, data: &[u8]) -> Result<Vec<StatementEntry>, Error> {
+        parse_csv(
+            data,
+            &CsvLayout {
+                date: "Date",
+                time: "Time",
+                deposit: "Deposit",
+                description: "Description",
+                date_formats: &["%d-%m-%Y"],
+            },
+        )
+    }
+}
+
+/// SCB Easy / SCB Business Net CSV export, with `dd/mm/yyyy` dates and a `Deposit` column.
+pub struct ScbCsvParser;
+
+impl StatementParser for ScbCsvParser {
+    fn parse(&self, data: &[u8]) -> Result<Vec<StatementEntry>, Error> {
+        parse_csv(
+            data,
+            &CsvLayout {
+                date: "Date",
+                time: "Time",
+                deposit: "Deposit",
+                description: "Description",
+                date_formats: &["%d/%m/%Y"],
+            },
+        )
+    }
+}
+
+struct CsvLayout {
+    date: &'static str,
+    time: &'static str,
+    deposit: &'static str,
+    description: &'static str,
+    /// Formats with a four-digit year; two-digit years are expanded before parsing
+    date_formats: &'static [&'static str],
+}
+
+fn parse_csv(data: &[u8], layout: &CsvLayout) -> Result<Vec<StatementEntry>, Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data);
+
+    let mut columns: Option<(usize, usize, usize, Option<usize>)> = None;
+    let mut entries = Vec::new();
+    let mut line = 1;
+    let mut scanned = 0;
+
+    for (index, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| {
+            Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Record {}: invalid CSV: {}", index + 1, e),
+            )
+        })?;
+        // The reader's own line count is thrown off by blank lines and CRLF endings, so count
+        // the line breaks before the record instead. Its position may point at the end of
+        // the previous line.
+        let mut start = record
+            .position()
+            .and_then(|position| usize::try_from(position.byte()).ok())
+            .map_or(scanned, |byte| byte.clamp(scanned, data.len()));
+        while matches!(data.get(start), Some(b'\r' | b'\n')) {
+            start += 1;
+        }
+        line += data[scanned..start].split(|&b| b == b'\n').count() - 1;
+        scanned = start;
+
+        // Skip the preamble until the transaction table header
+        let Some((date_col, time_col, deposit_col, description_col)) = columns else {
+            let find = |name: &str| {
+                record.iter().position(|field| {
+                    field
+                        .trim_start_matches('\u{feff}')
+                        .eq_ignore_ascii_case(name)
+                })
+            };
+            if let (Some(date), Some(time), Some(deposit)) =
+                (find(layout.date), find(layout.time), find(layout.deposit))
+            {
+                columns = Some((date, time, deposit, find(layout.description)));
+            }
+            continue;
+        };
+
+        let deposit = record.get(deposit_col).unwrap_or_default();
+        let Some(amount) = parse_amount(deposit) else {
+            continue;
+        };
+        if amount <= BigDecimal::zero() {
+            continue;
+        }
+
+        let date_text = record.get(date_col).unwrap_or_default();
+        let time_text = record.get(time_col).unwrap_or_default();
+        let transferred_at = parse_timestamp(date_text, time_text, layout.date_formats)
+            .ok_or_else(|| {
+                Error::with_message(
+                    ErrorCode::ValidationError,
+                    format!(
+                        "Line {}: invalid date/time '{} {}'",
+                        line, date_text, time_text
+                    ),
+                )
+            })?;
+
+        entries.push(StatementEntry {
+            line,
+            transferred_at,
+            amount,
+            description: description_col
+                .and_then(|col| record.get(col))
+                .filter(|value| !value.is_empty())
+                .map(ToString::to_string),
+        });
+    }
+
+    if columns.is_none() {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Statement header row not found",
+        ));
+    }
+
+    Ok(entries)
+}
+
+fn parse_amount(value: &str) -> Option<BigDecimal> {
+    let cleaned: String = value.chars().filter(|c| *c != ',' && *c != ' ').collect();
+    if cleaned.is_empty() || cleaned == "-" {
+        return None;
+    }
+    BigDecimal::from_str(&cleaned).ok()
+}
+
+fn parse_timestamp(date: &str, time: &str, formats: &[&str]) -> Option<NaiveDateTime> {
+    let date = expand_short_year(date);
+    let date = formats
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(&date, format).ok())?;
+
+    // Thai banks often print Buddhist Era years (CE + 543)
+    let year = date.year();
+    let date = if year > 2400 {
+        date.with_year(year - 543)?
+    } else {
+        date
+    };
+
+    let time = NaiveTime::parse_from_str(time, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+        .ok()?;
+
+    Some(date.and_time(time))
+}
+
+/// Two-digit years are always Buddhist Era 25xx; chrono's `%y` would read "70" to "99" as 19xx.
+fn expand_short_year(date: &str) -> String {
+    let year_start = date
+        .rfind(|c: char| !c.is_ascii_digit())
+        .map_or(0, |i| i + 1);
+    let (day_month, year) = date.split_at(year_start);
+    if year_start > 0 && year.len() == 2 {
+        format!("{}25{}", day_month, year)
+    } else {
+        date.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KBANK: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/statements/kbank.csv"
+    ));
+    const SCB: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/statements/scb.csv"
+    ));
+
+    fn at(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn amount(value: &str) -> BigDecimal {
+        BigDecimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn kbank_reads_deposits_after_the_preamble() {
+        let entries = parser_for(StatementFormat::Kbank).parse(KBANK).unwrap();
+
+        let summary: Vec<(usize, NaiveDateTime, BigDecimal)> = entries
+            .iter()
+            .map(|entry| (entry.line, entry.transferred_at, entry.amount.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (6, at("2025-12-01 09:15:00"), amount("1250.00")),
+                (7, at("2025-12-01 10:02:00"), amount("350.00")),
+                (9, at("2025-12-02 18:45:10"), amount("350.00")),
+                (10, at("2025-12-02 23:59:00"), amount("99.00")),
+            ]
+        );
+        assert_eq!(
+            entries[0].description.as_deref(),
+            Some("Transfer from SOMCHAI J")
+        );
+    }
+
+    #[test]
+    fn scb_reads_deposits_and_skips_withdrawals() {
+        let entries = parser_for(StatementFormat::Scb).parse(SCB).unwrap();
+
+        let summary: Vec<(usize, NaiveDateTime, BigDecimal)> = entries
+            .iter()
+            .map(|entry| (entry.line, entry.transferred_at, entry.amount.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (2, at("2025-12-03 14:00:00"), amount("500.00")),
+                (3, at("2025-12-03 14:10:00"), amount("500.00")),
+                (4, at("2025-12-03 16:20:30"), amount("2000.50")),
+            ]
+        );
+        assert_eq!(entries[2].description, None);
+    }
+
+    #[test]
+    fn buddhist_era_years_are_converted() {
+        let formats = &["%d-%m-%Y", "%d/%m/%Y"];
+        let expected = Some(at("2025-12-01 09:15:00"));
+
+        assert_eq!(parse_timestamp("01-12-68", "09:15", formats), expected);
+        assert_eq!(parse_timestamp("01-12-2568", "09:15", formats), expected);
+        assert_eq!(parse_timestamp("01/12/2568", "09:15:00", formats), expected);
+        assert_eq!(parse_timestamp("01/12/2025", "09:15", formats), expected);
+    }
+
+    #[test]
+    fn two_digit_years_are_buddhist_era() {
+        let formats = &["%d-%m-%Y"];
+
+        assert_eq!(
+            parse_timestamp("01-01-69", "00:00", formats),
+            Some(at("2026-01-01 00:00:00"))
+        );
+        assert_eq!(
+            parse_timestamp("01-01-70", "00:00", formats),
+            Some(at("2027-01-01 00:00:00"))
+        );
+        assert_eq!(
+            parse_timestamp("01-01-99", "00:00", formats),
+            Some(at("2056-01-01 00:00:00"))
+        );
+    }
+
+    #[test]
+    fn missing_header_is_rejected() {
+        let data = b"01-12-68,09:15,Transfer,,100.00,100.00\n";
+        let err = parser_for(StatementFormat::Kbank).parse(data).unwrap_err();
+        assert_eq!(err.code, ErrorCode::ValidationError);
+    }
+
+    #[test]
+    fn invalid_date_reports_its_line() {
+        let data = b"Date,Time,Deposit,Description\n31-02-68,09:15,100.00,Transfer\n";
+        let err = parser_for(StatementFormat::Kbank).parse(data).unwrap_err();
+        assert!(err.message.starts_with("Line 2:"), "{}", err.message);
+    }
+}
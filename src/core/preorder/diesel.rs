@@ -0,0 +1,310 @@
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use diesel::dsl::sum;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Nullable, Text};
+
+use crate::core::order::entity::OrderStatus;
+use crate::core::product::entity::ProductStatus;
+use crate::schema::{order_items, orders, preorder_campaigns, products, variants};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{
+    PreorderCampaign, PreorderCampaignRequest, PreorderPaymentMode, PreorderTerms,
+    PreorderVariantLine,
+};
+use super::repository::PreorderRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = preorder_campaigns)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct CampaignModel {
+    pub product_id: i64,
+    pub opens_at: NaiveDateTime,
+    pub closes_at: NaiveDateTime,
+    pub quota: Option<i32>,
+    pub estimated_delivery: Option<NaiveDate>,
+    pub payment_mode: PreorderPaymentMode,
+    pub deposit_percent: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+// Written as a whole, so leaving out the quota or delivery date clears it
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = preorder_campaigns)]
+#[diesel(treat_none_as_null = true)]
+struct CampaignChangeset {
+    pub product_id: i64,
+    pub opens_at: NaiveDateTime,
+    pub closes_at: NaiveDateTime,
+    pub quota: Option<i32>,
+    pub estimated_delivery: Option<NaiveDate>,
+    pub payment_mode: PreorderPaymentMode,
+    pub deposit_percent: Option<i32>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(QueryableByName)]
+struct VariantQuantityRow {
+    #[diesel(sql_type = BigInt)]
+    variant_id: i64,
+    #[diesel(sql_type = Nullable<Text>)]
+    sku: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    size: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    color: Option<String>,
+    #[diesel(sql_type = BigInt)]
+    confirmed_quantity: i64,
+    #[diesel(sql_type = BigInt)]
+    pending_quantity: i64,
+}
+
+impl CampaignModel {
+    fn into_campaign(self, reserved: i64) -> PreorderCampaign {
+        PreorderCampaign {
+            product_id: self.product_id,
+            opens_at: self.opens_at,
+            closes_at: self.closes_at,
+            quota: self.quota,
+            reserved,
+            estimated_delivery: self.estimated_delivery,
+            payment_mode: self.payment_mode,
+            deposit_percent: self.deposit_percent,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl From<VariantQuantityRow> for PreorderVariantLine {
+    fn from(row: VariantQuantityRow) -> Self {
+        PreorderVariantLine {
+            variant_id: row.variant_id,
+            sku: row.sku,
+            size: row.size,
+            color: row.color,
+            confirmed_quantity: row.confirmed_quantity,
+            pending_quantity: row.pending_quantity,
+        }
+    }
+}
+
+// Orders that no longer hold their pre-ordered units
+const RELEASED_STATUSES: [OrderStatus; 2] = [OrderStatus::Cancelled, OrderStatus::Refunded];
+
+// Binds: $1 the product. Units in cancelled or refunded orders are not counted.
+const VARIANT_QUANTITIES: &str = "SELECT v.variant_id, v.sku, v.size, v.color, \
+         COALESCE(SUM(oi.quantity) FILTER (WHERE o.order_status \
+             IN ('CONFIRMED', 'SHIPPING', 'COMPLETED', 'PARTIALLY_REFUNDED')), 0)::BIGINT \
+             AS confirmed_quantity, \
+         COALESCE(SUM(oi.quantity) FILTER (WHERE o.order_status = 'PENDING_PAYMENT'), 0)::BIGINT \
+             AS pending_quantity \
+     FROM variants v \
+     LEFT JOIN order_items oi ON oi.variant_id = v.variant_id AND oi.preorder \
+     LEFT JOIN orders o ON o.order_id = oi.order_id \
+     WHERE v.product_id = $1 \
+     GROUP BY v.variant_id \
+     ORDER BY v.variant_id";
+
+fn load_campaign(
+    conn: &mut PgConnection,
+    product_id: i64,
+    lock: bool,
+) -> Result<Option<CampaignModel>, Error> {
+    let query = preorder_campaigns::table
+        .filter(preorder_campaigns::product_id.eq(product_id))
+        .select(CampaignModel::as_select());
+
+    let campaign = if lock {
+        query.for_update().first(conn).optional()?
+    } else {
+        query.first(conn).optional()?
+    };
+
+    Ok(campaign)
+}
+
+// Units of the product held by pre-orders that haven't been cancelled or refunded
+fn reserved_quantity(conn: &mut PgConnection, product_id: i64) -> Result<i64, Error> {
+    let reserved: Option<i64> = order_items::table
+        .inner_join(variants::table)
+        .inner_join(orders::table)
+        .filter(variants::product_id.eq(product_id))
+        .filter(order_items::preorder.eq(true))
+        .filter(orders::order_status.ne_all(RELEASED_STATUSES))
+        .select(sum(order_items::quantity))
+        .first(conn)?;
+
+    Ok(reserved.unwrap_or(0))
+}
+
+/// Looks up how a variant is sold. Returns its product and pre-order terms when the product
+/// has the `Preorder` status, or `None` for products sold from stock.
+///
+/// Fails when the product takes pre-orders but its campaign isn't open. The campaign row is
+/// locked, so within a transaction concurrent checkouts count the quota one at a time.
+pub fn preorder_terms(
+    conn: &mut PgConnection,
+    variant_id: i64,
+) -> Result<Option<(i64, PreorderTerms)>, Error> {
+    let (product_id, status): (i64, ProductStatus) = variants::table
+        .inner_join(products::table)
+        .filter(variants::variant_id.eq(variant_id))
+        .select((products::id, products::status))
+        .first(conn)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => {
+                Error::with_message(ErrorCode::ResourceNotFound, "Variant not found")
+            }
+            _ => Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e)),
+        })?;
+
+    if !matches!(status, ProductStatus::Preorder) {
+        return Ok(None);
+    }
+
+    let now = Utc::now().naive_utc();
+    let campaign = load_campaign(conn, product_id, true)?
+        .filter(|campaign| campaign.opens_at <= now && now < campaign.closes_at)
+        .ok_or_else(|| {
+            Error::with_message(
+                ErrorCode::ValidationError,
+                "Pre-orders for this product are not open",
+            )
+        })?;
+
+    Ok(Some((
+        product_id,
+        PreorderTerms {
+            payment_mode: campaign.payment_mode,
+            deposit_percent: campaign.deposit_percent,
+        },
+    )))
+}
+
+/// Fails when `extra` more units would take the product past its pre-order quota
+pub fn ensure_within_quota(
+    conn: &mut PgConnection,
+    product_id: i64,
+    extra: i64,
+) -> Result<(), Error> {
+    let Some(quota) = load_campaign(conn, product_id, false)?.and_then(|c| c.quota) else {
+        return Ok(());
+    };
+
+    let reserved = reserved_quantity(conn, product_id)?;
+    if reserved + extra > i64::from(quota) {
+        let left = (i64::from(quota) - reserved).max(0);
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Only {} pre-orders are left for this product", left),
+        ));
+    }
+
+    Ok(())
+}
+
+pub struct DieselPreorderRepository {
+    pool: DBPool,
+}
+
+impl DieselPreorderRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PreorderRepository for DieselPreorderRepository {
+    async fn find(&self, product_id: i64) -> Result<PreorderCampaign, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let campaign = load_campaign(&mut conn, product_id, false)?.ok_or_else(|| {
+            Error::with_message(ErrorCode::ResourceNotFound, "Pre-order campaign not found")
+        })?;
+        let reserved = reserved_quantity(&mut conn, product_id)?;
+
+        Ok(campaign.into_campaign(reserved))
+    }
+
+    async fn upsert(
+        &self,
+        product_id: i64,
+        campaign: PreorderCampaignRequest,
+    ) -> Result<PreorderCampaign, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction::<_, Error, _>(|conn| {
+            let product_exists: i64 = products::table
+                .filter(products::id.eq(product_id))
+                .count()
+                .get_result(conn)?;
+            if product_exists == 0 {
+                return Err(Error::with_message(
+                    ErrorCode::ResourceNotFound,
+                    "Product not found",
+                ));
+            }
+
+            let changeset = CampaignChangeset {
+                product_id,
+                opens_at: campaign.opens_at,
+                closes_at: campaign.closes_at,
+                quota: campaign.quota,
+                estimated_delivery: campaign.estimated_delivery,
+                payment_mode: campaign.payment_mode,
+                deposit_percent: campaign.deposit_percent,
+                updated_at: Utc::now().naive_utc(),
+            };
+            let saved: CampaignModel = diesel::insert_into(preorder_campaigns::table)
+                .values(&changeset)
+                .on_conflict(preorder_campaigns::product_id)
+                .do_update()
+                .set(&changeset)
+                .returning(CampaignModel::as_returning())
+                .get_result(conn)?;
+
+            let reserved = reserved_quantity(conn, product_id)?;
+            Ok(saved.into_campaign(reserved))
+        })
+    }
+
+    async fn delete(&self, product_id: i64) -> Result<(), Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let deleted = diesel::delete(
+            preorder_campaigns::table.filter(preorder_campaigns::product_id.eq(product_id)),
+        )
+        .execute(&mut conn)?;
+
+        if deleted == 0 {
+            return Err(Error::with_message(
+                ErrorCode::ResourceNotFound,
+                "Pre-order campaign not found",
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn variant_quantities(&self, product_id: i64) -> Result<Vec<PreorderVariantLine>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: Vec<VariantQuantityRow> = diesel::sql_query(VARIANT_QUANTITIES)
+            .bind::<BigInt, _>(product_id)
+            .load(&mut conn)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
@@ -0,0 +1,83 @@
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::PreorderPaymentMode"]
+pub enum PreorderPaymentMode {
+    /// The whole price is paid at checkout
+    #[db_rename = "FULL"]
+    Full,
+    /// `deposit_percent` of the price confirms the order; the balance is paid later
+    #[db_rename = "DEPOSIT"]
+    Deposit,
+}
+
+/// Pre-order terms for a product with the `Preorder` status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreorderCampaign {
+    pub product_id: i64,
+    pub opens_at: NaiveDateTime,
+    pub closes_at: NaiveDateTime,
+    /// Units across all variants; unlimited when absent
+    pub quota: Option<i32>,
+    /// Units pre-ordered so far, counting orders still awaiting payment
+    pub reserved: i64,
+    pub estimated_delivery: Option<NaiveDate>,
+    pub payment_mode: PreorderPaymentMode,
+    pub deposit_percent: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Creates or replaces a product's campaign
+#[derive(Debug, Clone, Deserialize)]
+pub struct PreorderCampaignRequest {
+    pub opens_at: NaiveDateTime,
+    pub closes_at: NaiveDateTime,
+    pub quota: Option<i32>,
+    pub estimated_delivery: Option<NaiveDate>,
+    pub payment_mode: PreorderPaymentMode,
+    pub deposit_percent: Option<i32>,
+}
+
+/// What a pre-order line is sold under, checked when it enters the cart and again at checkout
+#[derive(Debug, Clone)]
+pub struct PreorderTerms {
+    pub payment_mode: PreorderPaymentMode,
+    pub deposit_percent: Option<i32>,
+}
+
+impl PreorderTerms {
+    /// The part of `amount` due at checkout
+    pub fn due_now(&self, amount: &BigDecimal) -> BigDecimal {
+        match (self.payment_mode, self.deposit_percent) {
+            (PreorderPaymentMode::Deposit, Some(percent)) => {
+                (amount * BigDecimal::from(percent) / BigDecimal::from(100)).round(2)
+            }
+            _ => amount.clone(),
+        }
+    }
+}
+
+/// Pre-ordered units of one variant, for ordering from the factory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreorderVariantLine {
+    pub variant_id: i64,
+    pub sku: Option<String>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    /// In orders that are paid, or whose deposit is paid
+    pub confirmed_quantity: i64,
+    /// In orders still awaiting their first payment
+    pub pending_quantity: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreorderReport {
+    pub campaign: PreorderCampaign,
+    pub variants: Vec<PreorderVariantLine>,
+    pub confirmed_total: i64,
+    pub pending_total: i64,
+}
@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+
+use crate::utils::errors::Error;
+
+use super::entity::{PreorderCampaign, PreorderCampaignRequest, PreorderVariantLine};
+
+#[async_trait]
+pub trait PreorderRepository: Send + Sync {
+    async fn find(&self, product_id: i64) -> Result<PreorderCampaign, Error>;
+    async fn upsert(
+        &self,
+        product_id: i64,
+        campaign: PreorderCampaignRequest,
+    ) -> Result<PreorderCampaign, Error>;
+    async fn delete(&self, product_id: i64) -> Result<(), Error>;
+    /// Pre-ordered units per variant of the product, in variant order.
+    async fn variant_quantities(&self, product_id: i64) -> Result<Vec<PreorderVariantLine>, Error>;
+}
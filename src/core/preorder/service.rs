@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{
+    PreorderCampaign, PreorderCampaignRequest, PreorderPaymentMode, PreorderReport,
+};
+use super::repository::PreorderRepository;
+
+#[derive(Clone)]
+pub struct PreorderService {
+    repo: Arc<dyn PreorderRepository>,
+}
+
+impl PreorderService {
+    pub fn new(repo: Arc<dyn PreorderRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn get_campaign(&self, product_id: i64) -> Result<PreorderCampaign, Error> {
+        self.repo.find(product_id).await
+    }
+
+    pub async fn save_campaign(
+        &self,
+        product_id: i64,
+        campaign: PreorderCampaignRequest,
+    ) -> Result<PreorderCampaign, Error> {
+        if campaign.closes_at <= campaign.opens_at {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "closes_at must be after opens_at",
+            ));
+        }
+
+        if campaign.quota.is_some_and(|quota| quota <= 0) {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Quota must be greater than 0",
+            ));
+        }
+
+        match (campaign.payment_mode, campaign.deposit_percent) {
+            (PreorderPaymentMode::Deposit, Some(percent)) if (1..=99).contains(&percent) => {}
+            (PreorderPaymentMode::Deposit, _) => {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "Deposit campaigns need a deposit_percent between 1 and 99",
+                ));
+            }
+            (PreorderPaymentMode::Full, Some(_)) => {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "deposit_percent is only used by deposit campaigns",
+                ));
+            }
+            (PreorderPaymentMode::Full, None) => {}
+        }
+
+        self.repo.upsert(product_id, campaign).await
+    }
+
+    pub async fn remove_campaign(&self, product_id: i64) -> Result<(), Error> {
+        self.repo.delete(product_id).await
+    }
+
+    /// Pre-ordered quantities by variant, split by whether the order has been paid.
+    pub async fn report(&self, product_id: i64) -> Result<PreorderReport, Error> {
+        let campaign = self.repo.find(product_id).await?;
+        let variants = self.repo.variant_quantities(product_id).await?;
+
+        Ok(PreorderReport {
+            confirmed_total: variants.iter().map(|v| v.confirmed_quantity).sum(),
+            pending_total: variants.iter().map(|v| v.pending_quantity).sum(),
+            campaign,
+            variants,
+        })
+    }
+}
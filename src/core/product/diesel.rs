@@ -1,15 +1,36 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use bigdecimal::BigDecimal;
+use chrono::Utc;
+use diesel::dsl::{sql, sum};
+use diesel::expression::SqlLiteral;
+use diesel::pg::Pg;
 use diesel::prelude::*;
+use diesel::sql_types::{Array, BigInt, Float4, Nullable, Numeric, Text, Timestamp};
 use serde::{Deserialize, Serialize};
+use tracing::error;
 
 use super::entity::{
-    NewProduct, Product, ProductDetail, ProductListItem, ProductStatus, UpdateProduct, Variant,
+    ImportReport, ImportRowError, NewProduct, PriceHistoryEntry, Product, ProductDetail,
+    ProductFilter, ProductImport, ProductKind, ProductListItem, ProductPricing, ProductSchedule,
+    ProductSearchHit, ProductSheetRow, ProductSort, ProductStatus, ProductVisibility, SheetProduct,
+    SheetVariant, SlugLookup, UnitPrice, UpdateProduct, Variant,
 };
 use super::repository::ProductRepository;
-use crate::schema::{products, variants};
+use crate::core::bundle::diesel::load_components;
+use crate::core::bundle::entity::bundle_stock;
+use crate::core::category::diesel::{category_name, subtree_ids};
+use crate::core::inventory::diesel::apply_movement;
+use crate::core::inventory::entity::{InventoryReason, NewInventoryMovement};
+use crate::core::variant::diesel::write_error;
+use crate::schema::{
+    bundle_components, cart_items, order_item_components, order_items, preorder_campaigns,
+    product_price_history, product_slug_redirects, products, variants,
+};
 use crate::utils::db::DBPool;
 use crate::utils::errors::{Error, ErrorCode};
+use crate::utils::slug::slugify;
 
 #[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
 #[diesel(table_name = products)]
@@ -21,12 +42,25 @@ pub struct ProductModel {
     pub price: BigDecimal,
     pub status: ProductStatus,
     pub category: Option<String>,
-    pub stock_quantity: Option<i32>,
     pub created_at: Option<chrono::NaiveDateTime>,
     pub updated_at: Option<chrono::NaiveDateTime>,
     pub preview_image: Option<Vec<Option<String>>>,
     pub preview_video: Option<Vec<Option<String>>>,
     pub shipping: Option<Vec<Option<String>>>,
+    pub category_id: Option<i64>,
+    pub slug: Option<String>,
+    pub archived_at: Option<chrono::NaiveDateTime>,
+    pub visibility: ProductVisibility,
+    pub publish_at: Option<chrono::NaiveDateTime>,
+    pub unpublish_at: Option<chrono::NaiveDateTime>,
+    pub compare_at_price: Option<BigDecimal>,
+    pub sale_price: Option<BigDecimal>,
+    pub sale_starts_at: Option<chrono::NaiveDateTime>,
+    pub sale_ends_at: Option<chrono::NaiveDateTime>,
+    pub member_price: Option<BigDecimal>,
+    pub kind: ProductKind,
+    pub rating_average: Option<BigDecimal>,
+    pub rating_count: i32,
 }
 
 #[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
@@ -37,10 +71,14 @@ pub struct NewProductModel {
     pub price: BigDecimal,
     pub status: ProductStatus,
     pub category: Option<String>,
-    pub stock_quantity: Option<i32>,
     pub preview_image: Option<Vec<Option<String>>>,
     pub preview_video: Option<Vec<Option<String>>>,
     pub shipping: Option<Vec<Option<String>>>,
+    pub category_id: Option<i64>,
+    pub slug: Option<String>,
+    pub visibility: ProductVisibility,
+    pub publish_at: Option<chrono::NaiveDateTime>,
+    pub unpublish_at: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(Debug, Clone, AsChangeset, Serialize, Deserialize)]
@@ -51,10 +89,51 @@ pub struct UpdateProductModel {
     pub price: Option<BigDecimal>,
     pub status: Option<ProductStatus>,
     pub category: Option<String>,
-    pub stock_quantity: Option<i32>,
     pub preview_image: Option<Vec<Option<String>>>,
     pub preview_video: Option<Vec<Option<String>>>,
     pub shipping: Option<Vec<Option<String>>>,
+    pub category_id: Option<i64>,
+    pub slug: Option<String>,
+}
+
+// Cleared timestamps are written as NULL, since the schedule is replaced as a whole
+#[derive(Debug, Clone, AsChangeset)]
+#[diesel(table_name = products)]
+#[diesel(treat_none_as_null = true)]
+struct ProductScheduleModel {
+    visibility: ProductVisibility,
+    publish_at: Option<chrono::NaiveDateTime>,
+    unpublish_at: Option<chrono::NaiveDateTime>,
+}
+
+// Cleared prices are written as NULL, since pricing is replaced as a whole
+#[derive(Debug, Clone, AsChangeset)]
+#[diesel(table_name = products)]
+#[diesel(treat_none_as_null = true)]
+struct ProductPricingModel {
+    compare_at_price: Option<BigDecimal>,
+    sale_price: Option<BigDecimal>,
+    sale_starts_at: Option<chrono::NaiveDateTime>,
+    sale_ends_at: Option<chrono::NaiveDateTime>,
+    member_price: Option<BigDecimal>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = product_price_history)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct PriceHistoryModel {
+    history_id: i64,
+    product_id: i64,
+    price: BigDecimal,
+    compare_at_price: Option<BigDecimal>,
+    sale_price: Option<BigDecimal>,
+    sale_starts_at: Option<chrono::NaiveDateTime>,
+    sale_ends_at: Option<chrono::NaiveDateTime>,
+    member_price: Option<BigDecimal>,
+    variant_id: Option<i64>,
+    price_override: Option<BigDecimal>,
+    member_price_override: Option<BigDecimal>,
+    changed_at: chrono::NaiveDateTime,
 }
 
 #[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
@@ -65,35 +144,563 @@ pub struct VariantModel {
     pub product_id: i64,
     pub size: Option<String>,
     pub color: Option<String>,
-    pub stock_quantity: Option<i32>,
+    pub stock_quantity: i32,
+    pub sku: Option<String>,
+    pub price_override: Option<BigDecimal>,
+    pub member_price_override: Option<BigDecimal>,
+}
+
+#[derive(QueryableByName)]
+struct SearchHitRow {
+    #[diesel(sql_type = BigInt)]
+    id: i64,
+    #[diesel(sql_type = Text)]
+    name: String,
+    #[diesel(sql_type = Numeric)]
+    price: BigDecimal,
+    #[diesel(sql_type = Numeric)]
+    effective_price: BigDecimal,
+    #[diesel(sql_type = crate::schema::sql_types::ProductStatus)]
+    status: ProductStatus,
+    #[diesel(sql_type = Nullable<Text>)]
+    category: Option<String>,
+    #[diesel(sql_type = Nullable<Array<Nullable<Text>>>)]
+    preview_image: Option<Vec<Option<String>>>,
+    #[diesel(sql_type = Text)]
+    highlighted_name: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    snippet: Option<String>,
+    #[diesel(sql_type = Float4)]
+    rank: f32,
+}
+
+#[derive(QueryableByName)]
+struct SearchCountRow {
+    #[diesel(sql_type = BigInt)]
+    total: i64,
 }
 
-impl From<ProductModel> for Product {
-    fn from(model: ProductModel) -> Self {
+impl From<SearchHitRow> for ProductSearchHit {
+    fn from(row: SearchHitRow) -> Self {
+        ProductSearchHit {
+            product_id: row.id,
+            name: row.name,
+            base_price: row.price,
+            effective_price: row.effective_price,
+            status: row.status,
+            category: row.category,
+            preview_image: row.preview_image,
+            highlighted_name: row.highlighted_name,
+            snippet: row.snippet,
+            rank: row.rank,
+        }
+    }
+}
+
+impl ProductModel {
+    fn pricing(&self) -> ProductPricing {
+        ProductPricing {
+            compare_at_price: self.compare_at_price.clone(),
+            sale_price: self.sale_price.clone(),
+            sale_starts_at: self.sale_starts_at,
+            sale_ends_at: self.sale_ends_at,
+            member_price: self.member_price.clone(),
+        }
+    }
+
+    // `effective_price` is what `effective_price()` selected alongside the model
+    fn into_product(self, effective_price: BigDecimal, stock_quantity: i64) -> Product {
+        let unit_price = UnitPrice {
+            regular: self.price.clone(),
+            effective: effective_price,
+            member: self.member_price.clone(),
+        };
+        let member_effective_price = unit_price.member_price().cloned();
+
         Product {
-            product_id: model.id,
-            name: model.name,
-            description: model.description,
-            base_price: model.price,
-            status: model.status,
-            category: model.category,
-            stock_quantity: model.stock_quantity,
-            preview_image: model.preview_image,
-            preview_video: model.preview_video,
-            shipping: model.shipping,
+            product_id: self.id,
+            name: self.name,
+            slug: self.slug,
+            description: self.description,
+            base_price: self.price,
+            status: self.status,
+            kind: self.kind,
+            category_id: self.category_id,
+            category: self.category,
+            stock_quantity,
+            preview_image: self.preview_image,
+            preview_video: self.preview_video,
+            shipping: self.shipping,
+            archived_at: self.archived_at,
+            visibility: self.visibility,
+            publish_at: self.publish_at,
+            unpublish_at: self.unpublish_at,
+            compare_at_price: self.compare_at_price,
+            sale_price: self.sale_price,
+            sale_starts_at: self.sale_starts_at,
+            sale_ends_at: self.sale_ends_at,
+            effective_price: unit_price.effective,
+            member_price: self.member_price,
+            member_effective_price,
+            rating_average: self.rating_average,
+            rating_count: self.rating_count,
+        }
+    }
+}
+
+// Product stock is the sum of its variants' stock; a bundle's is what its components can
+// make up
+fn product_stock(conn: &mut PgConnection, product_id: i64) -> Result<i64, Error> {
+    let kind: ProductKind = products::table
+        .filter(products::id.eq(product_id))
+        .select(products::kind)
+        .first(conn)?;
+    if kind == ProductKind::Bundle {
+        return Ok(bundle_stock(&load_components(conn, product_id)?));
+    }
+
+    let total: Option<i64> = variants::table
+        .filter(variants::product_id.eq(product_id))
+        .select(sum(variants::stock_quantity))
+        .first(conn)
+        .map_err(|e| {
+            Error::with_message(
+                ErrorCode::DatabaseError,
+                format!("Failed to compute product stock: {}", e),
+            )
+        })?;
+
+    Ok(total.unwrap_or(0))
+}
+
+diesel::define_sql_function! {
+    fn product_on_sale(
+        archived_at: Nullable<Timestamp>,
+        visibility: crate::schema::sql_types::ProductVisibility,
+        publish_at: Nullable<Timestamp>,
+        unpublish_at: Nullable<Timestamp>,
+    ) -> Bool;
+}
+
+/// Whether the storefront shows a product and lets it be bought: not archived, published (or
+/// scheduled and due), and not yet unpublished. Usable in any query that includes `products`.
+pub fn on_sale() -> product_on_sale<
+    products::archived_at,
+    products::visibility,
+    products::publish_at,
+    products::unpublish_at,
+> {
+    product_on_sale(
+        products::archived_at,
+        products::visibility,
+        products::publish_at,
+        products::unpublish_at,
+    )
+}
+
+diesel::define_sql_function! {
+    fn product_effective_price(
+        regular_price: Numeric,
+        sale_price: Nullable<Numeric>,
+        sale_starts_at: Nullable<Timestamp>,
+        sale_ends_at: Nullable<Timestamp>,
+    ) -> Numeric;
+}
+
+/// What a product sells for right now: its sale price while a sale runs and is lower,
+/// otherwise its base price. Usable in any query that includes `products`.
+pub fn effective_price() -> product_effective_price<
+    products::price,
+    products::sale_price,
+    products::sale_starts_at,
+    products::sale_ends_at,
+> {
+    product_effective_price(
+        products::price,
+        products::sale_price,
+        products::sale_starts_at,
+        products::sale_ends_at,
+    )
+}
+
+/// What a unit of a variant costs right now: the product's sale applies to the variant's own
+/// price when it has one. Usable in any query that joins `variants` with `products`.
+pub fn variant_unit_price() -> SqlLiteral<Numeric> {
+    sql::<Numeric>(
+        "product_effective_price(COALESCE(variants.price_override, products.price), \
+         products.sale_price, products.sale_starts_at, products.sale_ends_at)",
+    )
+}
+
+/// A variant's price before any sale. Usable in any query that joins `variants` with
+/// `products`.
+pub fn variant_regular_price() -> SqlLiteral<Numeric> {
+    sql::<Numeric>("COALESCE(variants.price_override, products.price)")
+}
+
+/// What a unit of a variant costs verified members, before comparing it with the sale
+/// price. Usable in any query that joins `variants` with `products`.
+pub fn variant_member_price() -> SqlLiteral<Nullable<Numeric>> {
+    sql::<Nullable<Numeric>>("COALESCE(variants.member_price_override, products.member_price)")
+}
+
+/// A variant's regular, current and member prices, as selected by `variant_prices()`
+pub type VariantPrices = (BigDecimal, BigDecimal, Option<BigDecimal>);
+
+impl From<VariantPrices> for UnitPrice {
+    fn from((regular, effective, member): VariantPrices) -> Self {
+        UnitPrice {
+            regular,
+            effective,
+            member,
+        }
+    }
+}
+
+/// A product with its `effective_price()`
+type PricedProductModel = (ProductModel, BigDecimal);
+
+/// `variant_regular_price()`, `variant_unit_price()` and `variant_member_price()` together
+pub fn variant_prices() -> (
+    SqlLiteral<Numeric>,
+    SqlLiteral<Numeric>,
+    SqlLiteral<Nullable<Numeric>>,
+) {
+    (
+        variant_regular_price(),
+        variant_unit_price(),
+        variant_member_price(),
+    )
+}
+
+// Units sold per product across orders that have been paid for
+const UNITS_SOLD: &str = "COALESCE((SELECT SUM(oi.quantity) \
+     FROM order_items oi \
+     JOIN variants v ON v.variant_id = oi.variant_id \
+     JOIN orders o ON o.order_id = oi.order_id \
+     WHERE v.product_id = products.id \
+       AND o.order_status IN ('CONFIRMED', 'SHIPPING', 'COMPLETED', 'PARTIALLY_REFUNDED')), 0)";
+
+// Binds: $1 the search text, $2 the same text as an escaped ILIKE pattern. A product matches
+// on full-text terms over name, category and description, a fuzzy name match (typos), or a
+// plain substring of the name (Thai words inside unspaced text).
+const SEARCH_MATCH: &str = "product_search_document(p.name, p.category, p.description) \
+         @@ websearch_to_tsquery('simple', $1) \
+     OR $1 <% p.name \
+     OR p.name ILIKE $2";
+
+// `effective_price()` for the raw search SQL
+const EFFECTIVE_PRICE: &str =
+    "product_effective_price(p.price, p.sale_price, p.sale_starts_at, p.sale_ends_at)";
+
+// `on_sale()` for the raw search SQL
+const ON_SALE: &str = "product_on_sale(p.archived_at, p.visibility, p.publish_at, p.unpublish_at)";
+
+const HIGHLIGHT_OPTIONS: &str = "StartSel=<mark>, StopSel=</mark>";
+
+fn like_pattern(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+// Builds the WHERE clause shared by listing and counting
+fn filtered_products(
+    conn: &mut PgConnection,
+    filter: &ProductFilter,
+) -> Result<products::BoxedQuery<'static, Pg>, Error> {
+    let mut query = products::table.filter(on_sale()).into_boxed();
+
+    // A category includes the products of its subcategories
+    if let Some(slug) = &filter.category {
+        let category_ids = subtree_ids(conn, slug)?;
+        query = query.filter(products::category_id.eq_any(category_ids));
+    }
+    if let Some(status) = &filter.status {
+        query = query.filter(products::status.eq(status.clone()));
+    }
+    if let Some(kind) = filter.kind {
+        query = query.filter(products::kind.eq(kind));
+    }
+    // Price bounds apply to what the product sells for now, sale included
+    if let Some(min_price) = &filter.min_price {
+        query = query.filter(effective_price().ge(min_price.clone()));
+    }
+    if let Some(max_price) = &filter.max_price {
+        query = query.filter(effective_price().le(max_price.clone()));
+    }
+
+    if filter.size.is_some() || filter.color.is_some() {
+        // Size and color must match on the same variant, one in stock or on pre-order
+        let now = Utc::now().naive_utc();
+        let preordering = preorder_campaigns::table
+            .inner_join(products::table)
+            .filter(products::status.eq(ProductStatus::Preorder))
+            .filter(preorder_campaigns::opens_at.le(now))
+            .filter(preorder_campaigns::closes_at.gt(now))
+            .select(preorder_campaigns::product_id);
+        let mut available = variants::table
+            .select(variants::product_id)
+            .filter(
+                variants::stock_quantity
+                    .gt(0)
+                    .or(variants::product_id.eq_any(preordering)),
+            )
+            .into_boxed();
+        if let Some(size) = &filter.size {
+            available = available.filter(variants::size.eq(size.clone()));
+        }
+        if let Some(color) = &filter.color {
+            available = available.filter(variants::color.eq(color.clone()));
+        }
+        query = query.filter(products::id.eq_any(available));
+    }
+
+    Ok(query)
+}
+
+// A slug is taken when another product uses it now, or used it before a rename and still
+// redirects from it
+fn slug_taken(conn: &mut PgConnection, slug: &str, product_id: Option<i64>) -> Result<bool, Error> {
+    let mut current = products::table.filter(products::slug.eq(slug)).into_boxed();
+    let mut redirected = product_slug_redirects::table
+        .filter(product_slug_redirects::slug.eq(slug))
+        .into_boxed();
+    if let Some(product_id) = product_id {
+        current = current.filter(products::id.ne(product_id));
+        redirected = redirected.filter(product_slug_redirects::product_id.ne(product_id));
+    }
+
+    let to_error = |e: diesel::result::Error| {
+        Error::with_message(
+            ErrorCode::DatabaseError,
+            format!("Failed to check slug: {}", e),
+        )
+    };
+    let in_use = current.count().get_result::<i64>(conn).map_err(to_error)?
+        + redirected
+            .count()
+            .get_result::<i64>(conn)
+            .map_err(to_error)?;
+
+    Ok(in_use > 0)
+}
+
+// Generates a free slug from a product name, numbering it when the plain one is taken
+fn available_slug(
+    conn: &mut PgConnection,
+    name: &str,
+    product_id: Option<i64>,
+) -> Result<String, Error> {
+    let base = Some(slugify(name))
+        .filter(|slug| !slug.is_empty())
+        .unwrap_or_else(|| "product".to_string());
+
+    let mut slug = base.clone();
+    let mut suffix = 2;
+    while slug_taken(conn, &slug, product_id)? {
+        slug = format!("{}-{}", base, suffix);
+        suffix += 1;
+    }
+
+    Ok(slug)
+}
+
+// Checks an explicitly chosen slug is free
+fn claim_slug(conn: &mut PgConnection, slug: &str, product_id: Option<i64>) -> Result<(), Error> {
+    if slug_taken(conn, slug, product_id)? {
+        return Err(Error::with_message(
+            ErrorCode::ResourceAlreadyExists,
+            "A product with this slug already exists",
+        ));
+    }
+
+    Ok(())
+}
+
+// Moves a product to a new slug, keeping the old one as a redirect
+fn change_slug(
+    conn: &mut PgConnection,
+    product_id: i64,
+    old_slug: Option<&str>,
+    new_slug: &str,
+) -> Result<(), Error> {
+    let to_error = |e: diesel::result::Error| {
+        Error::with_message(
+            ErrorCode::DatabaseError,
+            format!("Failed to record slug redirect: {}", e),
+        )
+    };
+
+    if let Some(old_slug) = old_slug {
+        diesel::insert_into(product_slug_redirects::table)
+            .values((
+                product_slug_redirects::slug.eq(old_slug),
+                product_slug_redirects::product_id.eq(product_id),
+            ))
+            .on_conflict(product_slug_redirects::slug)
+            .do_update()
+            .set(product_slug_redirects::product_id.eq(product_id))
+            .execute(conn)
+            .map_err(to_error)?;
+    }
+
+    // Going back to an earlier slug makes it current again rather than a redirect
+    diesel::delete(product_slug_redirects::table)
+        .filter(product_slug_redirects::slug.eq(new_slug))
+        .execute(conn)
+        .map_err(to_error)?;
+
+    Ok(())
+}
+
+fn load_product(conn: &mut PgConnection, product_id: i64) -> Result<ProductModel, Error> {
+    products::table
+        .filter(products::id.eq(product_id))
+        .select(ProductModel::as_select())
+        .first(conn)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => {
+                Error::with_message(ErrorCode::ResourceNotFound, "Product not found")
+            }
+            _ => Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e)),
+        })
+}
+
+// Loads a product with what it sells for right now
+fn load_priced_product(
+    conn: &mut PgConnection,
+    product_id: i64,
+) -> Result<PricedProductModel, Error> {
+    products::table
+        .filter(products::id.eq(product_id))
+        .select((ProductModel::as_select(), effective_price()))
+        .first(conn)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => {
+                Error::with_message(ErrorCode::ResourceNotFound, "Product not found")
+            }
+            _ => Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e)),
+        })
+}
+
+// Loads a product with its variants. The storefront doesn't find products that aren't on
+// sale; admin previews do.
+fn product_detail(
+    conn: &mut PgConnection,
+    product_id: i64,
+    storefront: bool,
+) -> Result<ProductDetail, Error> {
+    if storefront {
+        let on_sale_count: i64 = products::table
+            .filter(products::id.eq(product_id))
+            .filter(on_sale())
+            .count()
+            .get_result(conn)?;
+        if on_sale_count == 0 {
+            return Err(Error::with_message(
+                ErrorCode::ResourceNotFound,
+                "Product not found",
+            ));
         }
     }
+
+    let (product_model, effective_price) = load_priced_product(conn, product_id)?;
+
+    // Get variants for this product, priced by the database like the cart and checkout
+    let variant_rows: Vec<(VariantModel, VariantPrices)> = variants::table
+        .inner_join(products::table)
+        .filter(variants::product_id.eq(product_id))
+        .select((VariantModel::as_select(), variant_prices()))
+        .order(variants::variant_id.asc())
+        .load(conn)
+        .map_err(|e| {
+            Error::with_message(
+                ErrorCode::DatabaseError,
+                format!("Failed to fetch variants: {}", e),
+            )
+        })?;
+
+    let pricing = product_model.pricing();
+    let variants: Vec<Variant> = variant_rows
+        .into_iter()
+        .map(|(model, prices)| {
+            let unit_price = UnitPrice::from(prices);
+            Variant {
+                member_effective_price: unit_price.member_price().cloned(),
+                effective_price: Some(unit_price.effective),
+                ..model.into()
+            }
+        })
+        .collect();
+    let components = load_components(conn, product_id)?;
+    let stock_quantity = if product_model.kind == ProductKind::Bundle {
+        bundle_stock(&components)
+    } else {
+        variants.iter().map(|v| i64::from(v.stock_quantity)).sum()
+    };
+    let unit_price = UnitPrice {
+        regular: product_model.price.clone(),
+        effective: effective_price,
+        member: pricing.member_price.clone(),
+    };
+    let member_effective_price = unit_price.member_price().cloned();
+
+    Ok(ProductDetail {
+        product_id: product_model.id,
+        name: product_model.name,
+        slug: product_model.slug,
+        description: product_model.description,
+        base_price: product_model.price,
+        status: product_model.status,
+        kind: product_model.kind,
+        category_id: product_model.category_id,
+        category: product_model.category,
+        stock_quantity,
+        preview_image: product_model.preview_image,
+        preview_video: product_model.preview_video,
+        shipping: product_model.shipping,
+        visibility: product_model.visibility,
+        publish_at: product_model.publish_at,
+        unpublish_at: product_model.unpublish_at,
+        compare_at_price: pricing.compare_at_price,
+        sale_price: pricing.sale_price,
+        sale_starts_at: pricing.sale_starts_at,
+        sale_ends_at: pricing.sale_ends_at,
+        effective_price: unit_price.effective,
+        member_price: pricing.member_price,
+        member_effective_price,
+        rating_average: product_model.rating_average,
+        rating_count: product_model.rating_count,
+        variants,
+        components,
+    })
 }
 
-impl From<ProductModel> for ProductListItem {
-    fn from(model: ProductModel) -> Self {
+impl From<PricedProductModel> for ProductListItem {
+    fn from((model, effective_price): PricedProductModel) -> Self {
+        // A running sale only matters to the listing when the database priced the product
+        // below its base price because of it
+        let sale_price = model.sale_price.filter(|_| effective_price < model.price);
+
         ProductListItem {
             product_id: model.id,
             name: model.name,
+            slug: model.slug,
             base_price: model.price,
             status: model.status,
+            kind: model.kind,
+            category_id: model.category_id,
             category: model.category,
             preview_image: model.preview_image,
+            archived_at: model.archived_at,
+            compare_at_price: model.compare_at_price,
+            sale_price,
+            effective_price,
+            rating_average: model.rating_average,
+            rating_count: model.rating_count,
         }
     }
 }
@@ -105,8 +712,15 @@ impl From<NewProduct> for NewProductModel {
             description: new_product.description,
             price: new_product.base_price,
             status: new_product.status.unwrap_or_default(),
-            category: new_product.category,
-            stock_quantity: new_product.stock_quantity,
+            // Filled in from the category by the repository
+            category: None,
+            category_id: new_product.category_id,
+            // Generated by the repository when not given
+            slug: new_product.slug,
+            // Defaulted by the service
+            visibility: new_product.visibility.unwrap_or_default(),
+            publish_at: new_product.publish_at,
+            unpublish_at: new_product.unpublish_at,
             preview_image: new_product.preview_image,
             preview_video: new_product.preview_video,
             shipping: new_product.shipping,
@@ -121,8 +735,9 @@ impl From<UpdateProduct> for UpdateProductModel {
             description: update_product.description,
             price: update_product.base_price,
             status: update_product.status,
-            category: update_product.category,
-            stock_quantity: update_product.stock_quantity,
+            category: None,
+            category_id: update_product.category_id,
+            slug: update_product.slug,
             preview_image: update_product.preview_image,
             preview_video: update_product.preview_video,
             shipping: update_product.shipping,
@@ -138,8 +753,338 @@ impl From<VariantModel> for Variant {
             size: model.size,
             color: model.color,
             stock_quantity: model.stock_quantity,
+            sku: model.sku,
+            price_override: model.price_override,
+            member_price_override: model.member_price_override,
+            effective_price: None,
+            member_effective_price: None,
+        }
+    }
+}
+
+// Ends an import transaction early. A rollback still reports what the import found.
+enum ImportAbort {
+    Rollback(ImportReport),
+    Failed(Error),
+}
+
+impl From<diesel::result::Error> for ImportAbort {
+    fn from(e: diesel::result::Error) -> Self {
+        ImportAbort::Failed(e.into())
+    }
+}
+
+#[derive(Default)]
+struct ImportedProduct {
+    created: bool,
+    variants_created: usize,
+    variants_updated: usize,
+}
+
+// The existing product a sheet product updates: the one with its slug, or else the one
+// owning any of its SKUs
+fn import_target(conn: &mut PgConnection, item: &ProductImport) -> Result<Option<i64>, Error> {
+    if let Some(slug) = &item.product.slug {
+        let by_slug: Option<i64> = products::table
+            .filter(products::slug.eq(slug))
+            .select(products::id)
+            .first(conn)
+            .optional()?;
+        if by_slug.is_some() {
+            return Ok(by_slug);
+        }
+    }
+
+    let skus: Vec<&String> = item
+        .variants
+        .iter()
+        .filter_map(|(_, variant)| variant.sku.as_ref())
+        .collect();
+    if skus.is_empty() || item.product.slug.is_some() {
+        return Ok(None);
+    }
+
+    Ok(variants::table
+        .filter(variants::sku.eq_any(skus))
+        .select(variants::product_id)
+        .order(variants::variant_id.asc())
+        .first(conn)
+        .optional()?)
+}
+
+fn import_new_product(conn: &mut PgConnection, product: &SheetProduct) -> Result<i64, Error> {
+    if product.kind == Some(ProductKind::Bundle) {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Bundles are made by setting their components; import the product as STANDARD",
+        ));
+    }
+
+    let name_taken: i64 = products::table
+        .filter(products::name.eq(&product.name))
+        .count()
+        .get_result(conn)?;
+    if name_taken > 0 {
+        return Err(Error::with_message(
+            ErrorCode::ResourceAlreadyExists,
+            "Product with this name already exists",
+        ));
+    }
+
+    let slug = match &product.slug {
+        Some(slug) => {
+            claim_slug(conn, slug, None)?;
+            slug.clone()
+        }
+        None => available_slug(conn, &product.name, None)?,
+    };
+    let category = product
+        .category_id
+        .map(|category_id| category_name(conn, category_id))
+        .transpose()?;
+
+    let created: i64 = diesel::insert_into(products::table)
+        .values(&NewProductModel {
+            name: product.name.clone(),
+            description: product.description.clone(),
+            price: product.base_price.clone(),
+            status: product.status.clone().unwrap_or_default(),
+            category,
+            preview_image: None,
+            preview_video: None,
+            shipping: None,
+            category_id: product.category_id,
+            slug: Some(slug),
+            visibility: product.visibility.unwrap_or_default(),
+            publish_at: product.publish_at,
+            unpublish_at: product.unpublish_at,
+        })
+        .returning(products::id)
+        .get_result(conn)?;
+
+    if let Some(member_price) = &product.member_price {
+        diesel::update(products::table.filter(products::id.eq(created)))
+            .set(products::member_price.eq(member_price))
+            .execute(conn)?;
+    }
+
+    Ok(created)
+}
+
+// Blank cells leave the product's current values alone
+fn import_existing_product(
+    conn: &mut PgConnection,
+    product_id: i64,
+    product: &SheetProduct,
+) -> Result<(), Error> {
+    let current = load_product(conn, product_id)?;
+    if product.kind.is_some_and(|kind| kind != current.kind) {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "kind is changed by setting or removing the bundle's components",
+        ));
+    }
+
+    let slug = match &product.slug {
+        Some(slug) => Some(slug.clone()),
+        None if product.name != current.name => {
+            Some(available_slug(conn, &product.name, Some(product_id))?)
+        }
+        None => None,
+    };
+    if let Some(slug) = &slug
+        && current.slug.as_ref() != Some(slug)
+    {
+        claim_slug(conn, slug, Some(product_id))?;
+        change_slug(conn, product_id, current.slug.as_deref(), slug)?;
+    }
+
+    let category = product
+        .category_id
+        .map(|category_id| category_name(conn, category_id))
+        .transpose()?;
+
+    diesel::update(products::table.filter(products::id.eq(product_id)))
+        .set(&UpdateProductModel {
+            name: Some(product.name.clone()),
+            description: product.description.clone(),
+            price: Some(product.base_price.clone()),
+            status: product.status.clone(),
+            category,
+            preview_image: None,
+            preview_video: None,
+            shipping: None,
+            category_id: product.category_id,
+            slug,
+        })
+        .execute(conn)?;
+
+    // The visibility comes with its whole schedule, so blank times clear the current ones
+    if let Some(visibility) = product.visibility {
+        diesel::update(products::table.filter(products::id.eq(product_id)))
+            .set((
+                products::visibility.eq(visibility),
+                products::publish_at.eq(product.publish_at),
+                products::unpublish_at.eq(product.unpublish_at),
+            ))
+            .execute(conn)?;
+    }
+
+    if let Some(member_price) = &product.member_price {
+        diesel::update(products::table.filter(products::id.eq(product_id)))
+            .set(products::member_price.eq(member_price))
+            .execute(conn)?;
+    }
+
+    Ok(())
+}
+
+// Matches the variant by SKU, or else by size and color. Returns whether it was created.
+fn import_variant(
+    conn: &mut PgConnection,
+    product_id: i64,
+    variant: &SheetVariant,
+    imported_by: &str,
+) -> Result<bool, Error> {
+    let by_sku: Option<VariantModel> = match &variant.sku {
+        Some(sku) => variants::table
+            .filter(variants::sku.eq(sku))
+            .select(VariantModel::as_select())
+            .first(conn)
+            .optional()?,
+        None => None,
+    };
+    if let Some(existing) = &by_sku
+        && existing.product_id != product_id
+    {
+        return Err(Error::with_message(
+            ErrorCode::ResourceAlreadyExists,
+            "SKU belongs to another product",
+        ));
+    }
+
+    let existing = match by_sku {
+        Some(existing) => Some(existing),
+        None => variants::table
+            .filter(variants::product_id.eq(product_id))
+            .filter(variants::size.is_not_distinct_from(&variant.size))
+            .filter(variants::color.is_not_distinct_from(&variant.color))
+            .select(VariantModel::as_select())
+            .first(conn)
+            .optional()?,
+    };
+
+    let Some(existing) = existing else {
+        let created: i64 = diesel::insert_into(variants::table)
+            .values((
+                variants::product_id.eq(product_id),
+                variants::size.eq(&variant.size),
+                variants::color.eq(&variant.color),
+                variants::sku.eq(&variant.sku),
+                variants::price_override.eq(&variant.price_override),
+                variants::member_price_override.eq(&variant.member_price_override),
+                variants::stock_quantity.eq(0),
+            ))
+            .returning(variants::variant_id)
+            .get_result(conn)
+            .map_err(write_error)?;
+
+        let opening = variant.stock_quantity.unwrap_or(0);
+        if opening > 0 {
+            import_stock_movement(
+                conn,
+                created,
+                opening,
+                InventoryReason::Restock,
+                imported_by,
+            )?;
+        }
+        return Ok(true);
+    };
+
+    diesel::update(variants::table.filter(variants::variant_id.eq(existing.variant_id)))
+        .set((
+            variants::size.eq(&variant.size),
+            variants::color.eq(&variant.color),
+            variants::sku.eq(variant.sku.as_ref().or(existing.sku.as_ref())),
+            variants::price_override.eq(variant
+                .price_override
+                .as_ref()
+                .or(existing.price_override.as_ref())),
+            variants::member_price_override.eq(variant
+                .member_price_override
+                .as_ref()
+                .or(existing.member_price_override.as_ref())),
+        ))
+        .execute(conn)
+        .map_err(write_error)?;
+
+    // Stock changes are recorded as adjustments so the ledger stays balanced
+    if let Some(stock) = variant.stock_quantity
+        && stock != existing.stock_quantity
+    {
+        import_stock_movement(
+            conn,
+            existing.variant_id,
+            stock - existing.stock_quantity,
+            InventoryReason::Adjustment,
+            imported_by,
+        )?;
+    }
+
+    Ok(false)
+}
+
+fn import_stock_movement(
+    conn: &mut PgConnection,
+    variant_id: i64,
+    quantity_change: i32,
+    reason: InventoryReason,
+    imported_by: &str,
+) -> Result<(), Error> {
+    apply_movement(
+        conn,
+        NewInventoryMovement {
+            variant_id,
+            quantity_change,
+            reason,
+            order_id: None,
+            note: Some("Product import".to_string()),
+            created_by: Some(imported_by.to_string()),
+        },
+    )?;
+    Ok(())
+}
+
+// Writes one sheet product and its variants, keeping `row` on the row being written so a
+// failure can be reported against it
+fn import_product(
+    conn: &mut PgConnection,
+    item: &ProductImport,
+    imported_by: &str,
+    row: &mut usize,
+) -> Result<ImportedProduct, Error> {
+    *row = item.row;
+    let mut imported = ImportedProduct::default();
+
+    let product_id = if let Some(product_id) = import_target(conn, item)? {
+        import_existing_product(conn, product_id, &item.product)?;
+        product_id
+    } else {
+        imported.created = true;
+        import_new_product(conn, &item.product)?
+    };
+
+    for (variant_row, variant) in &item.variants {
+        *row = *variant_row;
+        if import_variant(conn, product_id, variant, imported_by)? {
+            imported.variants_created += 1;
+        } else {
+            imported.variants_updated += 1;
         }
     }
+
+    Ok(imported)
 }
 
 pub struct DieselProductRepository {
@@ -159,20 +1104,36 @@ impl ProductRepository for DieselProductRepository {
             Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
         })?;
 
-        let new_product_model: NewProductModel = new_product.into();
+        let mut new_product_model: NewProductModel = new_product.into();
 
-        let product_model: ProductModel = diesel::insert_into(products::table)
-            .values(&new_product_model)
-            .returning(ProductModel::as_returning())
-            .get_result(&mut conn)
-            .map_err(|e| {
-                Error::with_message(
-                    ErrorCode::DatabaseError,
-                    format!("Failed to create product: {}", e),
-                )
-            })?;
+        let (product_model, effective_price) = conn.transaction::<_, Error, _>(|conn| {
+            if let Some(category_id) = new_product_model.category_id {
+                new_product_model.category = Some(category_name(conn, category_id)?);
+            }
+
+            let slug = match new_product_model.slug.take() {
+                Some(slug) => {
+                    claim_slug(conn, &slug, None)?;
+                    slug
+                }
+                None => available_slug(conn, &new_product_model.name, None)?,
+            };
+            new_product_model.slug = Some(slug);
 
-        Ok(product_model.into())
+            diesel::insert_into(products::table)
+                .values(&new_product_model)
+                .returning((ProductModel::as_returning(), effective_price()))
+                .get_result::<PricedProductModel>(conn)
+                .map_err(|e| {
+                    Error::with_message(
+                        ErrorCode::DatabaseError,
+                        format!("Failed to create product: {}", e),
+                    )
+                })
+        })?;
+
+        // A new product has no variants yet
+        Ok(product_model.into_product(effective_price, 0))
     }
 
     async fn find_by_id(&self, product_id: i64) -> Result<Product, Error> {
@@ -180,30 +1141,38 @@ impl ProductRepository for DieselProductRepository {
             Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
         })?;
 
-        let product_model: ProductModel = products::table
-            .filter(products::id.eq(product_id))
-            .select(ProductModel::as_select())
-            .first(&mut conn)
-            .map_err(|e| match e {
-                diesel::result::Error::NotFound => {
-                    Error::with_message(ErrorCode::ResourceNotFound, "Product not found")
-                }
-                _ => {
-                    Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e))
-                }
-            })?;
+        let (product_model, effective_price) = load_priced_product(&mut conn, product_id)?;
+        let stock_quantity = product_stock(&mut conn, product_id)?;
 
-        Ok(product_model.into())
+        Ok(product_model.into_product(effective_price, stock_quantity))
     }
 
-    async fn find_all(&self, offset: i64, limit: i64) -> Result<Vec<ProductListItem>, Error> {
+    async fn find_all(
+        &self,
+        filter: &ProductFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<ProductListItem>, Error> {
         let mut conn = self.pool.get().map_err(|e| {
             Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
         })?;
 
-        let product_models: Vec<ProductModel> = products::table
-            .select(ProductModel::as_select())
-            .order(products::id.desc())
+        let query = filtered_products(&mut conn, filter)?
+            .select((ProductModel::as_select(), effective_price()));
+        let query = match filter.sort {
+            ProductSort::Newest => query.order(products::id.desc()),
+            ProductSort::PriceAsc => query
+                .order(effective_price().asc())
+                .then_order_by(products::id.desc()),
+            ProductSort::PriceDesc => query
+                .order(effective_price().desc())
+                .then_order_by(products::id.desc()),
+            ProductSort::Popular => query
+                .order(sql::<BigInt>(UNITS_SOLD).desc())
+                .then_order_by(products::id.desc()),
+        };
+
+        let product_models: Vec<PricedProductModel> = query
             .offset(offset)
             .limit(limit)
             .load(&mut conn)
@@ -222,64 +1191,172 @@ impl ProductRepository for DieselProductRepository {
             Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
         })?;
 
-        // Get product
-        let product_model: ProductModel = products::table
-            .filter(products::id.eq(product_id))
-            .select(ProductModel::as_select())
+        product_detail(&mut conn, product_id, true)
+    }
+
+    async fn find_preview(&self, product_id: i64) -> Result<ProductDetail, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        product_detail(&mut conn, product_id, false)
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> Result<SlugLookup, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let to_error = |e: diesel::result::Error| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e))
+        };
+
+        let product_id: Option<i64> = products::table
+            .filter(products::slug.eq(slug))
+            .select(products::id)
             .first(&mut conn)
-            .map_err(|e| match e {
-                diesel::result::Error::NotFound => {
-                    Error::with_message(ErrorCode::ResourceNotFound, "Product not found")
-                }
-                _ => {
-                    Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e))
+            .optional()
+            .map_err(to_error)?;
+        if let Some(product_id) = product_id {
+            return product_detail(&mut conn, product_id, true)
+                .map(|detail| SlugLookup::Found(Box::new(detail)));
+        }
+
+        let current_slug: Option<Option<String>> = product_slug_redirects::table
+            .inner_join(products::table)
+            .filter(product_slug_redirects::slug.eq(slug))
+            .filter(on_sale())
+            .select(products::slug)
+            .first(&mut conn)
+            .optional()
+            .map_err(to_error)?;
+
+        current_slug
+            .flatten()
+            .map(SlugLookup::Moved)
+            .ok_or_else(|| Error::with_message(ErrorCode::ResourceNotFound, "Product not found"))
+    }
+
+    async fn update(
+        &self,
+        product_id: i64,
+        update_product: UpdateProduct,
+    ) -> Result<Product, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let mut update_model: UpdateProductModel = update_product.into();
+
+        let (product_model, effective_price) = conn.transaction::<_, Error, _>(|conn| {
+            if let Some(category_id) = update_model.category_id {
+                update_model.category = Some(category_name(conn, category_id)?);
+            }
+
+            let current = load_product(conn, product_id)?;
+            let slug = match update_model.slug.take() {
+                Some(slug) => {
+                    claim_slug(conn, &slug, Some(product_id))?;
+                    Some(slug)
                 }
-            })?;
+                // Renaming regenerates the slug unless one was chosen
+                None => match &update_model.name {
+                    Some(name) if *name != current.name => {
+                        Some(available_slug(conn, name, Some(product_id))?)
+                    }
+                    _ => None,
+                },
+            };
+            if let Some(slug) = &slug
+                && current.slug.as_ref() != Some(slug)
+            {
+                change_slug(conn, product_id, current.slug.as_deref(), slug)?;
+            }
+            update_model.slug = slug;
 
-        // Get variants for this product
-        let variant_models: Vec<VariantModel> = variants::table
-            .filter(variants::product_id.eq(product_id))
-            .select(VariantModel::as_select())
-            .load(&mut conn)
+            diesel::update(products::table)
+                .filter(products::id.eq(product_id))
+                .set(&update_model)
+                .returning((ProductModel::as_returning(), effective_price()))
+                .get_result::<PricedProductModel>(conn)
+                .map_err(|e| {
+                    Error::with_message(
+                        ErrorCode::DatabaseError,
+                        format!("Failed to update product: {}", e),
+                    )
+                })
+        })?;
+
+        let stock_quantity = product_stock(&mut conn, product_id)?;
+
+        Ok(product_model.into_product(effective_price, stock_quantity))
+    }
+
+    async fn archive(&self, product_id: i64) -> Result<Product, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        diesel::update(products::table)
+            .filter(products::id.eq(product_id))
+            .filter(products::archived_at.is_null())
+            .set(products::archived_at.eq(Utc::now().naive_utc()))
+            .execute(&mut conn)
             .map_err(|e| {
                 Error::with_message(
                     ErrorCode::DatabaseError,
-                    format!("Failed to fetch variants: {}", e),
+                    format!("Failed to archive product: {}", e),
                 )
             })?;
 
-        let variants: Vec<Variant> = variant_models.into_iter().map(Into::into).collect();
-
-        Ok(ProductDetail {
-            product_id: product_model.id,
-            name: product_model.name,
-            description: product_model.description,
-            base_price: product_model.price,
-            status: product_model.status,
-            category: product_model.category,
-            stock_quantity: product_model.stock_quantity,
-            preview_image: product_model.preview_image,
-            preview_video: product_model.preview_video,
-            shipping: product_model.shipping,
-            variants,
-        })
+        let (product_model, effective_price) = load_priced_product(&mut conn, product_id)?;
+        let stock_quantity = product_stock(&mut conn, product_id)?;
+
+        Ok(product_model.into_product(effective_price, stock_quantity))
     }
 
-    async fn update(
+    async fn restore(&self, product_id: i64) -> Result<Product, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let (product_model, effective_price): PricedProductModel = diesel::update(products::table)
+            .filter(products::id.eq(product_id))
+            .set(products::archived_at.eq(None::<chrono::NaiveDateTime>))
+            .returning((ProductModel::as_returning(), effective_price()))
+            .get_result(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    Error::with_message(ErrorCode::ResourceNotFound, "Product not found")
+                }
+                _ => Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to restore product: {}", e),
+                ),
+            })?;
+
+        let stock_quantity = product_stock(&mut conn, product_id)?;
+
+        Ok(product_model.into_product(effective_price, stock_quantity))
+    }
+
+    async fn set_schedule(
         &self,
         product_id: i64,
-        update_product: UpdateProduct,
+        schedule: ProductSchedule,
     ) -> Result<Product, Error> {
         let mut conn = self.pool.get().map_err(|e| {
             Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
         })?;
 
-        let update_model: UpdateProductModel = update_product.into();
-
-        let product_model: ProductModel = diesel::update(products::table)
+        let (product_model, effective_price): PricedProductModel = diesel::update(products::table)
             .filter(products::id.eq(product_id))
-            .set(&update_model)
-            .returning(ProductModel::as_returning())
+            .set(&ProductScheduleModel {
+                visibility: schedule.visibility,
+                publish_at: schedule.publish_at,
+                unpublish_at: schedule.unpublish_at,
+            })
+            .returning((ProductModel::as_returning(), effective_price()))
             .get_result(&mut conn)
             .map_err(|e| match e {
                 diesel::result::Error::NotFound => {
@@ -287,36 +1364,222 @@ impl ProductRepository for DieselProductRepository {
                 }
                 _ => Error::with_message(
                     ErrorCode::DatabaseError,
-                    format!("Failed to update product: {}", e),
+                    format!("Failed to update product visibility: {}", e),
                 ),
             })?;
 
-        Ok(product_model.into())
+        let stock_quantity = product_stock(&mut conn, product_id)?;
+
+        Ok(product_model.into_product(effective_price, stock_quantity))
     }
 
-    async fn delete(&self, product_id: i64) -> Result<(), Error> {
+    async fn set_pricing(
+        &self,
+        product_id: i64,
+        pricing: ProductPricing,
+    ) -> Result<Product, Error> {
         let mut conn = self.pool.get().map_err(|e| {
             Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
         })?;
 
-        let deleted_rows = diesel::delete(products::table)
+        let (product_model, effective_price): PricedProductModel = diesel::update(products::table)
             .filter(products::id.eq(product_id))
-            .execute(&mut conn)
+            .set(&ProductPricingModel {
+                compare_at_price: pricing.compare_at_price,
+                sale_price: pricing.sale_price,
+                sale_starts_at: pricing.sale_starts_at,
+                sale_ends_at: pricing.sale_ends_at,
+                member_price: pricing.member_price,
+            })
+            .returning((ProductModel::as_returning(), effective_price()))
+            .get_result(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    Error::with_message(ErrorCode::ResourceNotFound, "Product not found")
+                }
+                _ => Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to update product pricing: {}", e),
+                ),
+            })?;
+
+        let stock_quantity = product_stock(&mut conn, product_id)?;
+
+        Ok(product_model.into_product(effective_price, stock_quantity))
+    }
+
+    async fn price_history(&self, product_id: i64) -> Result<Vec<PriceHistoryEntry>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        load_product(&mut conn, product_id)?;
+
+        let rows: Vec<PriceHistoryModel> = product_price_history::table
+            .filter(product_price_history::product_id.eq(product_id))
+            .select(PriceHistoryModel::as_select())
+            .order(product_price_history::history_id.asc())
+            .load(&mut conn)
             .map_err(|e| {
                 Error::with_message(
                     ErrorCode::DatabaseError,
-                    format!("Failed to delete product: {}", e),
+                    format!("Failed to fetch price history: {}", e),
                 )
             })?;
 
-        if deleted_rows == 0 {
-            return Err(Error::with_message(
-                ErrorCode::ResourceNotFound,
-                "Product not found",
-            ));
-        }
+        Ok(rows
+            .into_iter()
+            .map(|row| PriceHistoryEntry {
+                history_id: row.history_id,
+                product_id: row.product_id,
+                price: row.price,
+                compare_at_price: row.compare_at_price,
+                sale_price: row.sale_price,
+                sale_starts_at: row.sale_starts_at,
+                sale_ends_at: row.sale_ends_at,
+                member_price: row.member_price,
+                variant_id: row.variant_id,
+                price_override: row.price_override,
+                member_price_override: row.member_price_override,
+                changed_at: row.changed_at,
+            })
+            .collect())
+    }
 
-        Ok(())
+    async fn apply_schedules(&self) -> Result<usize, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let now = Utc::now().naive_utc();
+        let to_error = |e: diesel::result::Error| {
+            Error::with_message(
+                ErrorCode::DatabaseError,
+                format!("Failed to apply product schedules: {}", e),
+            )
+        };
+
+        conn.transaction::<_, Error, _>(|conn| {
+            let published = diesel::update(products::table)
+                .filter(products::visibility.eq(ProductVisibility::Scheduled))
+                .filter(products::publish_at.le(now))
+                .set(products::visibility.eq(ProductVisibility::Published))
+                .execute(conn)
+                .map_err(to_error)?;
+
+            // Runs after publishing so a launch whose window has already closed ends hidden
+            let hidden = diesel::update(products::table)
+                .filter(products::visibility.eq(ProductVisibility::Published))
+                .filter(products::unpublish_at.le(now))
+                .set(products::visibility.eq(ProductVisibility::Hidden))
+                .execute(conn)
+                .map_err(to_error)?;
+
+            Ok(published + hidden)
+        })
+    }
+
+    async fn find_archived(&self, offset: i64, limit: i64) -> Result<Vec<ProductListItem>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let product_models: Vec<PricedProductModel> = products::table
+            .filter(products::archived_at.is_not_null())
+            .select((ProductModel::as_select(), effective_price()))
+            .order((products::archived_at.desc(), products::id.desc()))
+            .offset(offset)
+            .limit(limit)
+            .load(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to fetch archived products: {}", e),
+                )
+            })?;
+
+        Ok(product_models.into_iter().map(Into::into).collect())
+    }
+
+    async fn count_archived(&self) -> Result<i64, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        products::table
+            .filter(products::archived_at.is_not_null())
+            .count()
+            .get_result(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to count archived products: {}", e),
+                )
+            })
+    }
+
+    async fn delete(&self, product_id: i64) -> Result<(), Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction::<_, Error, _>(|conn| {
+            let product_variants = variants::table
+                .filter(variants::product_id.eq(product_id))
+                .select(variants::variant_id);
+
+            // Order lines keep pointing at the variants they sold, so those must stay
+            let ordered: i64 = order_items::table
+                .filter(order_items::variant_id.eq_any(product_variants))
+                .count()
+                .get_result(conn)?;
+            let sold_in_bundles: i64 = order_item_components::table
+                .filter(order_item_components::variant_id.eq_any(product_variants))
+                .count()
+                .get_result(conn)?;
+            if ordered + sold_in_bundles > 0 {
+                return Err(Error::with_message(
+                    ErrorCode::ResourceAlreadyExists,
+                    "Product has been ordered and cannot be deleted; archive it instead",
+                ));
+            }
+
+            let in_bundles: i64 = bundle_components::table
+                .filter(bundle_components::product_id.eq(product_id))
+                .count()
+                .get_result(conn)?;
+            if in_bundles > 0 {
+                return Err(Error::with_message(
+                    ErrorCode::ResourceAlreadyExists,
+                    "Product is part of a bundle and cannot be deleted",
+                ));
+            }
+
+            diesel::delete(
+                cart_items::table.filter(cart_items::variant_id.eq_any(product_variants)),
+            )
+            .execute(conn)?;
+
+            // Variants, favorites and slug redirects go with the product
+            let deleted_rows = diesel::delete(products::table)
+                .filter(products::id.eq(product_id))
+                .execute(conn)
+                .map_err(|e| {
+                    Error::with_message(
+                        ErrorCode::DatabaseError,
+                        format!("Failed to delete product: {}", e),
+                    )
+                })?;
+
+            if deleted_rows == 0 {
+                return Err(Error::with_message(
+                    ErrorCode::ResourceNotFound,
+                    "Product not found",
+                ));
+            }
+
+            Ok(())
+        })
     }
 
     async fn search_by_name(
@@ -331,9 +1594,9 @@ impl ProductRepository for DieselProductRepository {
 
         let search_pattern = format!("%{}%", name);
 
-        let product_models: Vec<ProductModel> = products::table
+        let product_models: Vec<PricedProductModel> = products::table
             .filter(products::name.ilike(&search_pattern))
-            .select(ProductModel::as_select())
+            .select((ProductModel::as_select(), effective_price()))
             .order(products::id.desc())
             .offset(offset)
             .limit(limit)
@@ -348,12 +1611,81 @@ impl ProductRepository for DieselProductRepository {
         Ok(product_models.into_iter().map(Into::into).collect())
     }
 
-    async fn count_total(&self) -> Result<i64, Error> {
+    async fn search(
+        &self,
+        query: &str,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<ProductSearchHit>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let sql = format!(
+            "SELECT p.id, p.name, p.price, {effective_price} AS effective_price, p.status, \
+                 p.category, p.preview_image, \
+                 ts_headline('simple', p.name, websearch_to_tsquery('simple', $1), \
+                     '{highlight}, HighlightAll=true') AS highlighted_name, \
+                 ts_headline('simple', p.description, websearch_to_tsquery('simple', $1), \
+                     '{highlight}, MaxWords=30, MinWords=10, MaxFragments=2') AS snippet, \
+                 (ts_rank(product_search_document(p.name, p.category, p.description), \
+                     websearch_to_tsquery('simple', $1)) \
+                  + word_similarity($1, p.name))::REAL AS rank \
+             FROM products p \
+             WHERE {on_sale} AND ({matches}) \
+             ORDER BY rank DESC, p.id DESC \
+             OFFSET $3 LIMIT $4",
+            effective_price = EFFECTIVE_PRICE,
+            highlight = HIGHLIGHT_OPTIONS,
+            on_sale = ON_SALE,
+            matches = SEARCH_MATCH,
+        );
+
+        let rows: Vec<SearchHitRow> = diesel::sql_query(sql)
+            .bind::<Text, _>(query)
+            .bind::<Text, _>(like_pattern(query))
+            .bind::<BigInt, _>(offset)
+            .bind::<BigInt, _>(limit)
+            .load(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to search products: {}", e),
+                )
+            })?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn count_search(&self, query: &str) -> Result<i64, Error> {
         let mut conn = self.pool.get().map_err(|e| {
             Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
         })?;
 
-        let count = products::table
+        let sql = format!(
+            "SELECT COUNT(*) AS total FROM products p WHERE {} AND ({})",
+            ON_SALE, SEARCH_MATCH
+        );
+        let row: SearchCountRow = diesel::sql_query(sql)
+            .bind::<Text, _>(query)
+            .bind::<Text, _>(like_pattern(query))
+            .get_result(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to count search results: {}", e),
+                )
+            })?;
+
+        Ok(row.total)
+    }
+
+    async fn count_total(&self, filter: &ProductFilter) -> Result<i64, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let count = filtered_products(&mut conn, filter)?
             .count()
             .get_result::<i64>(&mut conn)
             .map_err(|e| {
@@ -365,4 +1697,160 @@ impl ProductRepository for DieselProductRepository {
 
         Ok(count)
     }
+
+    async fn fill_missing_slugs(&self) -> Result<usize, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let missing: Vec<(i64, String)> = products::table
+            .filter(products::slug.is_null())
+            .select((products::id, products::name))
+            .order(products::id.asc())
+            .load(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to fetch products without slugs: {}", e),
+                )
+            })?;
+
+        for (product_id, name) in &missing {
+            conn.transaction::<_, Error, _>(|conn| {
+                let slug = available_slug(conn, name, Some(*product_id))?;
+                diesel::update(products::table)
+                    .filter(products::id.eq(product_id))
+                    .set(products::slug.eq(slug))
+                    .execute(conn)
+                    .map_err(|e| {
+                        Error::with_message(
+                            ErrorCode::DatabaseError,
+                            format!("Failed to set product slug: {}", e),
+                        )
+                    })?;
+                Ok(())
+            })?;
+        }
+
+        Ok(missing.len())
+    }
+
+    async fn import(
+        &self,
+        products: Vec<ProductImport>,
+        commit: bool,
+        imported_by: &str,
+    ) -> Result<ImportReport, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let outcome = conn.transaction::<_, ImportAbort, _>(|conn| {
+            let mut report = ImportReport::default();
+            for item in &products {
+                // Each product gets a savepoint, so a failed one doesn't hide later errors
+                let mut row = item.row;
+                match conn.transaction::<_, Error, _>(|conn| {
+                    import_product(conn, item, imported_by, &mut row)
+                }) {
+                    Ok(imported) => {
+                        if imported.created {
+                            report.products_created += 1;
+                        } else {
+                            report.products_updated += 1;
+                        }
+                        report.variants_created += imported.variants_created;
+                        report.variants_updated += imported.variants_updated;
+                    }
+                    Err(e) => report.errors.push(ImportRowError {
+                        row,
+                        message: e.to_string(),
+                    }),
+                }
+            }
+
+            if commit && report.errors.is_empty() {
+                report.applied = true;
+                Ok(report)
+            } else {
+                Err(ImportAbort::Rollback(report))
+            }
+        });
+
+        match outcome {
+            Ok(report) | Err(ImportAbort::Rollback(report)) => Ok(report),
+            Err(ImportAbort::Failed(e)) => {
+                error!(error = %e, "Product import failed");
+                Err(e)
+            }
+        }
+    }
+
+    async fn export_rows(&self) -> Result<Vec<ProductSheetRow>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let product_rows: Vec<ProductModel> = products::table
+            .filter(products::archived_at.is_null())
+            .select(ProductModel::as_select())
+            .order(products::id.asc())
+            .load(&mut conn)?;
+        let variant_rows: Vec<VariantModel> = variants::table
+            .inner_join(products::table)
+            .filter(products::archived_at.is_null())
+            .select(VariantModel::as_select())
+            .order((variants::product_id.asc(), variants::variant_id.asc()))
+            .load(&mut conn)?;
+
+        let mut by_product: HashMap<i64, Vec<VariantModel>> = HashMap::new();
+        for variant in variant_rows {
+            by_product
+                .entry(variant.product_id)
+                .or_default()
+                .push(variant);
+        }
+
+        let mut rows = Vec::with_capacity(product_rows.len());
+        for model in product_rows {
+            let product = SheetProduct {
+                slug: model.slug,
+                name: model.name,
+                description: model.description,
+                kind: Some(model.kind),
+                base_price: model.price,
+                member_price: model.member_price,
+                status: Some(model.status),
+                category_id: model.category_id,
+                visibility: Some(model.visibility),
+                publish_at: model.publish_at,
+                unpublish_at: model.unpublish_at,
+            };
+
+            // Products without variants still get a row so they round-trip
+            let variants = by_product.remove(&model.id).unwrap_or_default();
+            if variants.is_empty() {
+                rows.push(ProductSheetRow {
+                    product,
+                    variant: None,
+                });
+                continue;
+            }
+            for variant in variants {
+                rows.push(ProductSheetRow {
+                    product: product.clone(),
+                    variant: Some(SheetVariant {
+                        sku: variant.sku,
+                        size: variant.size,
+                        color: variant.color,
+                        stock_quantity: Some(variant.stock_quantity),
+                        price_override: variant.price_override,
+                        member_price_override: variant.member_price_override,
+                    }),
+                });
+            }
+        }
+
+        Ok(rows)
+    }
 }
@@ -2,54 +2,237 @@ use bigdecimal::BigDecimal;
 use diesel_derive_enum::DbEnum;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, DbEnum)]
+use crate::core::bundle::entity::BundleComponent;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
 #[ExistingTypePath = "crate::schema::sql_types::ProductStatus"]
 #[derive(Default)]
 pub enum ProductStatus {
     #[db_rename = "PREORDER"]
+    #[serde(alias = "PREORDER")]
     Preorder,
     #[db_rename = "IN_STOCK"]
+    #[serde(alias = "IN_STOCK")]
     #[default]
     InStock,
     #[db_rename = "OUT_OF_STOCK"]
+    #[serde(alias = "OUT_OF_STOCK")]
     OutOfStock,
 }
 
+/// Whether the storefront shows a product, separate from its stock status.
+///
+/// A scheduled product goes live at `publish_at`; any product with `unpublish_at` is taken
+/// down at that time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::ProductVisibility"]
+#[derive(Default)]
+pub enum ProductVisibility {
+    #[db_rename = "DRAFT"]
+    #[serde(alias = "DRAFT")]
+    #[default]
+    Draft,
+    #[db_rename = "SCHEDULED"]
+    #[serde(alias = "SCHEDULED")]
+    Scheduled,
+    #[db_rename = "PUBLISHED"]
+    #[serde(alias = "PUBLISHED")]
+    Published,
+    #[db_rename = "HIDDEN"]
+    #[serde(alias = "HIDDEN")]
+    Hidden,
+}
+
+/// A bundle sells other products together at its own price; its stock is what its
+/// components can make up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::ProductKind"]
+#[derive(Default)]
+pub enum ProductKind {
+    #[db_rename = "STANDARD"]
+    #[serde(alias = "STANDARD")]
+    #[default]
+    Standard,
+    #[db_rename = "BUNDLE"]
+    #[serde(alias = "BUNDLE")]
+    Bundle,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProductSort {
+    #[default]
+    Newest,
+    PriceAsc,
+    PriceDesc,
+    /// Most units sold across paid orders first
+    Popular,
+}
+
+/// Narrows a product listing. Listing and counting take the same filter so `total` matches
+/// what is paged through.
+#[derive(Debug, Clone, Default)]
+pub struct ProductFilter {
+    /// Category slug; includes its subcategories
+    pub category: Option<String>,
+    pub status: Option<ProductStatus>,
+    /// Only bundles, or only products sold on their own
+    pub kind: Option<ProductKind>,
+    /// Bounds on the effective price, sale included, inclusive
+    pub min_price: Option<BigDecimal>,
+    pub max_price: Option<BigDecimal>,
+    /// Only products with a variant of this size and/or color that is in stock or open for
+    /// pre-order
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub sort: ProductSort,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Product {
     pub product_id: i64,
     pub name: String,
+    pub slug: Option<String>,
     pub description: Option<String>,
     pub base_price: BigDecimal,
     pub status: ProductStatus,
+    pub kind: ProductKind,
+    pub category_id: Option<i64>,
+    /// Name of the category, kept in sync with it
     pub category: Option<String>,
-    pub stock_quantity: Option<i32>,
+    /// Sum of the stock of all variants; for a bundle, how many its components can make up
+    pub stock_quantity: i64,
     pub preview_image: Option<Vec<Option<String>>>,
     pub preview_video: Option<Vec<Option<String>>>,
     pub shipping: Option<Vec<Option<String>>>,
+    /// Set while the product is archived and hidden from the storefront
+    pub archived_at: Option<chrono::NaiveDateTime>,
+    pub visibility: ProductVisibility,
+    pub publish_at: Option<chrono::NaiveDateTime>,
+    pub unpublish_at: Option<chrono::NaiveDateTime>,
+    pub compare_at_price: Option<BigDecimal>,
+    pub sale_price: Option<BigDecimal>,
+    pub sale_starts_at: Option<chrono::NaiveDateTime>,
+    pub sale_ends_at: Option<chrono::NaiveDateTime>,
+    /// What the product sells for right now: the sale price while a sale runs, otherwise
+    /// the base price
+    pub effective_price: BigDecimal,
+    pub member_price: Option<BigDecimal>,
+    /// What verified student members pay now, when lower than `effective_price`
+    pub member_effective_price: Option<BigDecimal>,
+    /// Average of the approved reviews, absent until there is one
+    pub rating_average: Option<BigDecimal>,
+    pub rating_count: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewProduct {
     pub name: String,
+    /// Generated from the name when absent
+    pub slug: Option<String>,
     pub description: Option<String>,
     pub base_price: BigDecimal,
     pub status: Option<ProductStatus>,
-    pub category: Option<String>,
-    pub stock_quantity: Option<i32>,
+    pub category_id: Option<i64>,
     pub preview_image: Option<Vec<Option<String>>>,
     pub preview_video: Option<Vec<Option<String>>>,
     pub shipping: Option<Vec<Option<String>>>,
+    /// Defaults to scheduled when `publish_at` is given, otherwise draft
+    pub visibility: Option<ProductVisibility>,
+    pub publish_at: Option<chrono::NaiveDateTime>,
+    pub unpublish_at: Option<chrono::NaiveDateTime>,
+}
+
+/// Replaces a product's visibility and schedule as a whole, so a timestamp is cleared by
+/// leaving it out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductSchedule {
+    pub visibility: ProductVisibility,
+    pub publish_at: Option<chrono::NaiveDateTime>,
+    pub unpublish_at: Option<chrono::NaiveDateTime>,
+}
+
+/// Replaces a product's compare-at, sale and member prices as a whole; leaving a field out
+/// clears it. The sale runs from `sale_starts_at` to `sale_ends_at`, open-ended when either
+/// is absent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProductPricing {
+    pub compare_at_price: Option<BigDecimal>,
+    pub sale_price: Option<BigDecimal>,
+    pub sale_starts_at: Option<chrono::NaiveDateTime>,
+    pub sale_ends_at: Option<chrono::NaiveDateTime>,
+    /// Paid by verified student members instead, unless the sale price is lower
+    pub member_price: Option<BigDecimal>,
+}
+
+/// Which price a unit was sold at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::PriceTier"]
+pub enum PriceTier {
+    #[db_rename = "REGULAR"]
+    #[default]
+    Regular,
+    #[db_rename = "SALE"]
+    Sale,
+    #[db_rename = "MEMBER"]
+    Member,
+}
+
+/// The prices a unit can sell for right now
+#[derive(Debug, Clone)]
+pub struct UnitPrice {
+    pub regular: BigDecimal,
+    /// The sale price while a sale runs and is lower, otherwise `regular`
+    pub effective: BigDecimal,
+    pub member: Option<BigDecimal>,
+}
+
+impl UnitPrice {
+    /// The member price, when it beats what everyone else pays
+    pub fn member_price(&self) -> Option<&BigDecimal> {
+        self.member
+            .as_ref()
+            .filter(|member| *member < &self.effective)
+    }
+
+    /// What a buyer pays per unit and under which tier. Members get the member price only
+    /// when it is lower than the sale price.
+    pub fn resolve(&self, is_member: bool) -> (BigDecimal, PriceTier) {
+        match self.member_price() {
+            Some(member) if is_member => (member.clone(), PriceTier::Member),
+            _ if self.effective < self.regular => (self.effective.clone(), PriceTier::Sale),
+            _ => (self.regular.clone(), PriceTier::Regular),
+        }
+    }
+}
+
+/// A product's prices as they were from `changed_at` until the next entry. Entries with a
+/// `variant_id` record a change to that variant's overrides and last until its next entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceHistoryEntry {
+    pub history_id: i64,
+    pub product_id: i64,
+    pub price: BigDecimal,
+    pub compare_at_price: Option<BigDecimal>,
+    pub sale_price: Option<BigDecimal>,
+    pub sale_starts_at: Option<chrono::NaiveDateTime>,
+    pub sale_ends_at: Option<chrono::NaiveDateTime>,
+    pub member_price: Option<BigDecimal>,
+    pub variant_id: Option<i64>,
+    pub price_override: Option<BigDecimal>,
+    pub member_price_override: Option<BigDecimal>,
+    pub changed_at: chrono::NaiveDateTime,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateProduct {
     pub name: Option<String>,
+    /// When absent, a new name regenerates the slug. The old slug keeps redirecting.
+    pub slug: Option<String>,
     pub description: Option<String>,
     pub base_price: Option<BigDecimal>,
     pub status: Option<ProductStatus>,
-    pub category: Option<String>,
-    pub stock_quantity: Option<i32>,
+    pub category_id: Option<i64>,
     pub preview_image: Option<Vec<Option<String>>>,
     pub preview_video: Option<Vec<Option<String>>>,
     pub shipping: Option<Vec<Option<String>>>,
@@ -59,45 +242,196 @@ pub struct UpdateProduct {
 pub struct ProductListItem {
     pub product_id: i64,
     pub name: String,
+    pub slug: Option<String>,
     pub base_price: BigDecimal,
     pub status: ProductStatus,
+    pub kind: ProductKind,
+    pub category_id: Option<i64>,
     pub category: Option<String>,
     pub preview_image: Option<Vec<Option<String>>>,
+    pub archived_at: Option<chrono::NaiveDateTime>,
+    pub compare_at_price: Option<BigDecimal>,
+    /// Only set while a sale is running and lowers the price, so the listing can show it next
+    /// to `base_price`
+    pub sale_price: Option<BigDecimal>,
+    pub effective_price: BigDecimal,
+    pub rating_average: Option<BigDecimal>,
+    pub rating_count: i32,
 }
 
 impl From<Product> for ProductListItem {
     fn from(product: Product) -> Self {
+        // `effective_price` is below the base price only while a sale is running
+        let sale_price = product
+            .sale_price
+            .filter(|_| product.effective_price < product.base_price);
+
         ProductListItem {
             product_id: product.product_id,
             name: product.name,
+            slug: product.slug,
             base_price: product.base_price,
             status: product.status,
+            kind: product.kind,
+            category_id: product.category_id,
             category: product.category,
             preview_image: product.preview_image,
+            archived_at: product.archived_at,
+            compare_at_price: product.compare_at_price,
+            sale_price,
+            effective_price: product.effective_price,
+            rating_average: product.rating_average,
+            rating_count: product.rating_count,
         }
     }
 }
 
+/// A search match, ranked by text relevance plus name similarity.
+///
+/// `highlighted_name` and `snippet` wrap matched terms in `<mark>` tags; `snippet` is an
+/// excerpt of the description and is absent when the product has none.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductSearchHit {
+    pub product_id: i64,
+    pub name: String,
+    pub base_price: BigDecimal,
+    pub effective_price: BigDecimal,
+    pub status: ProductStatus,
+    pub category: Option<String>,
+    pub preview_image: Option<Vec<Option<String>>>,
+    pub highlighted_name: String,
+    pub snippet: Option<String>,
+    pub rank: f32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Variant {
     pub variant_id: i64,
     pub product_id: i64,
     pub size: Option<String>,
     pub color: Option<String>,
-    pub stock_quantity: Option<i32>,
+    pub stock_quantity: i32,
+    pub sku: Option<String>,
+    /// Replaces the product's base price for this variant when set
+    pub price_override: Option<BigDecimal>,
+    /// Replaces the product's member price for this variant when set
+    pub member_price_override: Option<BigDecimal>,
+    /// What a unit sells for now, sale included; only filled in on product details
+    pub effective_price: Option<BigDecimal>,
+    /// What a unit sells for to verified members, when lower than `effective_price`; only
+    /// filled in on product details
+    pub member_effective_price: Option<BigDecimal>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProductDetail {
     pub product_id: i64,
     pub name: String,
+    pub slug: Option<String>,
     pub description: Option<String>,
     pub base_price: BigDecimal,
     pub status: ProductStatus,
+    pub kind: ProductKind,
+    pub category_id: Option<i64>,
     pub category: Option<String>,
-    pub stock_quantity: Option<i32>,
+    pub stock_quantity: i64,
     pub preview_image: Option<Vec<Option<String>>>,
     pub preview_video: Option<Vec<Option<String>>>,
     pub shipping: Option<Vec<Option<String>>>,
+    pub visibility: ProductVisibility,
+    pub publish_at: Option<chrono::NaiveDateTime>,
+    pub unpublish_at: Option<chrono::NaiveDateTime>,
+    pub compare_at_price: Option<BigDecimal>,
+    pub sale_price: Option<BigDecimal>,
+    pub sale_starts_at: Option<chrono::NaiveDateTime>,
+    pub sale_ends_at: Option<chrono::NaiveDateTime>,
+    pub effective_price: BigDecimal,
+    /// The member price as configured, shown to everyone so non-members see what they
+    /// would save
+    pub member_price: Option<BigDecimal>,
+    /// What verified members pay for the product now, when lower than `effective_price`
+    pub member_effective_price: Option<BigDecimal>,
+    /// Average of the approved reviews, absent until there is one
+    pub rating_average: Option<BigDecimal>,
+    /// Number of approved reviews
+    pub rating_count: i32,
     pub variants: Vec<Variant>,
+    /// What a bundle is made of; empty for other products
+    pub components: Vec<BundleComponent>,
+}
+
+/// Result of looking a product up by slug
+#[derive(Debug, Clone)]
+pub enum SlugLookup {
+    Found(Box<ProductDetail>),
+    /// The slug belonged to the product before a rename; holds its current slug
+    Moved(String),
+}
+
+/// Product fields of a sheet row. Rows for the same product repeat them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SheetProduct {
+    pub slug: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    /// Only checked against an existing product; bundles are made by setting their components
+    pub kind: Option<ProductKind>,
+    pub base_price: BigDecimal,
+    pub member_price: Option<BigDecimal>,
+    pub status: Option<ProductStatus>,
+    pub category_id: Option<i64>,
+    pub visibility: Option<ProductVisibility>,
+    /// Only read together with `visibility`, which sets the whole schedule
+    pub publish_at: Option<chrono::NaiveDateTime>,
+    pub unpublish_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheetVariant {
+    pub sku: Option<String>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    /// Absent leaves the stock of an existing variant unchanged
+    pub stock_quantity: Option<i32>,
+    /// Absent keeps an existing variant's override, like the SKU; overrides are cleared
+    /// through the variant API
+    pub price_override: Option<BigDecimal>,
+    pub member_price_override: Option<BigDecimal>,
+}
+
+/// One row of a product import/export sheet: a product, and one of its variants unless the
+/// variant columns are blank
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductSheetRow {
+    pub product: SheetProduct,
+    pub variant: Option<SheetVariant>,
+}
+
+/// A validated product from an import with the variants listed for it. `row` numbers are
+/// the sheet's, with the header as row 1.
+#[derive(Debug, Clone)]
+pub struct ProductImport {
+    pub row: usize,
+    pub product: SheetProduct,
+    pub variants: Vec<(usize, SheetVariant)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportRowError {
+    pub row: usize,
+    pub message: String,
+}
+
+/// Outcome of an import. Nothing is written unless every row is valid; a dry run reports
+/// the same counts without writing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub applied: bool,
+    pub rows: usize,
+    pub products_created: usize,
+    pub products_updated: usize,
+    pub variants_created: usize,
+    pub variants_updated: usize,
+    pub errors: Vec<ImportRowError>,
 }
@@ -2,6 +2,7 @@ pub mod diesel;
 pub mod entity;
 pub mod repository;
 pub mod service;
+pub mod sheet;
 
 pub use diesel::*;
 pub use entity::*;
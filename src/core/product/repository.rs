@@ -1,4 +1,8 @@
-use super::entity::{NewProduct, Product, ProductDetail, ProductListItem, UpdateProduct};
+use super::entity::{
+    ImportReport, NewProduct, PriceHistoryEntry, Product, ProductDetail, ProductFilter,
+    ProductImport, ProductListItem, ProductPricing, ProductSchedule, ProductSearchHit,
+    ProductSheetRow, SlugLookup, UpdateProduct,
+};
 use crate::utils::errors::Error;
 use async_trait::async_trait;
 
@@ -7,12 +11,37 @@ pub trait ProductRepository: Send + Sync {
     async fn create(&self, new_product: NewProduct) -> Result<Product, Error>;
     async fn find_by_id(&self, product_id: i64) -> Result<Product, Error>;
     async fn find_by_id_with_variants(&self, product_id: i64) -> Result<ProductDetail, Error>;
-    async fn find_all(&self, offset: i64, limit: i64) -> Result<Vec<ProductListItem>, Error>;
+    async fn find_by_slug(&self, slug: &str) -> Result<SlugLookup, Error>;
+    /// Like `find_by_id_with_variants`, but also finds products the storefront doesn't show
+    async fn find_preview(&self, product_id: i64) -> Result<ProductDetail, Error>;
+    async fn find_all(
+        &self,
+        filter: &ProductFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<ProductListItem>, Error>;
     async fn update(
         &self,
         product_id: i64,
         update_product: UpdateProduct,
     ) -> Result<Product, Error>;
+    /// Hides a product from the storefront; archiving an archived product keeps its timestamp
+    async fn archive(&self, product_id: i64) -> Result<Product, Error>;
+    async fn restore(&self, product_id: i64) -> Result<Product, Error>;
+    async fn set_schedule(
+        &self,
+        product_id: i64,
+        schedule: ProductSchedule,
+    ) -> Result<Product, Error>;
+    async fn set_pricing(&self, product_id: i64, pricing: ProductPricing)
+    -> Result<Product, Error>;
+    /// Every price the product has had, oldest first
+    async fn price_history(&self, product_id: i64) -> Result<Vec<PriceHistoryEntry>, Error>;
+    /// Records scheduled launches and take-downs that are due; returns how many products changed
+    async fn apply_schedules(&self) -> Result<usize, Error>;
+    async fn find_archived(&self, offset: i64, limit: i64) -> Result<Vec<ProductListItem>, Error>;
+    async fn count_archived(&self) -> Result<i64, Error>;
+    /// Removes the product for good; refused once any of its variants has been ordered
     async fn delete(&self, product_id: i64) -> Result<(), Error>;
     async fn search_by_name(
         &self,
@@ -20,5 +49,24 @@ pub trait ProductRepository: Send + Sync {
         offset: i64,
         limit: i64,
     ) -> Result<Vec<ProductListItem>, Error>;
-    async fn count_total(&self) -> Result<i64, Error>;
+    async fn search(
+        &self,
+        query: &str,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<ProductSearchHit>, Error>;
+    async fn count_search(&self, query: &str) -> Result<i64, Error>;
+    async fn count_total(&self, filter: &ProductFilter) -> Result<i64, Error>;
+    /// Gives every product without a slug one generated from its name; returns how many
+    async fn fill_missing_slugs(&self) -> Result<usize, Error>;
+    /// Upserts the products in one transaction, each under its own savepoint so every failing
+    /// row is reported. Nothing is kept unless `commit` is set and no row failed.
+    async fn import(
+        &self,
+        products: Vec<ProductImport>,
+        commit: bool,
+        imported_by: &str,
+    ) -> Result<ImportReport, Error>;
+    /// Every product that isn't archived, one row per variant
+    async fn export_rows(&self) -> Result<Vec<ProductSheetRow>, Error>;
 }
@@ -1,11 +1,21 @@
 use super::entity::{
-    NewProduct, Product, ProductDetail, ProductListItem, ProductStatus, UpdateProduct,
+    ImportReport, ImportRowError, NewProduct, PriceHistoryEntry, Product, ProductDetail,
+    ProductFilter, ProductImport, ProductListItem, ProductPricing, ProductSchedule,
+    ProductSearchHit, ProductSheetRow, ProductStatus, ProductVisibility, SlugLookup, UpdateProduct,
 };
 use super::repository::ProductRepository;
+use super::sheet::{SheetFormat, read_sheet, write_sheet};
+use crate::core::variant::service::{
+    MAX_COLOR_LEN, MAX_SIZE_LEN, MAX_SKU_LEN, option_value, validate_member_price_override,
+    validate_price_override,
+};
 use crate::utils::errors::{Error, ErrorCode};
-use bigdecimal::BigDecimal;
+use crate::utils::slug::{MAX_SLUG_LEN, slugify};
+use bigdecimal::{BigDecimal, Zero};
 use std::sync::Arc;
 
+const MAX_IMPORT_ROWS: usize = 5000;
+
 #[derive(Clone)]
 pub struct ProductService {
     repository: Arc<dyn ProductRepository>,
@@ -20,6 +30,23 @@ impl ProductService {
         // Business validation
         self.validate_product_data(&new_product.name, &new_product.base_price)?;
 
+        new_product.slug = Self::normalize_slug(new_product.slug.take())?;
+
+        // New products stay off the storefront until published or scheduled
+        let visibility = new_product
+            .visibility
+            .unwrap_or(if new_product.publish_at.is_some() {
+                ProductVisibility::Scheduled
+            } else {
+                ProductVisibility::Draft
+            });
+        Self::validate_schedule(&ProductSchedule {
+            visibility,
+            publish_at: new_product.publish_at,
+            unpublish_at: new_product.unpublish_at,
+        })?;
+        new_product.visibility = Some(visibility);
+
         // Set default status if not provided
         if new_product.status.is_none() {
             new_product.status = Some(ProductStatus::InStock);
@@ -52,8 +79,24 @@ impl ProductService {
         self.repository.find_by_id_with_variants(product_id).await
     }
 
+    pub async fn preview_product(&self, product_id: i64) -> Result<ProductDetail, Error> {
+        if product_id <= 0 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Invalid product ID",
+            ));
+        }
+
+        self.repository.find_preview(product_id).await
+    }
+
+    pub async fn get_product_by_slug(&self, slug: &str) -> Result<SlugLookup, Error> {
+        self.repository.find_by_slug(&slug.to_lowercase()).await
+    }
+
     pub async fn list_products(
         &self,
+        filter: ProductFilter,
         page: u32,
         page_size: u32,
     ) -> Result<ProductListResponse, Error> {
@@ -64,11 +107,32 @@ impl ProductService {
             ));
         }
 
+        if filter
+            .min_price
+            .iter()
+            .chain(filter.max_price.iter())
+            .any(|price| *price < BigDecimal::zero())
+        {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Price bounds cannot be negative",
+            ));
+        }
+
+        if let (Some(min_price), Some(max_price)) = (&filter.min_price, &filter.max_price)
+            && min_price > max_price
+        {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "min_price cannot be greater than max_price",
+            ));
+        }
+
         let offset = i64::from(page.saturating_sub(1) * page_size);
         let limit = i64::from(page_size);
 
-        let products = self.repository.find_all(offset, limit).await?;
-        let total = self.repository.count_total().await?;
+        let products = self.repository.find_all(&filter, offset, limit).await?;
+        let total = self.repository.count_total(&filter).await?;
 
         Ok(ProductListResponse {
             products,
@@ -83,7 +147,7 @@ impl ProductService {
     pub async fn update_product(
         &self,
         product_id: i64,
-        update_product: UpdateProduct,
+        mut update_product: UpdateProduct,
     ) -> Result<Product, Error> {
         if product_id <= 0 {
             return Err(Error::with_message(
@@ -99,12 +163,120 @@ impl ProductService {
             self.validate_product_data(name, base_price)?;
         }
 
+        update_product.slug = Self::normalize_slug(update_product.slug.take())?;
+
         // Check if product exists
         self.repository.find_by_id(product_id).await?;
 
         self.repository.update(product_id, update_product).await
     }
 
+    pub async fn set_product_schedule(
+        &self,
+        product_id: i64,
+        schedule: ProductSchedule,
+    ) -> Result<Product, Error> {
+        if product_id <= 0 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Invalid product ID",
+            ));
+        }
+
+        Self::validate_schedule(&schedule)?;
+
+        self.repository.set_schedule(product_id, schedule).await
+    }
+
+    /// Replaces a product's compare-at, sale and member prices. Each change is kept in the price
+    /// history along with changes to the base price.
+    pub async fn set_product_pricing(
+        &self,
+        product_id: i64,
+        pricing: ProductPricing,
+    ) -> Result<Product, Error> {
+        if product_id <= 0 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Invalid product ID",
+            ));
+        }
+
+        let product = self.repository.find_by_id(product_id).await?;
+        Self::validate_pricing(&pricing, &product.base_price)?;
+
+        self.repository.set_pricing(product_id, pricing).await
+    }
+
+    pub async fn get_price_history(
+        &self,
+        product_id: i64,
+    ) -> Result<Vec<PriceHistoryEntry>, Error> {
+        if product_id <= 0 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Invalid product ID",
+            ));
+        }
+
+        self.repository.price_history(product_id).await
+    }
+
+    pub async fn apply_schedules(&self) -> Result<usize, Error> {
+        self.repository.apply_schedules().await
+    }
+
+    pub async fn archive_product(&self, product_id: i64) -> Result<Product, Error> {
+        if product_id <= 0 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Invalid product ID",
+            ));
+        }
+
+        self.repository.archive(product_id).await
+    }
+
+    pub async fn restore_product(&self, product_id: i64) -> Result<Product, Error> {
+        if product_id <= 0 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Invalid product ID",
+            ));
+        }
+
+        self.repository.restore(product_id).await
+    }
+
+    pub async fn list_archived_products(
+        &self,
+        page: u32,
+        page_size: u32,
+    ) -> Result<ProductListResponse, Error> {
+        if page_size == 0 || page_size > 100 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Page size must be between 1 and 100",
+            ));
+        }
+
+        let offset = i64::from(page.saturating_sub(1) * page_size);
+        let limit = i64::from(page_size);
+
+        let products = self.repository.find_archived(offset, limit).await?;
+        let total = self.repository.count_archived().await?;
+
+        Ok(ProductListResponse {
+            products,
+            total,
+            page,
+            page_size,
+            total_pages: u32::try_from((total + i64::from(page_size) - 1) / i64::from(page_size))
+                .unwrap_or(u32::MAX),
+        })
+    }
+
+    /// Permanently deletes a product. Products that have been ordered can only be archived.
     pub async fn delete_product(&self, product_id: i64) -> Result<(), Error> {
         if product_id <= 0 {
             return Err(Error::with_message(
@@ -121,14 +293,22 @@ impl ProductService {
 
     pub async fn search_products(
         &self,
-        name: &str,
+        query: &str,
         page: u32,
         page_size: u32,
-    ) -> Result<Vec<ProductListItem>, Error> {
-        if name.trim().is_empty() {
+    ) -> Result<ProductSearchResponse, Error> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Search query cannot be empty",
+            ));
+        }
+
+        if query.chars().count() > 100 {
             return Err(Error::with_message(
                 ErrorCode::ValidationError,
-                "Search name cannot be empty",
+                "Search query must be 100 characters or less",
             ));
         }
 
@@ -142,7 +322,295 @@ impl ProductService {
         let offset = i64::from(page.saturating_sub(1) * page_size);
         let limit = i64::from(page_size);
 
-        self.repository.search_by_name(name, offset, limit).await
+        let products = self.repository.search(query, offset, limit).await?;
+        let total = self.repository.count_search(query).await?;
+
+        Ok(ProductSearchResponse {
+            products,
+            total,
+            page,
+            page_size,
+            total_pages: u32::try_from((total + i64::from(page_size) - 1) / i64::from(page_size))
+                .unwrap_or(u32::MAX),
+        })
+    }
+
+    pub async fn fill_missing_slugs(&self) -> Result<usize, Error> {
+        self.repository.fill_missing_slugs().await
+    }
+
+    /// Imports a product sheet. Every row is checked with the same rules as the product and
+    /// variant endpoints, and the import is written only if all of them pass. Products match
+    /// existing ones by slug, or by the SKU of one of their variants; variants match by SKU,
+    /// or by size and color.
+    pub async fn import_products(
+        &self,
+        format: SheetFormat,
+        data: &[u8],
+        dry_run: bool,
+        imported_by: &str,
+    ) -> Result<ImportReport, Error> {
+        let rows = read_sheet(format, data)?;
+        if rows.is_empty() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "The sheet has no product rows",
+            ));
+        }
+        if rows.len() > MAX_IMPORT_ROWS {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("A sheet can have at most {} rows", MAX_IMPORT_ROWS),
+            ));
+        }
+
+        let row_count = rows.len();
+        let mut errors = Vec::new();
+        let mut products: Vec<ProductImport> = Vec::new();
+        for (row, parsed) in rows {
+            if let Err(e) = parsed
+                .and_then(|sheet_row| self.validate_sheet_row(sheet_row))
+                .and_then(|sheet_row| Self::add_import_row(&mut products, row, sheet_row))
+            {
+                errors.push(ImportRowError {
+                    row,
+                    message: e.to_string(),
+                });
+            }
+        }
+
+        // Valid rows are still tried against the database so the report is complete, but
+        // nothing is kept while any row is invalid
+        let commit = !dry_run && errors.is_empty();
+        let mut report = self
+            .repository
+            .import(products, commit, imported_by)
+            .await?;
+
+        report.errors.append(&mut errors);
+        report.errors.sort_by_key(|error| error.row);
+        report.dry_run = dry_run;
+        report.rows = row_count;
+        Ok(report)
+    }
+
+    /// Exports every product that isn't archived in the layout `import_products` reads
+    pub async fn export_products(&self, format: SheetFormat) -> Result<Vec<u8>, Error> {
+        let rows = self.repository.export_rows().await?;
+        write_sheet(format, &rows)
+    }
+
+    fn validate_sheet_row(&self, mut row: ProductSheetRow) -> Result<ProductSheetRow, Error> {
+        let product = &mut row.product;
+        self.validate_product_data(&product.name, &product.base_price)?;
+        product.slug = Self::normalize_slug(product.slug.take())?;
+        if product
+            .member_price
+            .as_ref()
+            .is_some_and(|member| *member <= BigDecimal::zero() || *member >= product.base_price)
+        {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Member price must be greater than 0 and lower than the base price",
+            ));
+        }
+
+        match product.visibility {
+            Some(visibility) => Self::validate_schedule(&ProductSchedule {
+                visibility,
+                publish_at: product.publish_at,
+                unpublish_at: product.unpublish_at,
+            })?,
+            None if product.publish_at.is_some() || product.unpublish_at.is_some() => {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "publish_at and unpublish_at need a visibility",
+                ));
+            }
+            None => {}
+        }
+
+        if let Some(variant) = &mut row.variant {
+            variant.sku = option_value(variant.sku.take(), "SKU", MAX_SKU_LEN)?;
+            variant.size = option_value(variant.size.take(), "Size", MAX_SIZE_LEN)?;
+            variant.color = option_value(variant.color.take(), "Color", MAX_COLOR_LEN)?;
+            validate_price_override(variant.price_override.as_ref())?;
+            validate_member_price_override(variant.member_price_override.as_ref())?;
+
+            if variant.stock_quantity.is_some_and(|stock| stock < 0) {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "Stock cannot be negative",
+                ));
+            }
+        }
+
+        Ok(row)
+    }
+
+    // Rows with the same slug, or with no slug and the same name, describe one product and
+    // must agree on its fields
+    fn add_import_row(
+        products: &mut Vec<ProductImport>,
+        row: usize,
+        sheet_row: ProductSheetRow,
+    ) -> Result<(), Error> {
+        let ProductSheetRow { product, variant } = sheet_row;
+
+        if let Some(sku) = variant.as_ref().and_then(|variant| variant.sku.as_ref())
+            && let Some((earlier, _)) = products
+                .iter()
+                .flat_map(|item| &item.variants)
+                .find(|(_, other)| other.sku.as_ref() == Some(sku))
+        {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("SKU {} is already used on row {}", sku, earlier),
+            ));
+        }
+
+        let existing = products.iter_mut().find(|item| match &product.slug {
+            Some(slug) => item.product.slug.as_ref() == Some(slug),
+            None => item.product.slug.is_none() && item.product.name == product.name,
+        });
+        let Some(item) = existing else {
+            products.push(ProductImport {
+                row,
+                product,
+                variants: variant.map(|variant| (row, variant)).into_iter().collect(),
+            });
+            return Ok(());
+        };
+
+        if item.product != product {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Product details differ from row {}", item.row),
+            ));
+        }
+
+        let Some(variant) = variant else {
+            return Ok(());
+        };
+        if let Some((earlier, _)) = item
+            .variants
+            .iter()
+            .find(|(_, other)| other.size == variant.size && other.color == variant.color)
+        {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Size and color are already used on row {}", earlier),
+            ));
+        }
+        item.variants.push((row, variant));
+
+        Ok(())
+    }
+
+    fn validate_schedule(schedule: &ProductSchedule) -> Result<(), Error> {
+        match (schedule.visibility, schedule.publish_at) {
+            (ProductVisibility::Scheduled, None) => {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "Scheduled products need a publish_at time",
+                ));
+            }
+            (ProductVisibility::Scheduled, Some(_)) | (_, None) => {}
+            (_, Some(_)) => {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "publish_at is only used by scheduled products",
+                ));
+            }
+        }
+
+        if let (Some(publish_at), Some(unpublish_at)) = (schedule.publish_at, schedule.unpublish_at)
+            && unpublish_at <= publish_at
+        {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "unpublish_at must be after publish_at",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validate_pricing(pricing: &ProductPricing, base_price: &BigDecimal) -> Result<(), Error> {
+        if pricing
+            .member_price
+            .as_ref()
+            .is_some_and(|member| *member <= BigDecimal::zero() || member >= base_price)
+        {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Member price must be greater than 0 and lower than the base price",
+            ));
+        }
+
+        if pricing
+            .compare_at_price
+            .as_ref()
+            .is_some_and(|compare_at| compare_at <= base_price)
+        {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Compare-at price must be higher than the base price",
+            ));
+        }
+
+        let Some(sale_price) = &pricing.sale_price else {
+            if pricing.sale_starts_at.is_some() || pricing.sale_ends_at.is_some() {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "Sale times need a sale_price",
+                ));
+            }
+            return Ok(());
+        };
+
+        if *sale_price <= BigDecimal::zero() || sale_price >= base_price {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Sale price must be greater than 0 and lower than the base price",
+            ));
+        }
+
+        if let (Some(starts_at), Some(ends_at)) = (pricing.sale_starts_at, pricing.sale_ends_at)
+            && ends_at <= starts_at
+        {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "sale_ends_at must be after sale_starts_at",
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Chosen slugs go through the same rules as generated ones, so "Summer Tee" becomes
+    // "summer-tee"
+    fn normalize_slug(slug: Option<String>) -> Result<Option<String>, Error> {
+        let Some(slug) = slug else {
+            return Ok(None);
+        };
+
+        if slug.chars().count() > MAX_SLUG_LEN {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Slug must be {} characters or less", MAX_SLUG_LEN),
+            ));
+        }
+
+        let normalized = slugify(&slug);
+        if normalized.is_empty() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Slug must contain letters or digits",
+            ));
+        }
+
+        Ok(Some(normalized))
     }
 
     fn validate_product_data(&self, name: &str, base_price: &BigDecimal) -> Result<(), Error> {
@@ -160,7 +628,7 @@ impl ProductService {
             ));
         }
 
-        if *base_price <= BigDecimal::from(0) {
+        if *base_price <= BigDecimal::zero() {
             return Err(Error::with_message(
                 ErrorCode::ValidationError,
                 "Product price must be greater than 0",
@@ -179,3 +647,12 @@ pub struct ProductListResponse {
     pub page_size: u32,
     pub total_pages: u32,
 }
+
+#[derive(Debug, serde::Serialize)]
+pub struct ProductSearchResponse {
+    pub products: Vec<ProductSearchHit>,
+    pub total: i64,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_pages: u32,
+}
@@ -0,0 +1,538 @@
+use std::io::Cursor;
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+use calamine::{Reader, Xlsx, open_workbook_from_rs};
+use chrono::{DateTime, NaiveDateTime};
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use serde::{Deserialize, Serialize};
+
+use super::entity::{
+    ProductKind, ProductSheetRow, ProductStatus, ProductVisibility, SheetProduct, SheetVariant,
+};
+use crate::utils::errors::{Error, ErrorCode};
+
+/// Columns of a product sheet, in export order. Imports match headers by name in any order;
+/// only `name` and `base_price` are required. Schedule times are UTC.
+pub const COLUMNS: [&str; 17] = [
+    "slug",
+    "name",
+    "description",
+    "kind",
+    "base_price",
+    "member_price",
+    "status",
+    "category_id",
+    "visibility",
+    "publish_at",
+    "unpublish_at",
+    "sku",
+    "size",
+    "color",
+    "stock_quantity",
+    "price_override",
+    "member_price_override",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SheetFormat {
+    Csv,
+    Xlsx,
+}
+
+impl SheetFormat {
+    /// Picks the format from an uploaded file's extension
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let (_, extension) = filename.rsplit_once('.')?;
+        Self::from_str(extension).ok()
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            SheetFormat::Csv => "text/csv; charset=utf-8",
+            SheetFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            SheetFormat::Csv => "csv",
+            SheetFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+impl FromStr for SheetFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "csv" => Ok(SheetFormat::Csv),
+            "xlsx" => Ok(SheetFormat::Xlsx),
+            _ => Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Unsupported sheet format: {}", s),
+            )),
+        }
+    }
+}
+
+/// A sheet row number with the row as read
+pub type SheetRecord = (usize, Result<ProductSheetRow, Error>);
+
+/// Reads the rows of a product sheet, each with its row number. A row that can't be read
+/// is returned as an error so the rest of the sheet can still be checked; the whole read
+/// fails only when the file itself or its header is unusable.
+pub fn read_sheet(format: SheetFormat, data: &[u8]) -> Result<Vec<SheetRecord>, Error> {
+    let grid = match format {
+        SheetFormat::Csv => csv_grid(data)?,
+        SheetFormat::Xlsx => xlsx_grid(data)?,
+    };
+
+    let mut rows = grid
+        .into_iter()
+        .filter(|(_, cells)| cells.iter().any(|cell| !cell.trim().is_empty()));
+
+    let Some((_, header)) = rows.next() else {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "The sheet is empty",
+        ));
+    };
+    let columns = SheetColumns::new(&header)?;
+
+    Ok(rows
+        .map(|(row, cells)| (row, columns.parse(&cells)))
+        .collect())
+}
+
+/// Writes rows in the same layout `read_sheet` reads
+pub fn write_sheet(format: SheetFormat, rows: &[ProductSheetRow]) -> Result<Vec<u8>, Error> {
+    match format {
+        SheetFormat::Csv => write_csv(rows),
+        SheetFormat::Xlsx => write_xlsx(rows).map_err(|e| {
+            Error::with_message(
+                ErrorCode::InternalError,
+                format!("Failed to write spreadsheet: {}", e),
+            )
+        }),
+    }
+}
+
+fn csv_grid(data: &[u8]) -> Result<Vec<(usize, Vec<String>)>, Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data);
+
+    let mut grid = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let row = index + 1;
+        let record = record.map_err(|e| {
+            Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Row {}: invalid CSV: {}", row, e),
+            )
+        })?;
+        grid.push((row, record.iter().map(ToString::to_string).collect()));
+    }
+
+    Ok(grid)
+}
+
+fn xlsx_grid(data: &[u8]) -> Result<Vec<(usize, Vec<String>)>, Error> {
+    let unreadable = |e: calamine::XlsxError| {
+        Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Invalid XLSX file: {}", e),
+        )
+    };
+
+    let mut workbook: Xlsx<_> = open_workbook_from_rs(Cursor::new(data)).map_err(unreadable)?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| {
+            Error::with_message(ErrorCode::ValidationError, "The workbook has no sheets")
+        })?
+        .map_err(unreadable)?;
+
+    // The range starts at the first used cell, which need not be A1
+    let first_row = range.start().map_or(0, |(row, _)| row as usize);
+    Ok(range
+        .rows()
+        .enumerate()
+        .map(|(index, cells)| {
+            (
+                first_row + index + 1,
+                cells.iter().map(ToString::to_string).collect(),
+            )
+        })
+        .collect())
+}
+
+// Where each known column is in the uploaded sheet
+struct SheetColumns {
+    positions: [Option<usize>; COLUMNS.len()],
+}
+
+impl SheetColumns {
+    fn new(header: &[String]) -> Result<Self, Error> {
+        let positions = COLUMNS.map(|name| {
+            header.iter().position(|cell| {
+                cell.trim_start_matches('\u{feff}')
+                    .trim()
+                    .eq_ignore_ascii_case(name)
+            })
+        });
+
+        let columns = SheetColumns { positions };
+        for required in ["name", "base_price"] {
+            if columns.position(required).is_none() {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    format!("The sheet has no '{}' column", required),
+                ));
+            }
+        }
+
+        Ok(columns)
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        let index = COLUMNS.iter().position(|column| *column == name)?;
+        self.positions[index]
+    }
+
+    // Blank cells and missing columns both read as unset
+    fn text(&self, cells: &[String], name: &str) -> Option<String> {
+        self.position(name)
+            .and_then(|position| cells.get(position))
+            .map(|cell| cell.trim().to_string())
+            .filter(|cell| !cell.is_empty())
+    }
+
+    fn parse(&self, cells: &[String]) -> Result<ProductSheetRow, Error> {
+        let product = SheetProduct {
+            slug: self.text(cells, "slug"),
+            name: self.text(cells, "name").unwrap_or_default(),
+            description: self.text(cells, "description"),
+            kind: self
+                .text(cells, "kind")
+                .map(|value| parse_kind(&value))
+                .transpose()?,
+            base_price: self
+                .text(cells, "base_price")
+                .map(|value| parse_decimal("base_price", &value))
+                .transpose()?
+                .ok_or_else(|| {
+                    Error::with_message(ErrorCode::ValidationError, "base_price is required")
+                })?,
+            member_price: self
+                .text(cells, "member_price")
+                .map(|value| parse_decimal("member_price", &value))
+                .transpose()?,
+            status: self
+                .text(cells, "status")
+                .map(|value| parse_status(&value))
+                .transpose()?,
+            category_id: self
+                .text(cells, "category_id")
+                .map(|value| parse_number("category_id", &value))
+                .transpose()?,
+            visibility: self
+                .text(cells, "visibility")
+                .map(|value| parse_visibility(&value))
+                .transpose()?,
+            publish_at: self
+                .text(cells, "publish_at")
+                .map(|value| parse_time("publish_at", &value))
+                .transpose()?,
+            unpublish_at: self
+                .text(cells, "unpublish_at")
+                .map(|value| parse_time("unpublish_at", &value))
+                .transpose()?,
+        };
+
+        let variant = SheetVariant {
+            sku: self.text(cells, "sku"),
+            size: self.text(cells, "size"),
+            color: self.text(cells, "color"),
+            stock_quantity: self
+                .text(cells, "stock_quantity")
+                .map(|value| parse_number("stock_quantity", &value))
+                .transpose()?,
+            price_override: self
+                .text(cells, "price_override")
+                .map(|value| parse_decimal("price_override", &value))
+                .transpose()?,
+            member_price_override: self
+                .text(cells, "member_price_override")
+                .map(|value| parse_decimal("member_price_override", &value))
+                .transpose()?,
+        };
+
+        let has_options =
+            variant.sku.is_some() || variant.size.is_some() || variant.color.is_some();
+        if !has_options
+            && (variant.stock_quantity.is_some()
+                || variant.price_override.is_some()
+                || variant.member_price_override.is_some())
+        {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "A variant needs a SKU, size or color",
+            ));
+        }
+
+        Ok(ProductSheetRow {
+            product,
+            variant: has_options.then_some(variant),
+        })
+    }
+}
+
+fn parse_decimal(column: &str, value: &str) -> Result<BigDecimal, Error> {
+    BigDecimal::from_str(&value.replace(',', "")).map_err(|_| {
+        Error::with_message(
+            ErrorCode::ValidationError,
+            format!("{} '{}' is not a number", column, value),
+        )
+    })
+}
+
+// Spreadsheets may store whole numbers as decimals, so "12.0" is accepted as 12
+fn parse_number<T: TryFrom<i64>>(column: &str, value: &str) -> Result<T, Error> {
+    let invalid = || {
+        Error::with_message(
+            ErrorCode::ValidationError,
+            format!("{} '{}' is not a whole number", column, value),
+        )
+    };
+
+    let decimal = parse_decimal(column, value)?;
+    if !decimal.is_integer() {
+        return Err(invalid());
+    }
+    decimal
+        .to_i64()
+        .and_then(|number| T::try_from(number).ok())
+        .ok_or_else(invalid)
+}
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+// Accepts what `row_cells` writes, RFC 3339 and "YYYY-MM-DD[T ]HH:MM[:SS]" values
+fn parse_time(column: &str, value: &str) -> Result<NaiveDateTime, Error> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.naive_utc())
+        .ok()
+        .or_else(|| {
+            [
+                TIME_FORMAT,
+                "%Y-%m-%dT%H:%M:%S",
+                "%Y-%m-%d %H:%M",
+                "%Y-%m-%dT%H:%M",
+            ]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        })
+        .ok_or_else(|| {
+            Error::with_message(
+                ErrorCode::ValidationError,
+                format!("{} '{}' is not a date and time", column, value),
+            )
+        })
+}
+
+fn parse_kind(value: &str) -> Result<ProductKind, Error> {
+    match normalize_label(value).as_str() {
+        "standard" => Ok(ProductKind::Standard),
+        "bundle" => Ok(ProductKind::Bundle),
+        _ => Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Unknown kind '{}'", value),
+        )),
+    }
+}
+
+// Accepts the labels `status_label` writes and looser spellings like "In stock"
+fn parse_status(value: &str) -> Result<ProductStatus, Error> {
+    match normalize_label(value).as_str() {
+        "preorder" => Ok(ProductStatus::Preorder),
+        "instock" => Ok(ProductStatus::InStock),
+        "outofstock" => Ok(ProductStatus::OutOfStock),
+        _ => Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Unknown status '{}'", value),
+        )),
+    }
+}
+
+fn parse_visibility(value: &str) -> Result<ProductVisibility, Error> {
+    match normalize_label(value).as_str() {
+        "draft" => Ok(ProductVisibility::Draft),
+        "scheduled" => Ok(ProductVisibility::Scheduled),
+        "published" => Ok(ProductVisibility::Published),
+        "hidden" => Ok(ProductVisibility::Hidden),
+        _ => Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Unknown visibility '{}'", value),
+        )),
+    }
+}
+
+fn normalize_label(value: &str) -> String {
+    value
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+fn kind_label(kind: ProductKind) -> &'static str {
+    match kind {
+        ProductKind::Standard => "STANDARD",
+        ProductKind::Bundle => "BUNDLE",
+    }
+}
+
+fn status_label(status: &ProductStatus) -> &'static str {
+    match status {
+        ProductStatus::Preorder => "PREORDER",
+        ProductStatus::InStock => "IN_STOCK",
+        ProductStatus::OutOfStock => "OUT_OF_STOCK",
+    }
+}
+
+fn visibility_label(visibility: ProductVisibility) -> &'static str {
+    match visibility {
+        ProductVisibility::Draft => "DRAFT",
+        ProductVisibility::Scheduled => "SCHEDULED",
+        ProductVisibility::Published => "PUBLISHED",
+        ProductVisibility::Hidden => "HIDDEN",
+    }
+}
+
+// A row as text, in `COLUMNS` order
+fn row_cells(row: &ProductSheetRow) -> [String; COLUMNS.len()] {
+    let product = &row.product;
+    let variant = row.variant.as_ref();
+    let text = |value: Option<&String>| value.cloned().unwrap_or_default();
+
+    [
+        text(product.slug.as_ref()),
+        product.name.clone(),
+        text(product.description.as_ref()),
+        product.kind.map(kind_label).unwrap_or_default().to_string(),
+        product.base_price.to_string(),
+        product
+            .member_price
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+        product
+            .status
+            .as_ref()
+            .map(status_label)
+            .unwrap_or_default()
+            .to_string(),
+        product
+            .category_id
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+        product
+            .visibility
+            .map(visibility_label)
+            .unwrap_or_default()
+            .to_string(),
+        product
+            .publish_at
+            .map(|time| time.format(TIME_FORMAT).to_string())
+            .unwrap_or_default(),
+        product
+            .unpublish_at
+            .map(|time| time.format(TIME_FORMAT).to_string())
+            .unwrap_or_default(),
+        text(variant.and_then(|v| v.sku.as_ref())),
+        text(variant.and_then(|v| v.size.as_ref())),
+        text(variant.and_then(|v| v.color.as_ref())),
+        variant
+            .and_then(|v| v.stock_quantity)
+            .map(|stock| stock.to_string())
+            .unwrap_or_default(),
+        variant
+            .and_then(|v| v.price_override.as_ref())
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+        variant
+            .and_then(|v| v.member_price_override.as_ref())
+            .map(ToString::to_string)
+            .unwrap_or_default(),
+    ]
+}
+
+fn write_csv(rows: &[ProductSheetRow]) -> Result<Vec<u8>, Error> {
+    let failed = |e: csv::Error| {
+        Error::with_message(
+            ErrorCode::InternalError,
+            format!("Failed to write CSV: {}", e),
+        )
+    };
+
+    // The BOM makes Excel open Thai text as UTF-8
+    let mut writer = csv::Writer::from_writer("\u{feff}".as_bytes().to_vec());
+    writer.write_record(COLUMNS).map_err(failed)?;
+    for row in rows {
+        writer.write_record(row_cells(row)).map_err(failed)?;
+    }
+
+    writer.into_inner().map_err(|e| {
+        Error::with_message(
+            ErrorCode::InternalError,
+            format!("Failed to write CSV: {}", e),
+        )
+    })
+}
+
+// Numeric columns are written as numbers so they can be summed and sorted in Excel
+const NUMERIC_COLUMNS: [&str; 6] = [
+    "base_price",
+    "member_price",
+    "category_id",
+    "stock_quantity",
+    "price_override",
+    "member_price_override",
+];
+
+fn write_xlsx(rows: &[ProductSheetRow]) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    let bold = Format::new().set_bold();
+
+    for (col, name) in (0u16..).zip(COLUMNS) {
+        sheet.write_string_with_format(0, col, name, &bold)?;
+    }
+
+    for (row, sheet_row) in (1u32..).zip(rows) {
+        for ((col, name), cell) in (0u16..).zip(COLUMNS).zip(row_cells(sheet_row)) {
+            if cell.is_empty() {
+                continue;
+            }
+            match BigDecimal::from_str(&cell)
+                .ok()
+                .filter(|_| NUMERIC_COLUMNS.contains(&name))
+                .and_then(|number| number.to_f64())
+            {
+                Some(number) => sheet.write_number(row, col, number)?,
+                None => sheet.write_string(row, col, &cell)?,
+            };
+        }
+    }
+
+    sheet.autofit();
+    workbook.save_to_buffer()
+}
@@ -0,0 +1,438 @@
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::DatabaseErrorKind;
+
+use crate::core::category::diesel::with_descendants;
+use crate::core::order::entity::OrderStatus;
+use crate::schema::{cart_promotions, categories, order_promotions, orders, products, promotions};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{
+    CandidatePromotion, Discounts, OrderPromotion, Promotion, PromotionKind, PromotionRequest,
+};
+use super::repository::PromotionRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = promotions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct PromotionModel {
+    pub promotion_id: i64,
+    pub code: String,
+    pub description: Option<String>,
+    pub kind: PromotionKind,
+    pub value: Option<BigDecimal>,
+    pub max_discount: Option<BigDecimal>,
+    pub min_spend: Option<BigDecimal>,
+    pub product_ids: Vec<i64>,
+    pub category_ids: Vec<i64>,
+    pub usage_limit: Option<i32>,
+    pub per_user_limit: Option<i32>,
+    pub starts_at: Option<NaiveDateTime>,
+    pub ends_at: Option<NaiveDateTime>,
+    pub stackable: bool,
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+// Written as a whole, so leaving out an optional field clears it
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = promotions)]
+#[diesel(treat_none_as_null = true)]
+struct PromotionChangeset {
+    pub code: String,
+    pub description: Option<String>,
+    pub kind: PromotionKind,
+    pub value: Option<BigDecimal>,
+    pub max_discount: Option<BigDecimal>,
+    pub min_spend: Option<BigDecimal>,
+    pub product_ids: Vec<i64>,
+    pub category_ids: Vec<i64>,
+    pub usage_limit: Option<i32>,
+    pub per_user_limit: Option<i32>,
+    pub starts_at: Option<NaiveDateTime>,
+    pub ends_at: Option<NaiveDateTime>,
+    pub stackable: bool,
+    pub active: bool,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = order_promotions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct OrderPromotionModel {
+    pub promotion_id: i64,
+    pub code: String,
+    pub discount_amount: BigDecimal,
+    pub free_shipping: bool,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = order_promotions)]
+struct NewOrderPromotionModel {
+    pub order_id: i64,
+    pub promotion_id: i64,
+    pub code: String,
+    pub discount_amount: BigDecimal,
+    pub free_shipping: bool,
+}
+
+impl PromotionModel {
+    fn into_promotion(self, times_used: i64) -> Promotion {
+        Promotion {
+            promotion_id: self.promotion_id,
+            code: self.code,
+            description: self.description,
+            kind: self.kind,
+            value: self.value,
+            max_discount: self.max_discount,
+            min_spend: self.min_spend,
+            product_ids: self.product_ids,
+            category_ids: self.category_ids,
+            usage_limit: self.usage_limit,
+            per_user_limit: self.per_user_limit,
+            starts_at: self.starts_at,
+            ends_at: self.ends_at,
+            stackable: self.stackable,
+            active: self.active,
+            times_used,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl From<OrderPromotionModel> for OrderPromotion {
+    fn from(m: OrderPromotionModel) -> Self {
+        OrderPromotion {
+            promotion_id: m.promotion_id,
+            code: m.code,
+            discount_amount: m.discount_amount,
+            free_shipping: m.free_shipping,
+        }
+    }
+}
+
+impl PromotionChangeset {
+    fn new(promotion: PromotionRequest) -> Self {
+        PromotionChangeset {
+            code: promotion.code,
+            description: promotion.description,
+            kind: promotion.kind,
+            value: promotion.value,
+            max_discount: promotion.max_discount,
+            min_spend: promotion.min_spend,
+            product_ids: promotion.product_ids,
+            category_ids: promotion.category_ids,
+            usage_limit: promotion.usage_limit,
+            per_user_limit: promotion.per_user_limit,
+            starts_at: promotion.starts_at,
+            ends_at: promotion.ends_at,
+            stackable: promotion.stackable,
+            active: promotion.active.unwrap_or(true),
+            updated_at: Utc::now().naive_utc(),
+        }
+    }
+}
+
+fn write_error(e: diesel::result::Error) -> Error {
+    match e {
+        diesel::result::Error::NotFound => {
+            Error::with_message(ErrorCode::ResourceNotFound, "Promotion not found")
+        }
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+            Error::with_message(ErrorCode::ResourceAlreadyExists, "Code is already in use")
+        }
+        _ => e.into(),
+    }
+}
+
+// The scope is stored as plain id arrays, so references are checked here instead of by
+// foreign keys
+fn check_scope(conn: &mut PgConnection, promotion: &PromotionRequest) -> Result<(), Error> {
+    let found: Vec<i64> = products::table
+        .filter(products::id.eq_any(&promotion.product_ids))
+        .select(products::id)
+        .load(conn)?;
+    if let Some(missing) = promotion.product_ids.iter().find(|id| !found.contains(id)) {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Product {} not found", missing),
+        ));
+    }
+
+    let found: Vec<i64> = categories::table
+        .filter(categories::category_id.eq_any(&promotion.category_ids))
+        .select(categories::category_id)
+        .load(conn)?;
+    if let Some(missing) = promotion.category_ids.iter().find(|id| !found.contains(id)) {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Category {} not found", missing),
+        ));
+    }
+
+    Ok(())
+}
+
+// Redemptions by orders that weren't cancelled, optionally only one customer's
+fn times_used(
+    conn: &mut PgConnection,
+    promotion_id: i64,
+    user_id: Option<i64>,
+) -> Result<i64, Error> {
+    let mut query = order_promotions::table
+        .inner_join(orders::table)
+        .filter(order_promotions::promotion_id.eq(promotion_id))
+        .filter(orders::order_status.ne(OrderStatus::Cancelled))
+        .into_boxed();
+    if let Some(user_id) = user_id {
+        query = query.filter(orders::user_id.eq(user_id));
+    }
+
+    Ok(query.count().get_result(conn)?)
+}
+
+fn load_promotion(conn: &mut PgConnection, promotion_id: i64) -> Result<Promotion, Error> {
+    let model: PromotionModel = promotions::table
+        .filter(promotions::promotion_id.eq(promotion_id))
+        .select(PromotionModel::as_select())
+        .first(conn)
+        .map_err(write_error)?;
+    let used = times_used(conn, promotion_id, None)?;
+    Ok(model.into_promotion(used))
+}
+
+// Why the customer can't redeem the code at this moment, whatever is in their cart
+fn redeem_problem(
+    conn: &mut PgConnection,
+    promotion: &Promotion,
+    user_id: i64,
+    now: NaiveDateTime,
+) -> Result<Option<String>, Error> {
+    let problem = if !promotion.active {
+        Some("This code is no longer available")
+    } else if promotion.starts_at.is_some_and(|starts_at| now < starts_at) {
+        Some("This code isn't valid yet")
+    } else if promotion.ends_at.is_some_and(|ends_at| now >= ends_at) {
+        Some("This code has expired")
+    } else if promotion
+        .usage_limit
+        .is_some_and(|limit| promotion.times_used >= i64::from(limit))
+    {
+        Some("This code has been fully redeemed")
+    } else if let Some(limit) = promotion.per_user_limit
+        && times_used(conn, promotion.promotion_id, Some(user_id))? >= i64::from(limit)
+    {
+        Some("You have already used this code as many times as allowed")
+    } else {
+        None
+    };
+
+    Ok(problem.map(str::to_string))
+}
+
+fn into_candidate(
+    conn: &mut PgConnection,
+    model: PromotionModel,
+    user_id: i64,
+    now: NaiveDateTime,
+) -> Result<CandidatePromotion, Error> {
+    let used = times_used(conn, model.promotion_id, None)?;
+    let promotion = model.into_promotion(used);
+    let category_scope = with_descendants(conn, &promotion.category_ids)?;
+    let problem = redeem_problem(conn, &promotion, user_id, now)?;
+
+    Ok(CandidatePromotion {
+        promotion,
+        category_scope,
+        problem,
+    })
+}
+
+/// The active code with the given code, ready to be priced for the customer. `code` must
+/// already be upper-case.
+pub fn candidate_by_code(
+    conn: &mut PgConnection,
+    code: &str,
+    user_id: i64,
+) -> Result<CandidatePromotion, Error> {
+    let model: PromotionModel = promotions::table
+        .filter(promotions::code.eq(code))
+        .filter(promotions::active.eq(true))
+        .select(PromotionModel::as_select())
+        .first(conn)
+        .optional()?
+        .ok_or_else(|| Error::with_message(ErrorCode::ResourceNotFound, "Coupon code not found"))?;
+
+    into_candidate(conn, model, user_id, Utc::now().naive_utc())
+}
+
+/// The codes applied to a cart, in the order they were applied. With `lock`, the promotion
+/// rows stay locked until the transaction ends so concurrent checkouts count usage limits
+/// one at a time.
+pub fn cart_candidates(
+    conn: &mut PgConnection,
+    cart_id: i64,
+    user_id: i64,
+    lock: bool,
+) -> Result<Vec<CandidatePromotion>, Error> {
+    let query = cart_promotions::table
+        .inner_join(promotions::table)
+        .filter(cart_promotions::cart_id.eq(cart_id))
+        .select(PromotionModel::as_select())
+        .order(cart_promotions::added_at.asc());
+    let models: Vec<PromotionModel> = if lock {
+        query.for_update().load(conn)?
+    } else {
+        query.load(conn)?
+    };
+
+    let now = Utc::now().naive_utc();
+    models
+        .into_iter()
+        .map(|model| into_candidate(conn, model, user_id, now))
+        .collect()
+}
+
+/// Freezes the cart's codes into the order and takes them off the cart.
+pub fn redeem(
+    conn: &mut PgConnection,
+    order_id: i64,
+    cart_id: i64,
+    discounts: &Discounts,
+) -> Result<Vec<OrderPromotion>, Error> {
+    let rows: Vec<NewOrderPromotionModel> = discounts
+        .coupons
+        .iter()
+        .map(|coupon| NewOrderPromotionModel {
+            order_id,
+            promotion_id: coupon.promotion_id,
+            code: coupon.code.clone(),
+            discount_amount: coupon.discount.clone(),
+            free_shipping: coupon.free_shipping,
+        })
+        .collect();
+
+    let redeemed: Vec<OrderPromotionModel> = diesel::insert_into(order_promotions::table)
+        .values(&rows)
+        .returning(OrderPromotionModel::as_returning())
+        .get_results(conn)?;
+    diesel::delete(cart_promotions::table.filter(cart_promotions::cart_id.eq(cart_id)))
+        .execute(conn)?;
+
+    Ok(redeemed.into_iter().map(Into::into).collect())
+}
+
+/// The codes an order redeemed, as frozen at checkout
+pub fn order_promotions(
+    conn: &mut PgConnection,
+    order_id: i64,
+) -> Result<Vec<OrderPromotion>, Error> {
+    let rows: Vec<OrderPromotionModel> = order_promotions::table
+        .filter(order_promotions::order_id.eq(order_id))
+        .select(OrderPromotionModel::as_select())
+        .order(order_promotions::promotion_id.asc())
+        .load(conn)?;
+
+    Ok(rows.into_iter().map(Into::into).collect())
+}
+
+pub struct DieselPromotionRepository {
+    pool: DBPool,
+}
+
+impl DieselPromotionRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PromotionRepository for DieselPromotionRepository {
+    async fn list(&self) -> Result<Vec<Promotion>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let models: Vec<PromotionModel> = promotions::table
+            .select(PromotionModel::as_select())
+            .order(promotions::promotion_id.desc())
+            .load(&mut conn)?;
+
+        models
+            .into_iter()
+            .map(|model| {
+                let used = times_used(&mut conn, model.promotion_id, None)?;
+                Ok(model.into_promotion(used))
+            })
+            .collect()
+    }
+
+    async fn find(&self, promotion_id: i64) -> Result<Promotion, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        load_promotion(&mut conn, promotion_id)
+    }
+
+    async fn create(&self, promotion: PromotionRequest) -> Result<Promotion, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        check_scope(&mut conn, &promotion)?;
+        let created: PromotionModel = diesel::insert_into(promotions::table)
+            .values(&PromotionChangeset::new(promotion))
+            .returning(PromotionModel::as_returning())
+            .get_result(&mut conn)
+            .map_err(write_error)?;
+
+        Ok(created.into_promotion(0))
+    }
+
+    async fn update(
+        &self,
+        promotion_id: i64,
+        promotion: PromotionRequest,
+    ) -> Result<Promotion, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        check_scope(&mut conn, &promotion)?;
+        let updated =
+            diesel::update(promotions::table.filter(promotions::promotion_id.eq(promotion_id)))
+                .set(&PromotionChangeset::new(promotion))
+                .execute(&mut conn)
+                .map_err(write_error)?;
+        if updated == 0 {
+            return Err(write_error(diesel::result::Error::NotFound));
+        }
+
+        load_promotion(&mut conn, promotion_id)
+    }
+
+    async fn deactivate(&self, promotion_id: i64) -> Result<Promotion, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let updated =
+            diesel::update(promotions::table.filter(promotions::promotion_id.eq(promotion_id)))
+                .set((
+                    promotions::active.eq(false),
+                    promotions::updated_at.eq(Utc::now().naive_utc()),
+                ))
+                .execute(&mut conn)?;
+        if updated == 0 {
+            return Err(write_error(diesel::result::Error::NotFound));
+        }
+
+        load_promotion(&mut conn, promotion_id)
+    }
+}
@@ -0,0 +1,356 @@
+use bigdecimal::{BigDecimal, Zero};
+
+use super::entity::{AppliedCoupon, CandidatePromotion, Discounts, PricedLine, PromotionKind};
+
+/// Prices the codes against the lines, in the order they were applied. Each code discounts
+/// what earlier codes left of its qualifying lines, and its discount is spread over those
+/// lines in proportion to their amounts. A code with a problem takes nothing off.
+pub fn apply_promotions(candidates: &[CandidatePromotion], lines: &[PricedLine]) -> Discounts {
+    let mut remaining: Vec<BigDecimal> = lines.iter().map(|line| line.amount.clone()).collect();
+    let mut line_discounts = vec![BigDecimal::zero(); lines.len()];
+    let mut coupons = Vec::with_capacity(candidates.len());
+
+    for candidate in candidates {
+        let promotion = &candidate.promotion;
+        let eligible: Vec<usize> = (0..lines.len())
+            .filter(|&index| candidate.covers(&lines[index]))
+            .collect();
+        let problem = candidate
+            .problem
+            .clone()
+            .or_else(|| stacking_problem(candidate, candidates.len()))
+            .or_else(|| cart_problem(candidate, lines, &eligible));
+
+        let base: BigDecimal = eligible.iter().map(|&index| &remaining[index]).sum();
+        let (discount, free_shipping) = match (&problem, promotion.kind) {
+            (Some(_), _) => (BigDecimal::zero(), false),
+            (None, PromotionKind::FreeShipping) => (BigDecimal::zero(), true),
+            (None, PromotionKind::Percentage) => {
+                let percent = promotion.value.clone().unwrap_or_else(BigDecimal::zero);
+                let mut discount = (&base * percent / BigDecimal::from(100)).round(2);
+                if let Some(cap) = &promotion.max_discount
+                    && discount > *cap
+                {
+                    discount = cap.clone();
+                }
+                (discount, false)
+            }
+            (None, PromotionKind::Fixed) => {
+                let value = promotion.value.clone().unwrap_or_else(BigDecimal::zero);
+                (value.min(base.clone()), false)
+            }
+        };
+
+        let discount = allocate(
+            &discount,
+            &base,
+            &eligible,
+            &mut remaining,
+            &mut line_discounts,
+        );
+        coupons.push(AppliedCoupon {
+            promotion_id: promotion.promotion_id,
+            code: promotion.code.clone(),
+            description: promotion.description.clone(),
+            kind: promotion.kind,
+            discount,
+            free_shipping,
+            problem,
+        });
+    }
+
+    Discounts {
+        total: coupons.iter().map(|coupon| &coupon.discount).sum(),
+        free_shipping: coupons.iter().any(|coupon| coupon.free_shipping),
+        coupons,
+        line_discounts,
+    }
+}
+
+fn stacking_problem(candidate: &CandidatePromotion, applied: usize) -> Option<String> {
+    (applied > 1 && !candidate.promotion.stackable)
+        .then(|| "This code can't be combined with other codes".to_string())
+}
+
+// Measured against the lines at full price, so earlier codes don't push a cart under the
+// minimum spend of later ones
+fn cart_problem(
+    candidate: &CandidatePromotion,
+    lines: &[PricedLine],
+    eligible: &[usize],
+) -> Option<String> {
+    if eligible.is_empty() {
+        return Some("No items in the cart qualify for this code".to_string());
+    }
+
+    let subtotal: BigDecimal = eligible.iter().map(|&index| &lines[index].amount).sum();
+    match &candidate.promotion.min_spend {
+        Some(min_spend) if subtotal < *min_spend => Some(format!(
+            "Spend at least {} on qualifying items to use this code",
+            min_spend
+        )),
+        _ => None,
+    }
+}
+
+// Spreads the discount over the eligible lines in proportion to what is left of them. The
+// rounding remainder is settled from the last line backwards, so no share goes below zero or
+// above what is left of its line. Returns the amount actually taken off.
+fn allocate(
+    discount: &BigDecimal,
+    base: &BigDecimal,
+    eligible: &[usize],
+    remaining: &mut [BigDecimal],
+    line_discounts: &mut [BigDecimal],
+) -> BigDecimal {
+    if discount.is_zero() || base.is_zero() {
+        return BigDecimal::zero();
+    }
+
+    let mut shares: Vec<BigDecimal> = eligible
+        .iter()
+        .map(|&index| {
+            (discount * &remaining[index] / base)
+                .round(2)
+                .min(remaining[index].clone())
+        })
+        .collect();
+
+    let mut difference = discount - shares.iter().sum::<BigDecimal>();
+    for (share, &index) in shares.iter_mut().zip(eligible).rev() {
+        if difference.is_zero() {
+            break;
+        }
+        let adjustment = if difference > BigDecimal::zero() {
+            difference.clone().min(&remaining[index] - &*share)
+        } else {
+            difference.clone().max(-share.clone())
+        };
+        *share += &adjustment;
+        difference -= adjustment;
+    }
+
+    let mut allocated = BigDecimal::zero();
+    for (share, &index) in shares.into_iter().zip(eligible) {
+        remaining[index] -= &share;
+        line_discounts[index] += &share;
+        allocated += share;
+    }
+    allocated
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use chrono::NaiveDateTime;
+
+    use super::*;
+    use crate::core::promotion::entity::Promotion;
+
+    fn amount(value: &str) -> BigDecimal {
+        BigDecimal::from_str(value).unwrap()
+    }
+
+    fn code(promotion_id: i64, kind: PromotionKind, value: Option<&str>) -> CandidatePromotion {
+        CandidatePromotion {
+            promotion: Promotion {
+                promotion_id,
+                code: format!("CODE{}", promotion_id),
+                description: None,
+                kind,
+                value: value.map(amount),
+                max_discount: None,
+                min_spend: None,
+                product_ids: Vec::new(),
+                category_ids: Vec::new(),
+                usage_limit: None,
+                per_user_limit: None,
+                starts_at: None,
+                ends_at: None,
+                stackable: true,
+                active: true,
+                times_used: 0,
+                created_at: NaiveDateTime::default(),
+                updated_at: NaiveDateTime::default(),
+            },
+            category_scope: Vec::new(),
+            problem: None,
+        }
+    }
+
+    fn line(product_id: i64, value: &str) -> PricedLine {
+        PricedLine {
+            product_id,
+            category_id: None,
+            amount: amount(value),
+        }
+    }
+
+    #[test]
+    fn last_line_takes_the_rounding_remainder() {
+        let lines = [line(1, "10.00"), line(2, "10.00"), line(3, "10.00")];
+        let discounts = apply_promotions(&[code(1, PromotionKind::Fixed, Some("10"))], &lines);
+
+        assert_eq!(
+            discounts.line_discounts,
+            vec![amount("3.33"), amount("3.33"), amount("3.34")]
+        );
+        assert_eq!(discounts.total, amount("10"));
+        assert_eq!(discounts.coupons[0].discount, amount("10"));
+    }
+
+    #[test]
+    fn rounded_shares_never_go_negative() {
+        // Each of seven lines rounds 0.0157 up to 0.02, which is more than 0.11 in total
+        let lines: Vec<PricedLine> = (1..=7).map(|id| line(id, "1.00")).collect();
+        let discounts = apply_promotions(&[code(1, PromotionKind::Fixed, Some("0.11"))], &lines);
+
+        assert_eq!(discounts.total, amount("0.11"));
+        assert!(
+            discounts
+                .line_discounts
+                .iter()
+                .all(|share| *share >= BigDecimal::zero()),
+            "{:?}",
+            discounts.line_discounts
+        );
+        assert_eq!(
+            discounts.line_discounts.iter().sum::<BigDecimal>(),
+            amount("0.11")
+        );
+    }
+
+    #[test]
+    fn percentage_is_rounded_then_spread_by_amount() {
+        let lines = [line(1, "33.35"), line(2, "66.70")];
+        let discounts = apply_promotions(&[code(1, PromotionKind::Percentage, Some("15"))], &lines);
+
+        // 15% of 100.05 is 15.0075, rounded to 15.01
+        assert_eq!(discounts.total, amount("15.01"));
+        assert_eq!(
+            discounts.line_discounts,
+            vec![amount("5.00"), amount("10.01")]
+        );
+    }
+
+    #[test]
+    fn percentage_stops_at_its_cap() {
+        let mut capped = code(1, PromotionKind::Percentage, Some("50"));
+        capped.promotion.max_discount = Some(amount("100"));
+
+        let discounts = apply_promotions(&[capped], &[line(1, "1000")]);
+
+        assert_eq!(discounts.total, amount("100"));
+        assert_eq!(discounts.line_discounts, vec![amount("100")]);
+    }
+
+    #[test]
+    fn fixed_never_exceeds_what_the_lines_cost() {
+        let discounts = apply_promotions(
+            &[code(1, PromotionKind::Fixed, Some("500"))],
+            &[line(1, "120")],
+        );
+
+        assert_eq!(discounts.total, amount("120"));
+    }
+
+    #[test]
+    fn stacked_codes_discount_what_earlier_ones_left() {
+        let candidates = [
+            code(1, PromotionKind::Fixed, Some("50")),
+            code(2, PromotionKind::Percentage, Some("10")),
+        ];
+        let discounts = apply_promotions(&candidates, &[line(1, "100")]);
+
+        assert_eq!(discounts.coupons[0].discount, amount("50"));
+        assert_eq!(discounts.coupons[1].discount, amount("5"));
+        assert_eq!(discounts.total, amount("55"));
+        assert_eq!(discounts.line_discounts, vec![amount("55")]);
+    }
+
+    #[test]
+    fn non_stackable_code_only_works_alone() {
+        let mut exclusive = code(1, PromotionKind::Fixed, Some("30"));
+        exclusive.promotion.stackable = false;
+        let lines = [line(1, "100")];
+
+        let alone = apply_promotions(std::slice::from_ref(&exclusive), &lines);
+        assert_eq!(alone.total, amount("30"));
+        assert_eq!(alone.coupons[0].problem, None);
+
+        let combined = apply_promotions(
+            &[exclusive, code(2, PromotionKind::FreeShipping, None)],
+            &lines,
+        );
+        assert_eq!(
+            combined.coupons[0].problem.as_deref(),
+            Some("This code can't be combined with other codes")
+        );
+        assert_eq!(combined.coupons[0].discount, amount("0"));
+        assert!(combined.coupons[1].problem.is_none());
+        assert!(combined.free_shipping);
+        assert_eq!(combined.total, amount("0"));
+    }
+
+    #[test]
+    fn minimum_spend_uses_full_prices() {
+        let mut needs_100 = code(2, PromotionKind::Percentage, Some("10"));
+        needs_100.promotion.min_spend = Some(amount("100"));
+
+        // The first code brings the line under 100, but the minimum is met at full price
+        let discounts = apply_promotions(
+            &[code(1, PromotionKind::Fixed, Some("20")), needs_100.clone()],
+            &[line(1, "100")],
+        );
+        assert!(discounts.coupons[1].problem.is_none());
+        assert_eq!(discounts.coupons[1].discount, amount("8"));
+
+        let discounts = apply_promotions(&[needs_100], &[line(1, "99.99")]);
+        assert_eq!(
+            discounts.coupons[0].problem.as_deref(),
+            Some("Spend at least 100 on qualifying items to use this code")
+        );
+        assert_eq!(discounts.total, amount("0"));
+    }
+
+    #[test]
+    fn code_for_other_products_takes_nothing() {
+        let mut scoped = code(1, PromotionKind::Fixed, Some("10"));
+        scoped.promotion.product_ids = vec![99];
+        let lines = [line(1, "50"), line(2, "50")];
+
+        let discounts = apply_promotions(&[scoped], &lines);
+
+        assert_eq!(
+            discounts.coupons[0].problem.as_deref(),
+            Some("No items in the cart qualify for this code")
+        );
+        assert_eq!(discounts.line_discounts, vec![amount("0"), amount("0")]);
+        assert_eq!(discounts.total, amount("0"));
+    }
+
+    #[test]
+    fn scoped_code_only_touches_its_lines() {
+        let mut scoped = code(1, PromotionKind::Percentage, Some("10"));
+        scoped.promotion.product_ids = vec![2];
+
+        let discounts = apply_promotions(&[scoped], &[line(1, "50"), line(2, "80")]);
+
+        assert_eq!(discounts.line_discounts, vec![amount("0"), amount("8")]);
+    }
+
+    #[test]
+    fn code_with_its_own_problem_takes_nothing() {
+        let mut expired = code(1, PromotionKind::Fixed, Some("10"));
+        expired.problem = Some("This code has expired".to_string());
+
+        let discounts = apply_promotions(&[expired], &[line(1, "50")]);
+
+        assert_eq!(
+            discounts.coupons[0].problem.as_deref(),
+            Some("This code has expired")
+        );
+        assert_eq!(discounts.total, amount("0"));
+    }
+}
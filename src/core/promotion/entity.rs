@@ -0,0 +1,137 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::PromotionKind"]
+pub enum PromotionKind {
+    /// `value` percent off the qualifying items, up to `max_discount`
+    #[db_rename = "PERCENTAGE"]
+    Percentage,
+    /// `value` off the qualifying items, never more than they cost
+    #[db_rename = "FIXED"]
+    Fixed,
+    /// Waives the shipping fee of a delivered order
+    #[db_rename = "FREE_SHIPPING"]
+    FreeShipping,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Promotion {
+    pub promotion_id: i64,
+    pub code: String,
+    pub description: Option<String>,
+    pub kind: PromotionKind,
+    pub value: Option<BigDecimal>,
+    pub max_discount: Option<BigDecimal>,
+    /// Compared with the subtotal of the items the code applies to
+    pub min_spend: Option<BigDecimal>,
+    /// The code applies to the whole cart when both lists are empty
+    pub product_ids: Vec<i64>,
+    /// Subcategories are included
+    pub category_ids: Vec<i64>,
+    pub usage_limit: Option<i32>,
+    pub per_user_limit: Option<i32>,
+    pub starts_at: Option<NaiveDateTime>,
+    pub ends_at: Option<NaiveDateTime>,
+    /// Whether the code can share a cart with other codes
+    pub stackable: bool,
+    pub active: bool,
+    /// Orders that redeemed the code and weren't cancelled
+    pub times_used: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// Creates or replaces a promotion; a field left out or null is cleared
+#[derive(Debug, Clone, Deserialize)]
+pub struct PromotionRequest {
+    pub code: String,
+    pub description: Option<String>,
+    pub kind: PromotionKind,
+    pub value: Option<BigDecimal>,
+    pub max_discount: Option<BigDecimal>,
+    pub min_spend: Option<BigDecimal>,
+    #[serde(default)]
+    pub product_ids: Vec<i64>,
+    #[serde(default)]
+    pub category_ids: Vec<i64>,
+    pub usage_limit: Option<i32>,
+    pub per_user_limit: Option<i32>,
+    pub starts_at: Option<NaiveDateTime>,
+    pub ends_at: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub stackable: bool,
+    /// Defaults to active
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApplyCouponRequest {
+    pub code: String,
+}
+
+/// A cart or order line as the discount engine sees it
+#[derive(Debug, Clone)]
+pub struct PricedLine {
+    pub product_id: i64,
+    pub category_id: Option<i64>,
+    /// Unit price times quantity
+    pub amount: BigDecimal,
+}
+
+/// A code applied to a cart, ready to be priced against it
+#[derive(Debug, Clone)]
+pub struct CandidatePromotion {
+    pub promotion: Promotion,
+    /// The promotion's categories with all of their subcategories
+    pub category_scope: Vec<i64>,
+    /// Why the code can't be redeemed regardless of the cart, e.g. it has expired
+    pub problem: Option<String>,
+}
+
+impl CandidatePromotion {
+    pub fn covers(&self, line: &PricedLine) -> bool {
+        let promotion = &self.promotion;
+        if promotion.product_ids.is_empty() && self.category_scope.is_empty() {
+            return true;
+        }
+        promotion.product_ids.contains(&line.product_id)
+            || line
+                .category_id
+                .is_some_and(|category_id| self.category_scope.contains(&category_id))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedCoupon {
+    pub promotion_id: i64,
+    pub code: String,
+    pub description: Option<String>,
+    pub kind: PromotionKind,
+    pub discount: BigDecimal,
+    pub free_shipping: bool,
+    /// Why the code gives nothing right now; checkout is refused until it's resolved or the
+    /// code is removed
+    pub problem: Option<String>,
+}
+
+/// What a set of codes takes off a list of lines
+#[derive(Debug, Clone)]
+pub struct Discounts {
+    pub coupons: Vec<AppliedCoupon>,
+    /// Each line's share of the discount, in line order
+    pub line_discounts: Vec<BigDecimal>,
+    pub total: BigDecimal,
+    pub free_shipping: bool,
+}
+
+/// A code redeemed by an order, as frozen at checkout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderPromotion {
+    pub promotion_id: i64,
+    pub code: String,
+    pub discount_amount: BigDecimal,
+    pub free_shipping: bool,
+}
@@ -0,0 +1,5 @@
+pub mod diesel;
+pub mod discount;
+pub mod entity;
+pub mod repository;
+pub mod service;
@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+
+use crate::utils::errors::Error;
+
+use super::entity::{Promotion, PromotionRequest};
+
+#[async_trait]
+pub trait PromotionRepository: Send + Sync {
+    async fn list(&self) -> Result<Vec<Promotion>, Error>;
+    async fn find(&self, promotion_id: i64) -> Result<Promotion, Error>;
+    async fn create(&self, promotion: PromotionRequest) -> Result<Promotion, Error>;
+    async fn update(
+        &self,
+        promotion_id: i64,
+        promotion: PromotionRequest,
+    ) -> Result<Promotion, Error>;
+    /// Stops the code from being applied; orders that redeemed it keep their discount.
+    async fn deactivate(&self, promotion_id: i64) -> Result<Promotion, Error>;
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{Promotion, PromotionKind, PromotionRequest};
+use super::repository::PromotionRepository;
+
+const MAX_CODE_LEN: usize = 40;
+
+#[derive(Clone)]
+pub struct PromotionService {
+    repo: Arc<dyn PromotionRepository>,
+}
+
+impl PromotionService {
+    pub fn new(repo: Arc<dyn PromotionRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list_promotions(&self) -> Result<Vec<Promotion>, Error> {
+        self.repo.list().await
+    }
+
+    pub async fn get_promotion(&self, promotion_id: i64) -> Result<Promotion, Error> {
+        self.repo.find(promotion_id).await
+    }
+
+    pub async fn create_promotion(&self, promotion: PromotionRequest) -> Result<Promotion, Error> {
+        let promotion = validate_promotion(promotion)?;
+        self.repo.create(promotion).await
+    }
+
+    pub async fn update_promotion(
+        &self,
+        promotion_id: i64,
+        promotion: PromotionRequest,
+    ) -> Result<Promotion, Error> {
+        let promotion = validate_promotion(promotion)?;
+        self.repo.update(promotion_id, promotion).await
+    }
+
+    pub async fn deactivate_promotion(&self, promotion_id: i64) -> Result<Promotion, Error> {
+        self.repo.deactivate(promotion_id).await
+    }
+}
+
+/// Trims and upper-cases a code as customers or admins typed it, rejecting anything but
+/// letters, digits, `-` and `_`.
+pub fn normalize_code(code: &str) -> Result<String, Error> {
+    let code = code.trim().to_uppercase();
+    if code.is_empty() || code.chars().count() > MAX_CODE_LEN {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("Code must be 1 to {} characters", MAX_CODE_LEN),
+        ));
+    }
+    if !code
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Code may only contain letters, digits, '-' and '_'",
+        ));
+    }
+    Ok(code)
+}
+
+fn positive(amount: Option<&BigDecimal>, field: &str) -> Result<(), Error> {
+    if amount.is_some_and(|amount| *amount <= BigDecimal::zero()) {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("{} must be greater than 0", field),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_promotion(mut promotion: PromotionRequest) -> Result<PromotionRequest, Error> {
+    promotion.code = normalize_code(&promotion.code)?;
+    promotion.description = promotion
+        .description
+        .map(|description| description.trim().to_string())
+        .filter(|description| !description.is_empty());
+
+    positive(promotion.value.as_ref(), "Value")?;
+    positive(promotion.max_discount.as_ref(), "Maximum discount")?;
+    positive(promotion.min_spend.as_ref(), "Minimum spend")?;
+
+    match (promotion.kind, &promotion.value) {
+        (PromotionKind::Percentage, Some(value)) if value > &BigDecimal::from(100) => {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "A percentage discount can be at most 100",
+            ));
+        }
+        (PromotionKind::Percentage | PromotionKind::Fixed, None) => {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Percentage and fixed discounts need a value",
+            ));
+        }
+        (PromotionKind::FreeShipping, Some(_)) => {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Free shipping codes take no value",
+            ));
+        }
+        _ => {}
+    }
+
+    if promotion.max_discount.is_some() && promotion.kind != PromotionKind::Percentage {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "max_discount only applies to percentage discounts",
+        ));
+    }
+
+    if promotion.usage_limit.is_some_and(|limit| limit <= 0)
+        || promotion.per_user_limit.is_some_and(|limit| limit <= 0)
+    {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Usage limits must be greater than 0",
+        ));
+    }
+
+    if let (Some(starts_at), Some(ends_at)) = (promotion.starts_at, promotion.ends_at)
+        && ends_at <= starts_at
+    {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "ends_at must be after starts_at",
+        ));
+    }
+
+    promotion.product_ids.sort_unstable();
+    promotion.product_ids.dedup();
+    promotion.category_ids.sort_unstable();
+    promotion.category_ids.dedup();
+
+    Ok(promotion)
+}
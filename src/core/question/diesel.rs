@@ -0,0 +1,210 @@
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::DatabaseErrorKind;
+
+use crate::schema::{product_questions, users};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::Question;
+use super::repository::QuestionRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = product_questions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct QuestionModel {
+    pub question_id: i64,
+    pub product_id: i64,
+    pub user_id: i64,
+    pub body: String,
+    pub answer: Option<String>,
+    pub answered_by: Option<String>,
+    pub answered_at: Option<NaiveDateTime>,
+    pub hidden_by: Option<String>,
+    pub hidden_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl QuestionModel {
+    fn into_question(self, asker: Option<String>) -> Question {
+        Question {
+            question_id: self.question_id,
+            product_id: self.product_id,
+            user_id: self.user_id,
+            asker,
+            body: self.body,
+            answer: self.answer,
+            answered_by: self.answered_by,
+            answered_at: self.answered_at,
+            hidden_at: self.hidden_at,
+            hidden_by: self.hidden_by,
+            created_at: self.created_at,
+        }
+    }
+}
+
+fn write_error(e: diesel::result::Error) -> Error {
+    match e {
+        diesel::result::Error::NotFound => {
+            Error::with_message(ErrorCode::ResourceNotFound, "Question not found")
+        }
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+            Error::with_message(ErrorCode::ResourceNotFound, "Product not found")
+        }
+        _ => e.into(),
+    }
+}
+
+fn load_question(conn: &mut PgConnection, question_id: i64) -> Result<Question, Error> {
+    let (model, asker): (QuestionModel, Option<String>) = product_questions::table
+        .inner_join(users::table)
+        .filter(product_questions::question_id.eq(question_id))
+        .select((QuestionModel::as_select(), users::full_name))
+        .first(conn)
+        .map_err(write_error)?;
+
+    Ok(model.into_question(asker))
+}
+
+pub struct DieselQuestionRepository {
+    pool: DBPool,
+}
+
+impl DieselQuestionRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl QuestionRepository for DieselQuestionRepository {
+    async fn find_by_product(
+        &self,
+        product_id: i64,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<Question>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: Vec<(QuestionModel, Option<String>)> = product_questions::table
+            .inner_join(users::table)
+            .filter(product_questions::product_id.eq(product_id))
+            .filter(product_questions::hidden_at.is_null())
+            .select((QuestionModel::as_select(), users::full_name))
+            .order((
+                product_questions::created_at.desc(),
+                product_questions::question_id.desc(),
+            ))
+            .offset(offset)
+            .limit(limit)
+            .load(&mut conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(model, asker)| model.into_question(asker))
+            .collect())
+    }
+
+    async fn count_by_product(&self, product_id: i64) -> Result<i64, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let total = product_questions::table
+            .filter(product_questions::product_id.eq(product_id))
+            .filter(product_questions::hidden_at.is_null())
+            .count()
+            .get_result(&mut conn)?;
+
+        Ok(total)
+    }
+
+    async fn create(&self, product_id: i64, user_id: i64, body: &str) -> Result<Question, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let question_id: i64 = diesel::insert_into(product_questions::table)
+            .values((
+                product_questions::product_id.eq(product_id),
+                product_questions::user_id.eq(user_id),
+                product_questions::body.eq(body),
+            ))
+            .returning(product_questions::question_id)
+            .get_result(&mut conn)
+            .map_err(write_error)?;
+
+        load_question(&mut conn, question_id)
+    }
+
+    async fn unanswered(&self) -> Result<Vec<Question>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: Vec<(QuestionModel, Option<String>)> = product_questions::table
+            .inner_join(users::table)
+            .filter(product_questions::answer.is_null())
+            .filter(product_questions::hidden_at.is_null())
+            .select((QuestionModel::as_select(), users::full_name))
+            .order(product_questions::created_at.asc())
+            .load(&mut conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(model, asker)| model.into_question(asker))
+            .collect())
+    }
+
+    async fn answer(
+        &self,
+        question_id: i64,
+        answer: &str,
+        answered_by: &str,
+    ) -> Result<Question, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        diesel::update(
+            product_questions::table.filter(product_questions::question_id.eq(question_id)),
+        )
+        .set((
+            product_questions::answer.eq(Some(answer)),
+            product_questions::answered_by.eq(Some(answered_by)),
+            product_questions::answered_at.eq(Some(Utc::now().naive_utc())),
+        ))
+        .returning(product_questions::question_id)
+        .get_result::<i64>(&mut conn)
+        .map_err(write_error)?;
+
+        load_question(&mut conn, question_id)
+    }
+
+    async fn set_hidden(
+        &self,
+        question_id: i64,
+        hidden_by: Option<&str>,
+    ) -> Result<Question, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let hidden_at = hidden_by.map(|_| Utc::now().naive_utc());
+        diesel::update(
+            product_questions::table.filter(product_questions::question_id.eq(question_id)),
+        )
+        .set((
+            product_questions::hidden_by.eq(hidden_by),
+            product_questions::hidden_at.eq(hidden_at),
+        ))
+        .returning(product_questions::question_id)
+        .get_result::<i64>(&mut conn)
+        .map_err(write_error)?;
+
+        load_question(&mut conn, question_id)
+    }
+}
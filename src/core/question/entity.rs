@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A customer's question about a product and, once staff reply, its public answer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Question {
+    pub question_id: i64,
+    pub product_id: i64,
+    pub user_id: i64,
+    /// The asker's name as on their account, if they gave one
+    pub asker: Option<String>,
+    pub body: String,
+    pub answer: Option<String>,
+    pub answered_by: Option<String>,
+    pub answered_at: Option<NaiveDateTime>,
+    /// Set while the question is hidden from the product page
+    pub hidden_at: Option<NaiveDateTime>,
+    pub hidden_by: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionPage {
+    pub product_id: i64,
+    pub questions: Vec<Question>,
+    pub total: i64,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuestionRequest {
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnswerRequest {
+    pub answer: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QuestionQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
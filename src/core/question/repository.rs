@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+
+use crate::utils::errors::Error;
+
+use super::entity::Question;
+
+#[async_trait]
+pub trait QuestionRepository: Send + Sync {
+    /// Questions shown on the product page, newest first
+    async fn find_by_product(
+        &self,
+        product_id: i64,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<Question>, Error>;
+    async fn count_by_product(&self, product_id: i64) -> Result<i64, Error>;
+    async fn create(&self, product_id: i64, user_id: i64, body: &str) -> Result<Question, Error>;
+    /// Questions still waiting for an answer, oldest first; hidden ones are left out
+    async fn unanswered(&self) -> Result<Vec<Question>, Error>;
+    /// Sets the answer, replacing any earlier one
+    async fn answer(
+        &self,
+        question_id: i64,
+        answer: &str,
+        answered_by: &str,
+    ) -> Result<Question, Error>;
+    /// Hides the question when `hidden_by` is given, shows it again otherwise
+    async fn set_hidden(
+        &self,
+        question_id: i64,
+        hidden_by: Option<&str>,
+    ) -> Result<Question, Error>;
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{Question, QuestionPage};
+use super::repository::QuestionRepository;
+
+const MAX_QUESTION_LEN: usize = 1000;
+const MAX_ANSWER_LEN: usize = 2000;
+
+#[derive(Clone)]
+pub struct QuestionService {
+    repo: Arc<dyn QuestionRepository>,
+}
+
+impl QuestionService {
+    pub fn new(repo: Arc<dyn QuestionRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list_questions(
+        &self,
+        product_id: i64,
+        page: u32,
+        page_size: u32,
+    ) -> Result<QuestionPage, Error> {
+        if page_size == 0 || page_size > 100 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Page size must be between 1 and 100",
+            ));
+        }
+
+        let offset = i64::from(page.saturating_sub(1) * page_size);
+        let limit = i64::from(page_size);
+
+        let questions = self.repo.find_by_product(product_id, offset, limit).await?;
+        let total = self.repo.count_by_product(product_id).await?;
+
+        Ok(QuestionPage {
+            product_id,
+            questions,
+            total,
+            page,
+            page_size,
+        })
+    }
+
+    pub async fn ask_question(
+        &self,
+        product_id: i64,
+        user_id: i64,
+        body: &str,
+    ) -> Result<Question, Error> {
+        let body = required_text(body, "Question", MAX_QUESTION_LEN)?;
+        self.repo.create(product_id, user_id, body).await
+    }
+
+    pub async fn unanswered_questions(&self) -> Result<Vec<Question>, Error> {
+        self.repo.unanswered().await
+    }
+
+    pub async fn answer_question(
+        &self,
+        question_id: i64,
+        answer: &str,
+        answered_by: &str,
+    ) -> Result<Question, Error> {
+        let answer = required_text(answer, "Answer", MAX_ANSWER_LEN)?;
+        self.repo.answer(question_id, answer, answered_by).await
+    }
+
+    pub async fn hide_question(
+        &self,
+        question_id: i64,
+        hidden_by: &str,
+    ) -> Result<Question, Error> {
+        self.repo.set_hidden(question_id, Some(hidden_by)).await
+    }
+
+    pub async fn restore_question(&self, question_id: i64) -> Result<Question, Error> {
+        self.repo.set_hidden(question_id, None).await
+    }
+}
+
+fn required_text<'a>(text: &'a str, field: &str, max_len: usize) -> Result<&'a str, Error> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("{} is required", field),
+        ));
+    }
+    if text.chars().count() > max_len {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("{} must be {} characters or fewer", field, max_len),
+        ));
+    }
+    Ok(text)
+}
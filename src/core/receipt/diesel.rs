@@ -0,0 +1,276 @@
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use chrono::{Datelike, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use tracing::error;
+
+use crate::core::payment::entity::PaymentStatus;
+use crate::schema::{
+    document_counters, order_items, orders, payments, products, receipts, users, variants,
+};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{BuyerDetails, DocumentType, Receipt, ReceiptLine, ReceiptOrder};
+use super::repository::ReceiptRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = receipts)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct ReceiptModel {
+    pub receipt_id: i64,
+    pub receipt_number: String,
+    pub document_type: DocumentType,
+    pub order_id: i64,
+    pub buyer_name: String,
+    pub buyer_tax_id: Option<String>,
+    pub buyer_address: Option<String>,
+    pub issued_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = receipts)]
+struct NewReceiptModel {
+    pub receipt_number: String,
+    pub document_type: DocumentType,
+    pub order_id: i64,
+    pub buyer_name: String,
+    pub buyer_tax_id: Option<String>,
+    pub buyer_address: Option<String>,
+}
+
+impl From<ReceiptModel> for Receipt {
+    fn from(m: ReceiptModel) -> Self {
+        Receipt {
+            receipt_id: m.receipt_id,
+            receipt_number: m.receipt_number,
+            document_type: m.document_type,
+            order_id: m.order_id,
+            buyer_name: m.buyer_name,
+            buyer_tax_id: m.buyer_tax_id,
+            buyer_address: m.buyer_address,
+            issued_at: m.issued_at,
+        }
+    }
+}
+
+// Takes the next number for the type and year; the counter row stays locked until commit
+fn next_number(
+    conn: &mut PgConnection,
+    document_type: DocumentType,
+    year: i32,
+) -> Result<i32, Error> {
+    let number = diesel::insert_into(document_counters::table)
+        .values((
+            document_counters::document_type.eq(document_type),
+            document_counters::year.eq(year),
+            document_counters::last_number.eq(1),
+        ))
+        .on_conflict((document_counters::document_type, document_counters::year))
+        .do_update()
+        .set(document_counters::last_number.eq(document_counters::last_number + 1))
+        .returning(document_counters::last_number)
+        .get_result(conn)?;
+
+    Ok(number)
+}
+
+pub struct DieselReceiptRepository {
+    pool: DBPool,
+}
+
+impl DieselReceiptRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+// Product name, size, color, quantity and unit price of an order line
+type LineRow = (
+    String,
+    Option<String>,
+    Option<String>,
+    Option<i32>,
+    Option<BigDecimal>,
+);
+
+fn receipt_line((name, size, color, quantity, unit_price): LineRow) -> ReceiptLine {
+    let options: Vec<String> = [size, color].into_iter().flatten().collect();
+    let description = if options.is_empty() {
+        name
+    } else {
+        format!("{} ({})", name, options.join(" / "))
+    };
+    let quantity = quantity.unwrap_or(0);
+    let unit_price = unit_price.unwrap_or_default();
+    let amount = &unit_price * BigDecimal::from(quantity);
+    ReceiptLine {
+        description,
+        quantity,
+        unit_price,
+        amount,
+    }
+}
+
+#[async_trait]
+impl ReceiptRepository for DieselReceiptRepository {
+    async fn find_order(&self, order_id: i64) -> Result<ReceiptOrder, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let (
+            user_id,
+            total_amount,
+            discount_amount,
+            shipping_fee,
+            shipping_address,
+            buyer_name,
+            buyer_email,
+        ): (
+            i64,
+            Option<BigDecimal>,
+            BigDecimal,
+            BigDecimal,
+            Option<String>,
+            Option<String>,
+            String,
+        ) = orders::table
+            .inner_join(users::table)
+            .filter(orders::order_id.eq(order_id))
+            .select((
+                orders::user_id,
+                orders::total_amount,
+                orders::discount_amount,
+                orders::shipping_fee,
+                orders::shipping_address,
+                users::full_name,
+                users::email,
+            ))
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    Error::with_message(ErrorCode::ResourceNotFound, "Order not found")
+                }
+                _ => {
+                    Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e))
+                }
+            })?;
+
+        let rows: Vec<LineRow> = order_items::table
+            .inner_join(variants::table.inner_join(products::table))
+            .filter(order_items::order_id.eq(order_id))
+            .select((
+                products::name,
+                variants::size,
+                variants::color,
+                order_items::quantity,
+                order_items::unit_price,
+            ))
+            .order(order_items::order_item_id.asc())
+            .load(&mut conn)?;
+
+        let lines: Vec<ReceiptLine> = rows.into_iter().map(receipt_line).collect();
+
+        let paid_at = payments::table
+            .filter(payments::order_id.eq(order_id))
+            .filter(payments::payment_status.eq(PaymentStatus::Verified))
+            .select((payments::transferred_at, payments::reviewed_at))
+            .order(payments::payment_id.desc())
+            .first::<(Option<NaiveDateTime>, Option<NaiveDateTime>)>(&mut conn)
+            .optional()?
+            .and_then(|(transferred_at, reviewed_at)| transferred_at.or(reviewed_at));
+
+        let total_amount = total_amount.unwrap_or_else(|| {
+            lines.iter().map(|line| &line.amount).sum::<BigDecimal>() - &discount_amount
+                + &shipping_fee
+        });
+
+        Ok(ReceiptOrder {
+            order_id,
+            user_id,
+            buyer_name,
+            buyer_email,
+            shipping_address,
+            discount_amount,
+            shipping_fee,
+            total_amount,
+            lines,
+            paid_at,
+        })
+    }
+
+    async fn find_issued(
+        &self,
+        order_id: i64,
+        document_type: DocumentType,
+    ) -> Result<Option<Receipt>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let receipt: Option<ReceiptModel> = receipts::table
+            .filter(receipts::order_id.eq(order_id))
+            .filter(receipts::document_type.eq(document_type))
+            .select(ReceiptModel::as_select())
+            .first(&mut conn)
+            .optional()?;
+
+        Ok(receipt.map(Into::into))
+    }
+
+    async fn issue(
+        &self,
+        order_id: i64,
+        document_type: DocumentType,
+        buyer: BuyerDetails,
+    ) -> Result<Receipt, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction::<_, Error, _>(|conn| {
+            // Serialises concurrent requests for the same order so only one number is taken
+            orders::table
+                .filter(orders::order_id.eq(order_id))
+                .select(orders::order_id)
+                .for_update()
+                .first::<i64>(conn)?;
+
+            let existing: Option<ReceiptModel> = receipts::table
+                .filter(receipts::order_id.eq(order_id))
+                .filter(receipts::document_type.eq(document_type))
+                .select(ReceiptModel::as_select())
+                .first(conn)
+                .optional()?;
+            if let Some(existing) = existing {
+                return Ok(existing.into());
+            }
+
+            let year = Utc::now().year();
+            let number = next_number(conn, document_type, year)?;
+            let receipt: ReceiptModel = diesel::insert_into(receipts::table)
+                .values(&NewReceiptModel {
+                    receipt_number: format!(
+                        "{}{}-{:06}",
+                        document_type.number_prefix(),
+                        year,
+                        number
+                    ),
+                    document_type,
+                    order_id,
+                    buyer_name: buyer.name,
+                    buyer_tax_id: buyer.tax_id,
+                    buyer_address: buyer.address,
+                })
+                .returning(ReceiptModel::as_returning())
+                .get_result(conn)?;
+
+            Ok(receipt.into())
+        })
+        .map_err(|e| {
+            error!(error = %e, order_id, "Failed to issue receipt");
+            e
+        })
+    }
+}
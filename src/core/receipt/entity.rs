@@ -0,0 +1,83 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::DocumentType"]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentType {
+    #[db_rename = "RECEIPT"]
+    Receipt,
+    #[db_rename = "TAX_INVOICE"]
+    TaxInvoice,
+}
+
+impl DocumentType {
+    pub fn number_prefix(self) -> &'static str {
+        match self {
+            DocumentType::Receipt => "RC",
+            DocumentType::TaxInvoice => "TX",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Receipt {
+    pub receipt_id: i64,
+    pub receipt_number: String,
+    pub document_type: DocumentType,
+    pub order_id: i64,
+    pub buyer_name: String,
+    pub buyer_tax_id: Option<String>,
+    pub buyer_address: Option<String>,
+    pub issued_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct BuyerDetails {
+    pub name: String,
+    pub tax_id: Option<String>,
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceiptLine {
+    pub description: String,
+    pub quantity: i32,
+    pub unit_price: BigDecimal,
+    pub amount: BigDecimal,
+}
+
+// Everything printed on a receipt that comes from the order
+#[derive(Debug, Clone)]
+pub struct ReceiptOrder {
+    pub order_id: i64,
+    pub user_id: i64,
+    pub buyer_name: Option<String>,
+    pub buyer_email: String,
+    pub shipping_address: Option<String>,
+    /// Taken off the lines by coupons
+    pub discount_amount: BigDecimal,
+    pub shipping_fee: BigDecimal,
+    pub total_amount: BigDecimal,
+    pub lines: Vec<ReceiptLine>,
+    /// When the verified payment was made; `None` while the order is unpaid
+    pub paid_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShopDetails {
+    pub name: String,
+    pub address: Option<String>,
+    pub tax_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReceiptQuery {
+    #[serde(rename = "type")]
+    pub document_type: Option<DocumentType>,
+    pub buyer_name: Option<String>,
+    pub tax_id: Option<String>,
+    pub address: Option<String>,
+}
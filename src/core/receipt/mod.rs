@@ -0,0 +1,5 @@
+pub mod diesel;
+pub mod entity;
+pub mod pdf;
+pub mod repository;
+pub mod service;
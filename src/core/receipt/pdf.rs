@@ -0,0 +1,329 @@
+use std::io::Cursor;
+
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use chrono::{FixedOffset, NaiveDateTime, TimeZone, Utc};
+use printpdf::{
+    IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Point,
+};
+
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{DocumentType, Receipt, ReceiptOrder, ShopDetails};
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const RIGHT_EDGE: f32 = PAGE_WIDTH - MARGIN;
+const BOTTOM_LIMIT: f32 = 35.0;
+const BODY_SIZE: f32 = 10.0;
+const LINE_HEIGHT: f32 = 6.0;
+const VAT_PERCENT: u32 = 7;
+
+// Table columns as (left edge, right edge) in mm
+const COL_NO: (f32, f32) = (MARGIN, 28.0);
+const COL_DESCRIPTION: (f32, f32) = (30.0, 120.0);
+const COL_QTY: (f32, f32) = (120.0, 135.0);
+const COL_UNIT_PRICE: (f32, f32) = (135.0, 162.0);
+const COL_AMOUNT: (f32, f32) = (162.0, RIGHT_EDGE);
+
+struct PageWriter<'a> {
+    doc: &'a PdfDocumentReference,
+    layer: PdfLayerReference,
+    font: IndirectFontRef,
+    face: ttf_parser::Face<'a>,
+    y: f32,
+}
+
+impl PageWriter<'_> {
+    fn text_width(&self, text: &str, size: f32) -> f32 {
+        let units_per_em = f32::from(self.face.units_per_em());
+        let advance: f32 = text
+            .chars()
+            .filter_map(|c| self.face.glyph_index(c))
+            .filter_map(|glyph| self.face.glyph_hor_advance(glyph))
+            .map(f32::from)
+            .sum();
+        // Font units to points, then points to millimetres
+        advance / units_per_em * size * 25.4 / 72.0
+    }
+
+    fn text(&self, text: &str, size: f32, x: f32) {
+        self.layer
+            .use_text(text, size, Mm(x), Mm(self.y), &self.font);
+    }
+
+    fn text_right(&self, text: &str, size: f32, right: f32) {
+        let x = right - self.text_width(text, size);
+        self.text(text, size, x);
+    }
+
+    // Cuts text that would run past `max_width`, marking the cut with an ellipsis
+    fn fit(&self, text: &str, size: f32, max_width: f32) -> String {
+        if self.text_width(text, size) <= max_width {
+            return text.to_string();
+        }
+        let mut fitted = String::new();
+        for c in text.chars() {
+            fitted.push(c);
+            if self.text_width(&format!("{}…", fitted), size) > max_width {
+                fitted.pop();
+                break;
+            }
+        }
+        format!("{}…", fitted)
+    }
+
+    fn rule(&self, from: f32, to: f32) {
+        let y = self.y + 2.0;
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(from), Mm(y)), false),
+                (Point::new(Mm(to), Mm(y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    fn advance(&mut self, lines: f32) {
+        self.y -= LINE_HEIGHT * lines;
+    }
+
+    // Starts a new page when fewer than `lines` fit; returns whether it did
+    fn ensure_room(&mut self, lines: f32) -> bool {
+        if self.y - LINE_HEIGHT * lines >= BOTTOM_LIMIT {
+            return false;
+        }
+        let (page, layer) = self
+            .doc
+            .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+        true
+    }
+}
+
+/// Renders a receipt or tax invoice as an A4 PDF with `font_data` embedded.
+///
+/// The font must cover Thai for Thai names and addresses to show; prices are VAT-inclusive,
+/// and tax invoices break the total down into the pre-VAT value and VAT.
+pub fn render(
+    font_data: &[u8],
+    shop: &ShopDetails,
+    receipt: &Receipt,
+    order: &ReceiptOrder,
+) -> Result<Vec<u8>, Error> {
+    let face = ttf_parser::Face::parse(font_data, 0).map_err(|e| {
+        Error::with_message(
+            ErrorCode::InternalError,
+            format!("Invalid receipt font: {}", e),
+        )
+    })?;
+
+    let title = match receipt.document_type {
+        DocumentType::Receipt => "ใบเสร็จรับเงิน / Receipt",
+        DocumentType::TaxInvoice => "ใบกำกับภาษี / ใบเสร็จรับเงิน  Tax Invoice / Receipt",
+    };
+    let (doc, page, layer) = PdfDocument::new(
+        format!("{} {}", title, receipt.receipt_number),
+        Mm(PAGE_WIDTH),
+        Mm(PAGE_HEIGHT),
+        "Layer 1",
+    );
+    let font = doc
+        .add_external_font(Cursor::new(font_data))
+        .map_err(|e| pdf_error(&e))?;
+
+    let mut writer = PageWriter {
+        doc: &doc,
+        layer: doc.get_page(page).get_layer(layer),
+        font,
+        face,
+        y: PAGE_HEIGHT - MARGIN,
+    };
+
+    write_header(&mut writer, shop, receipt, order.order_id, title);
+    write_buyer(&mut writer, receipt);
+    write_items(&mut writer, order);
+    write_totals(&mut writer, receipt, order);
+
+    doc.save_to_bytes().map_err(|e| pdf_error(&e))
+}
+
+// Shop on the left, document number and dates on the right
+fn write_header(
+    writer: &mut PageWriter<'_>,
+    shop: &ShopDetails,
+    receipt: &Receipt,
+    order_id: i64,
+    title: &str,
+) {
+    writer.text(&shop.name, 16.0, MARGIN);
+    writer.text_right(title, 12.0, RIGHT_EDGE);
+    writer.advance(1.3);
+    if let Some(address) = &shop.address {
+        writer.text(&writer.fit(address, BODY_SIZE, 100.0), BODY_SIZE, MARGIN);
+    }
+    writer.text_right(
+        &format!("เลขที่ / No. {}", receipt.receipt_number),
+        BODY_SIZE,
+        RIGHT_EDGE,
+    );
+    writer.advance(1.0);
+    if let Some(tax_id) = &shop.tax_id {
+        writer.text(
+            &format!("เลขประจำตัวผู้เสียภาษี / Tax ID {}", tax_id),
+            BODY_SIZE,
+            MARGIN,
+        );
+    }
+    writer.text_right(
+        &format!("วันที่ / Date {}", format_date(receipt.issued_at)),
+        BODY_SIZE,
+        RIGHT_EDGE,
+    );
+    writer.advance(1.0);
+    writer.text_right(
+        &format!("คำสั่งซื้อ / Order #{}", order_id),
+        BODY_SIZE,
+        RIGHT_EDGE,
+    );
+    writer.advance(2.0);
+}
+
+fn write_buyer(writer: &mut PageWriter<'_>, receipt: &Receipt) {
+    writer.text(
+        &format!("ลูกค้า / Customer  {}", receipt.buyer_name),
+        BODY_SIZE,
+        MARGIN,
+    );
+    writer.advance(1.0);
+    if let Some(tax_id) = &receipt.buyer_tax_id {
+        writer.text(
+            &format!("เลขประจำตัวผู้เสียภาษี / Tax ID  {}", tax_id),
+            BODY_SIZE,
+            MARGIN,
+        );
+        writer.advance(1.0);
+    }
+    if let Some(address) = &receipt.buyer_address {
+        let address = writer.fit(
+            &format!("ที่อยู่ / Address  {}", address),
+            BODY_SIZE,
+            RIGHT_EDGE - MARGIN,
+        );
+        writer.text(&address, BODY_SIZE, MARGIN);
+        writer.advance(1.0);
+    }
+    writer.advance(1.0);
+}
+
+fn write_items(writer: &mut PageWriter<'_>, order: &ReceiptOrder) {
+    table_header(writer);
+    for (index, line) in order.lines.iter().enumerate() {
+        if writer.ensure_room(1.0) {
+            table_header(writer);
+        }
+        writer.text(&(index + 1).to_string(), BODY_SIZE, COL_NO.0);
+        let description = writer.fit(
+            &line.description,
+            BODY_SIZE,
+            COL_DESCRIPTION.1 - COL_DESCRIPTION.0 - 2.0,
+        );
+        writer.text(&description, BODY_SIZE, COL_DESCRIPTION.0);
+        writer.text_right(&line.quantity.to_string(), BODY_SIZE, COL_QTY.1);
+        writer.text_right(&format_money(&line.unit_price), BODY_SIZE, COL_UNIT_PRICE.1);
+        writer.text_right(&format_money(&line.amount), BODY_SIZE, COL_AMOUNT.1);
+        writer.advance(1.0);
+    }
+    writer.rule(MARGIN, RIGHT_EDGE);
+    writer.advance(0.5);
+}
+
+fn write_totals(writer: &mut PageWriter<'_>, receipt: &Receipt, order: &ReceiptOrder) {
+    writer.ensure_room(7.0);
+    if !order.discount_amount.is_zero() {
+        total_row(writer, "ส่วนลด / Discount", &-&order.discount_amount);
+    }
+    if !order.shipping_fee.is_zero() {
+        total_row(writer, "ค่าจัดส่ง / Shipping", &order.shipping_fee);
+    }
+    if receipt.document_type == DocumentType::TaxInvoice {
+        let (base, vat) = split_vat(&order.total_amount);
+        total_row(writer, "มูลค่าก่อนภาษี / Value before VAT", &base);
+        total_row(writer, &format!("ภาษีมูลค่าเพิ่ม / VAT {}%", VAT_PERCENT), &vat);
+    }
+    total_row(writer, "รวมทั้งสิ้น / Total (THB)", &order.total_amount);
+    writer.advance(1.0);
+
+    if let Some(paid_at) = order.paid_at {
+        writer.text(
+            &format!("ชำระเงินเมื่อ / Paid on {}", format_datetime(paid_at)),
+            BODY_SIZE,
+            MARGIN,
+        );
+    }
+}
+
+fn table_header(writer: &mut PageWriter<'_>) {
+    writer.text("#", BODY_SIZE, COL_NO.0);
+    writer.text("รายการ / Description", BODY_SIZE, COL_DESCRIPTION.0);
+    writer.text_right("จำนวน / Qty", BODY_SIZE, COL_QTY.1);
+    writer.text_right("ราคา / Price", BODY_SIZE, COL_UNIT_PRICE.1);
+    writer.text_right("จำนวนเงิน / Amount", BODY_SIZE, COL_AMOUNT.1);
+    writer.advance(0.4);
+    writer.rule(MARGIN, RIGHT_EDGE);
+    writer.advance(0.8);
+}
+
+fn total_row(writer: &mut PageWriter<'_>, label: &str, amount: &BigDecimal) {
+    writer.text_right(label, BODY_SIZE, COL_UNIT_PRICE.1);
+    writer.text_right(&format_money(amount), BODY_SIZE, COL_AMOUNT.1);
+    writer.advance(1.0);
+}
+
+// Splits a VAT-inclusive total into the value before VAT and the VAT itself
+fn split_vat(total: &BigDecimal) -> (BigDecimal, BigDecimal) {
+    let base = (total * BigDecimal::from(100) / BigDecimal::from(100 + VAT_PERCENT))
+        .with_scale_round(2, RoundingMode::HalfUp);
+    let vat = total - &base;
+    (base, vat)
+}
+
+fn format_money(amount: &BigDecimal) -> String {
+    let text = amount.with_scale_round(2, RoundingMode::HalfUp).to_string();
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", text.as_str()),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, "00"));
+
+    let mut grouped = String::new();
+    for (index, c) in whole.chars().enumerate() {
+        if index > 0 && (whole.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("{}{}.{}", sign, grouped, fraction)
+}
+
+// Timestamps are stored in UTC; documents show Bangkok time
+fn to_local(at: NaiveDateTime) -> chrono::DateTime<FixedOffset> {
+    let bangkok = FixedOffset::east_opt(7 * 3600).expect("valid offset");
+    Utc.from_utc_datetime(&at).with_timezone(&bangkok)
+}
+
+fn format_date(at: NaiveDateTime) -> String {
+    to_local(at).format("%d/%m/%Y").to_string()
+}
+
+fn format_datetime(at: NaiveDateTime) -> String {
+    to_local(at).format("%d/%m/%Y %H:%M").to_string()
+}
+
+fn pdf_error(e: &printpdf::Error) -> Error {
+    Error::with_message(
+        ErrorCode::InternalError,
+        format!("Failed to render PDF: {}", e),
+    )
+}
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+
+use crate::utils::errors::Error;
+
+use super::entity::{BuyerDetails, DocumentType, Receipt, ReceiptOrder};
+
+#[async_trait]
+pub trait ReceiptRepository: Send + Sync {
+    async fn find_order(&self, order_id: i64) -> Result<ReceiptOrder, Error>;
+    async fn find_issued(
+        &self,
+        order_id: i64,
+        document_type: DocumentType,
+    ) -> Result<Option<Receipt>, Error>;
+    /// Returns the order's document of this type, issuing it with the next number in sequence
+    /// if it does not exist yet. Buyer details are fixed at first issue.
+    async fn issue(
+        &self,
+        order_id: i64,
+        document_type: DocumentType,
+        buyer: BuyerDetails,
+    ) -> Result<Receipt, Error>;
+}
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use tracing::error;
+
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{BuyerDetails, DocumentType, Receipt, ReceiptOrder, ReceiptQuery, ShopDetails};
+use super::pdf;
+use super::repository::ReceiptRepository;
+
+#[derive(Clone)]
+pub struct ReceiptService {
+    repo: Arc<dyn ReceiptRepository>,
+    shop: ShopDetails,
+    font_path: String,
+}
+
+impl ReceiptService {
+    pub fn new(repo: Arc<dyn ReceiptRepository>, shop: ShopDetails, font_path: String) -> Self {
+        Self {
+            repo,
+            shop,
+            font_path,
+        }
+    }
+
+    /// Issues (or re-prints) the order's receipt or tax invoice and renders it as a PDF.
+    /// Only the order owner or an admin may download it, and only once the order is paid.
+    pub async fn render_receipt(
+        &self,
+        order_id: i64,
+        user_id: i64,
+        is_admin: bool,
+        query: ReceiptQuery,
+    ) -> Result<(Receipt, Vec<u8>), Error> {
+        let order = self.repo.find_order(order_id).await?;
+        if order.user_id != user_id && !is_admin {
+            return Err(Error::with_message(
+                ErrorCode::ResourceNotFound,
+                "Order not found",
+            ));
+        }
+
+        if order.paid_at.is_none() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Receipts are only available for paid orders",
+            ));
+        }
+
+        let document_type = query.document_type.unwrap_or(DocumentType::Receipt);
+        if document_type == DocumentType::TaxInvoice && self.shop.tax_id.is_none() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Tax invoices are not available",
+            ));
+        }
+
+        let font_data = tokio::fs::read(&self.font_path).await.map_err(|e| {
+            error!(error = %e, path = self.font_path, "Failed to read receipt font");
+            Error::with_message(ErrorCode::InternalError, "Receipt font is not available")
+        })?;
+
+        // Re-prints keep the buyer details the document was first issued with
+        let receipt = if let Some(receipt) = self.repo.find_issued(order_id, document_type).await? {
+            receipt
+        } else {
+            let buyer = buyer_details(&order, document_type, query)?;
+            self.repo.issue(order_id, document_type, buyer).await?
+        };
+        let pdf = pdf::render(&font_data, &self.shop, &receipt, &order)?;
+
+        Ok((receipt, pdf))
+    }
+}
+
+fn buyer_details(
+    order: &ReceiptOrder,
+    document_type: DocumentType,
+    query: ReceiptQuery,
+) -> Result<BuyerDetails, Error> {
+    let tax_id = match non_empty(query.tax_id) {
+        Some(tax_id) if document_type == DocumentType::TaxInvoice => {
+            Some(normalize_tax_id(&tax_id)?)
+        }
+        _ => None,
+    };
+
+    if document_type == DocumentType::TaxInvoice && tax_id.is_none() {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "tax_id is required for a tax invoice",
+        ));
+    }
+
+    let name = non_empty(query.buyer_name)
+        .or_else(|| order.buyer_name.clone())
+        .unwrap_or_else(|| order.buyer_email.clone());
+    if name.chars().count() > 200 {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Buyer name must be 200 characters or fewer",
+        ));
+    }
+
+    Ok(BuyerDetails {
+        name,
+        tax_id,
+        address: non_empty(query.address).or_else(|| order.shipping_address.clone()),
+    })
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// Thai tax IDs are 13 digits, the last being a mod-11 check digit over the first twelve
+fn normalize_tax_id(tax_id: &str) -> Result<String, Error> {
+    let digits: Vec<u32> = tax_id
+        .chars()
+        .filter(|c| *c != '-' && *c != ' ')
+        .map(|c| c.to_digit(10))
+        .collect::<Option<_>>()
+        .unwrap_or_default();
+
+    let invalid = || Error::with_message(ErrorCode::ValidationError, "Invalid tax ID");
+    if digits.len() != 13 {
+        return Err(invalid());
+    }
+
+    let sum: u32 = digits[..12]
+        .iter()
+        .zip((2..=13).rev())
+        .map(|(digit, weight)| digit * weight)
+        .sum();
+    if (11 - sum % 11) % 10 != digits[12] {
+        return Err(invalid());
+    }
+
+    Ok(digits.iter().map(ToString::to_string).collect())
+}
@@ -0,0 +1,633 @@
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use bigdecimal::{BigDecimal, Zero};
+use chrono::NaiveDateTime;
+use diesel::dsl::sum;
+use diesel::prelude::*;
+use tracing::error;
+
+use crate::core::bundle::diesel::stock_units;
+use crate::core::inventory::diesel::apply_movement;
+use crate::core::inventory::entity::{InventoryReason, NewInventoryMovement};
+use crate::core::order::entity::OrderStatus;
+use crate::core::payment::entity::PaymentStatus;
+use crate::schema::{order_items, orders, payments, refund_items, refunds};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{
+    IssuedRefund, NewRefund, PendingRefund, Refund, RefundItem, RefundLine, RefundQuote,
+    RefundStatus,
+};
+use super::repository::RefundRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = refunds)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct RefundModel {
+    pub refund_id: i64,
+    pub payment_id: i64,
+    pub order_id: i64,
+    pub amount: BigDecimal,
+    pub reason: String,
+    pub restocked: bool,
+    pub evidence_object: Option<String>,
+    pub provider_reference: Option<String>,
+    pub created_by: String,
+    pub created_at: NaiveDateTime,
+    pub refund_status: RefundStatus,
+}
+
+#[derive(Queryable, Selectable, Insertable)]
+#[diesel(table_name = refund_items)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct RefundItemModel {
+    pub refund_id: i64,
+    pub order_item_id: i64,
+    pub quantity: i32,
+    pub amount: BigDecimal,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = refunds)]
+struct NewRefundModel<'a> {
+    pub payment_id: i64,
+    pub order_id: i64,
+    pub amount: &'a BigDecimal,
+    pub reason: &'a str,
+    pub restocked: bool,
+    pub created_by: &'a str,
+    pub refund_status: RefundStatus,
+}
+
+impl RefundModel {
+    fn into_refund(self, items: Vec<RefundItemModel>) -> Refund {
+        Refund {
+            refund_id: self.refund_id,
+            payment_id: self.payment_id,
+            order_id: self.order_id,
+            amount: self.amount,
+            reason: self.reason,
+            restocked: self.restocked,
+            evidence_object: self.evidence_object,
+            provider_reference: self.provider_reference,
+            created_by: self.created_by,
+            created_at: self.created_at,
+            refund_status: self.refund_status,
+            items: items
+                .into_iter()
+                .map(|item| RefundItem {
+                    order_item_id: item.order_item_id,
+                    quantity: item.quantity,
+                    amount: item.amount,
+                })
+                .collect(),
+        }
+    }
+}
+
+// An order line: item id, quantity ordered, unit price and its share of the discount
+type OrderLine = (i64, Option<i32>, Option<BigDecimal>, BigDecimal);
+
+struct PlannedItem {
+    order_item_id: i64,
+    quantity: i32,
+    amount: BigDecimal,
+}
+
+// A refund checked against the payment and what has already been refunded
+struct RefundPlan {
+    order_id: i64,
+    amount: BigDecimal,
+    provider: Option<String>,
+    provider_reference: Option<String>,
+    items: Vec<PlannedItem>,
+}
+
+fn refundable_quantity(
+    refunded_quantities: &HashMap<i64, i64>,
+    order_item_id: i64,
+    quantity: Option<i32>,
+) -> i32 {
+    let refunded = refunded_quantities
+        .get(&order_item_id)
+        .copied()
+        .unwrap_or(0);
+    i32::try_from(i64::from(quantity.unwrap_or(0)) - refunded).unwrap_or(0)
+}
+
+// What `quantity` units of a line were paid: their price less their share of the line's
+// discount
+fn line_amount(
+    unit_price: &BigDecimal,
+    discount: &BigDecimal,
+    ordered: Option<i32>,
+    quantity: i32,
+) -> BigDecimal {
+    let amount = unit_price * BigDecimal::from(quantity);
+    match ordered.filter(|ordered| *ordered > 0) {
+        Some(ordered) => {
+            amount - (discount * BigDecimal::from(quantity) / BigDecimal::from(ordered)).round(2)
+        }
+        None => amount,
+    }
+}
+
+fn remaining_items(
+    order_lines: Vec<OrderLine>,
+    refunded_quantities: &HashMap<i64, i64>,
+) -> Vec<PlannedItem> {
+    order_lines
+        .into_iter()
+        .filter_map(|(order_item_id, ordered, unit_price, discount)| {
+            let quantity = refundable_quantity(refunded_quantities, order_item_id, ordered);
+            (quantity > 0).then(|| PlannedItem {
+                order_item_id,
+                quantity,
+                amount: line_amount(
+                    &unit_price.unwrap_or_else(BigDecimal::zero),
+                    &discount,
+                    ordered,
+                    quantity,
+                ),
+            })
+        })
+        .collect()
+}
+
+// Prices each requested line, checking it belongs to the order and is not refunded twice
+fn plan_lines(
+    lines: &[RefundLine],
+    order_lines: &[OrderLine],
+    refunded_quantities: &HashMap<i64, i64>,
+) -> Result<Vec<PlannedItem>, Error> {
+    if lines.is_empty() {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "At least one item is required for a partial refund",
+        ));
+    }
+
+    let mut seen = HashSet::new();
+    let mut items = Vec::with_capacity(lines.len());
+    for line in lines {
+        if !seen.insert(line.order_item_id) {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Order item {} is listed more than once", line.order_item_id),
+            ));
+        }
+
+        let Some((_, quantity, unit_price, discount)) = order_lines
+            .iter()
+            .find(|(order_item_id, ..)| *order_item_id == line.order_item_id)
+        else {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!(
+                    "Order item {} is not part of this order",
+                    line.order_item_id
+                ),
+            ));
+        };
+
+        let available = refundable_quantity(refunded_quantities, line.order_item_id, *quantity);
+        if line.quantity <= 0 || line.quantity > available {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!(
+                    "Order item {} can be refunded for 1 to {} units",
+                    line.order_item_id, available
+                ),
+            ));
+        }
+
+        let Some(unit_price) = unit_price else {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Order item {} has no unit price", line.order_item_id),
+            ));
+        };
+
+        items.push(PlannedItem {
+            order_item_id: line.order_item_id,
+            quantity: line.quantity,
+            amount: line_amount(unit_price, discount, *quantity, line.quantity),
+        });
+    }
+
+    Ok(items)
+}
+
+// Locks the payment so concurrent refunds against it are planned one at a time. Pending
+// refunds count as paid out; failed ones don't.
+fn plan_refund(
+    conn: &mut PgConnection,
+    payment_id: i64,
+    lines: Option<&[RefundLine]>,
+) -> Result<RefundPlan, Error> {
+    let (order_id, amount_paid, status, provider, provider_reference): (
+        i64,
+        Option<BigDecimal>,
+        PaymentStatus,
+        Option<String>,
+        Option<String>,
+    ) = payments::table
+        .filter(payments::payment_id.eq(payment_id))
+        .select((
+            payments::order_id,
+            payments::amount_paid,
+            payments::payment_status,
+            payments::provider,
+            payments::provider_reference,
+        ))
+        .for_update()
+        .first(conn)
+        .map_err(|e| match e {
+            diesel::result::Error::NotFound => {
+                Error::with_message(ErrorCode::ResourceNotFound, "Payment not found")
+            }
+            _ => Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e)),
+        })?;
+
+    let amount_paid = match (status, amount_paid) {
+        (PaymentStatus::Verified, Some(amount_paid)) => amount_paid,
+        _ => {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Only verified payments can be refunded",
+            ));
+        }
+    };
+
+    let already_refunded: BigDecimal = refunds::table
+        .filter(refunds::payment_id.eq(payment_id))
+        .filter(refunds::refund_status.ne(RefundStatus::Failed))
+        .select(sum(refunds::amount))
+        .first::<Option<BigDecimal>>(conn)?
+        .unwrap_or_else(BigDecimal::zero);
+
+    let remaining = &amount_paid - &already_refunded;
+    if remaining <= BigDecimal::zero() {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Payment has already been fully refunded",
+        ));
+    }
+
+    let order_lines: Vec<OrderLine> = order_items::table
+        .filter(order_items::order_id.eq(order_id))
+        .select((
+            order_items::order_item_id,
+            order_items::quantity,
+            order_items::unit_price,
+            order_items::discount_amount,
+        ))
+        .order(order_items::order_item_id.asc())
+        .load(conn)?;
+
+    let refunded_quantities: HashMap<i64, i64> = refund_items::table
+        .inner_join(refunds::table)
+        .filter(refunds::order_id.eq(order_id))
+        .filter(refunds::refund_status.ne(RefundStatus::Failed))
+        .group_by(refund_items::order_item_id)
+        .select((refund_items::order_item_id, sum(refund_items::quantity)))
+        .load::<(i64, Option<i64>)>(conn)?
+        .into_iter()
+        .map(|(order_item_id, quantity)| (order_item_id, quantity.unwrap_or(0)))
+        .collect();
+
+    let Some(lines) = lines else {
+        // A full refund pays back the whole remaining balance and covers every line left
+        let items = remaining_items(order_lines, &refunded_quantities);
+        return Ok(RefundPlan {
+            order_id,
+            amount: remaining,
+            provider,
+            provider_reference,
+            items,
+        });
+    };
+
+    let items = plan_lines(lines, &order_lines, &refunded_quantities)?;
+    let amount: BigDecimal = items.iter().map(|item| &item.amount).sum();
+    if amount > remaining {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!(
+                "Refund of {} would exceed the {} left on this payment",
+                amount, remaining
+            ),
+        ));
+    }
+
+    Ok(RefundPlan {
+        order_id,
+        amount,
+        provider,
+        provider_reference,
+        items,
+    })
+}
+
+fn load_items(
+    conn: &mut PgConnection,
+    refund_ids: &[i64],
+) -> Result<HashMap<i64, Vec<RefundItemModel>>, Error> {
+    let rows: Vec<RefundItemModel> = refund_items::table
+        .filter(refund_items::refund_id.eq_any(refund_ids))
+        .select(RefundItemModel::as_select())
+        .order(refund_items::order_item_id.asc())
+        .load(conn)?;
+
+    let mut by_refund: HashMap<i64, Vec<RefundItemModel>> = HashMap::new();
+    for row in rows {
+        by_refund.entry(row.refund_id).or_default().push(row);
+    }
+    Ok(by_refund)
+}
+
+pub struct DieselRefundRepository {
+    pool: DBPool,
+}
+
+impl DieselRefundRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RefundRepository for DieselRefundRepository {
+    async fn quote(
+        &self,
+        payment_id: i64,
+        lines: Option<&[RefundLine]>,
+    ) -> Result<RefundQuote, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let plan = conn.transaction(|conn| plan_refund(conn, payment_id, lines))?;
+
+        Ok(RefundQuote {
+            amount: plan.amount,
+            provider: plan.provider,
+        })
+    }
+
+    async fn create(&self, new_refund: NewRefund) -> Result<PendingRefund, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let payment_id = new_refund.payment_id;
+        conn.transaction::<_, Error, _>(|conn| {
+            let plan = plan_refund(conn, payment_id, new_refund.lines.as_deref())?;
+            if plan.amount != new_refund.amount {
+                return Err(Error::with_message(
+                    ErrorCode::ValidationError,
+                    "The refundable balance changed while the refund was being issued",
+                ));
+            }
+
+            let refund: RefundModel = diesel::insert_into(refunds::table)
+                .values(&NewRefundModel {
+                    payment_id,
+                    order_id: plan.order_id,
+                    amount: &plan.amount,
+                    reason: &new_refund.reason,
+                    restocked: new_refund.restock,
+                    created_by: &new_refund.created_by,
+                    refund_status: RefundStatus::Pending,
+                })
+                .returning(RefundModel::as_returning())
+                .get_result(conn)?;
+
+            let items: Vec<RefundItemModel> = plan
+                .items
+                .iter()
+                .map(|item| RefundItemModel {
+                    refund_id: refund.refund_id,
+                    order_item_id: item.order_item_id,
+                    quantity: item.quantity,
+                    amount: item.amount.clone(),
+                })
+                .collect();
+            if !items.is_empty() {
+                diesel::insert_into(refund_items::table)
+                    .values(&items)
+                    .execute(conn)?;
+            }
+
+            Ok(PendingRefund {
+                refund: refund.into_refund(items),
+                provider_reference: plan.provider_reference,
+            })
+        })
+        .map_err(|e| {
+            error!(error = %e, payment_id, "Failed to record refund");
+            e
+        })
+    }
+
+    async fn complete(
+        &self,
+        refund_id: i64,
+        provider_reference: Option<&str>,
+    ) -> Result<IssuedRefund, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction::<_, Error, _>(|conn| {
+            let refund: RefundModel = diesel::update(
+                refunds::table
+                    .filter(refunds::refund_id.eq(refund_id))
+                    .filter(refunds::refund_status.eq(RefundStatus::Pending)),
+            )
+            .set((
+                refunds::refund_status.eq(RefundStatus::Completed),
+                refunds::provider_reference.eq(provider_reference),
+            ))
+            .returning(RefundModel::as_returning())
+            .get_result(conn)
+            .optional()?
+            .ok_or_else(|| {
+                Error::with_message(ErrorCode::ResourceNotFound, "Pending refund not found")
+            })?;
+
+            let items = load_items(conn, &[refund_id])?
+                .remove(&refund_id)
+                .unwrap_or_default();
+
+            if refund.restocked {
+                // Pre-order lines took no stock at checkout, so there is none to put back
+                let lines: Vec<(i64, i64)> = order_items::table
+                    .filter(
+                        order_items::order_item_id
+                            .eq_any(items.iter().map(|item| item.order_item_id)),
+                    )
+                    .filter(order_items::preorder.eq(false))
+                    .select((order_items::order_item_id, order_items::variant_id))
+                    .load(conn)?;
+
+                for item in &items {
+                    let Some((_, variant_id)) = lines
+                        .iter()
+                        .find(|(order_item_id, _)| *order_item_id == item.order_item_id)
+                    else {
+                        continue;
+                    };
+                    for (variant_id, quantity) in
+                        stock_units(conn, item.order_item_id, *variant_id, item.quantity)?
+                    {
+                        apply_movement(
+                            conn,
+                            NewInventoryMovement {
+                                variant_id,
+                                quantity_change: quantity,
+                                reason: InventoryReason::Return,
+                                order_id: Some(refund.order_id),
+                                note: Some(format!("Refund #{}", refund_id)),
+                                created_by: Some(refund.created_by.clone()),
+                            },
+                        )?;
+                    }
+                }
+            }
+
+            // Locked so refunds of the same payment completing together agree on the status
+            let amount_paid: Option<BigDecimal> = payments::table
+                .filter(payments::payment_id.eq(refund.payment_id))
+                .select(payments::amount_paid)
+                .for_update()
+                .first(conn)?;
+            let refunded: BigDecimal = refunds::table
+                .filter(refunds::payment_id.eq(refund.payment_id))
+                .filter(refunds::refund_status.eq(RefundStatus::Completed))
+                .select(sum(refunds::amount))
+                .first::<Option<BigDecimal>>(conn)?
+                .unwrap_or_else(BigDecimal::zero);
+            let order_status = if amount_paid.is_some_and(|amount_paid| refunded >= amount_paid) {
+                diesel::update(payments::table.filter(payments::payment_id.eq(refund.payment_id)))
+                    .set(payments::needs_refund.eq(false))
+                    .execute(conn)?;
+                OrderStatus::Refunded
+            } else {
+                OrderStatus::PartiallyRefunded
+            };
+            let user_id: i64 =
+                diesel::update(orders::table.filter(orders::order_id.eq(refund.order_id)))
+                    .set(orders::order_status.eq(order_status))
+                    .returning(orders::user_id)
+                    .get_result(conn)?;
+
+            Ok(IssuedRefund {
+                refund: refund.into_refund(items),
+                user_id,
+            })
+        })
+        .map_err(|e| {
+            error!(error = %e, refund_id, "Failed to complete refund");
+            e
+        })
+    }
+
+    async fn fail(&self, refund_id: i64) -> Result<(), Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        diesel::update(
+            refunds::table
+                .filter(refunds::refund_id.eq(refund_id))
+                .filter(refunds::refund_status.eq(RefundStatus::Pending)),
+        )
+        .set(refunds::refund_status.eq(RefundStatus::Failed))
+        .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn find_by_payment(&self, payment_id: i64) -> Result<Vec<Refund>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: Vec<RefundModel> = refunds::table
+            .filter(refunds::payment_id.eq(payment_id))
+            .select(RefundModel::as_select())
+            .order(refunds::refund_id.asc())
+            .load(&mut conn)
+            .map_err(|e| {
+                Error::with_message(
+                    ErrorCode::DatabaseError,
+                    format!("Failed to fetch refunds: {}", e),
+                )
+            })?;
+
+        let ids: Vec<i64> = rows.iter().map(|row| row.refund_id).collect();
+        let mut items = load_items(&mut conn, &ids)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let row_items = items.remove(&row.refund_id).unwrap_or_default();
+                row.into_refund(row_items)
+            })
+            .collect())
+    }
+
+    async fn find_with_owner(&self, refund_id: i64) -> Result<(Refund, i64), Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let (row, owner_id): (RefundModel, i64) = refunds::table
+            .inner_join(orders::table)
+            .filter(refunds::refund_id.eq(refund_id))
+            .select((RefundModel::as_select(), orders::user_id))
+            .first(&mut conn)
+            .map_err(|e| match e {
+                diesel::result::Error::NotFound => {
+                    Error::with_message(ErrorCode::ResourceNotFound, "Refund not found")
+                }
+                _ => {
+                    Error::with_message(ErrorCode::DatabaseError, format!("Database error: {}", e))
+                }
+            })?;
+
+        let items = load_items(&mut conn, &[refund_id])?
+            .remove(&refund_id)
+            .unwrap_or_default();
+        Ok((row.into_refund(items), owner_id))
+    }
+
+    async fn set_evidence(&self, refund_id: i64, evidence_object: &str) -> Result<Refund, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let row: RefundModel =
+            diesel::update(refunds::table.filter(refunds::refund_id.eq(refund_id)))
+                .set(refunds::evidence_object.eq(Some(evidence_object)))
+                .returning(RefundModel::as_returning())
+                .get_result(&mut conn)
+                .map_err(|e| match e {
+                    diesel::result::Error::NotFound => {
+                        Error::with_message(ErrorCode::ResourceNotFound, "Refund not found")
+                    }
+                    _ => Error::with_message(
+                        ErrorCode::DatabaseError,
+                        format!("Database error: {}", e),
+                    ),
+                })?;
+
+        let items = load_items(&mut conn, &[refund_id])?
+            .remove(&refund_id)
+            .unwrap_or_default();
+        Ok(row.into_refund(items))
+    }
+}
@@ -0,0 +1,92 @@
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::RefundStatus"]
+pub enum RefundStatus {
+    /// Recorded and holding its share of the payment while the provider sends the money
+    #[db_rename = "PENDING"]
+    Pending,
+    #[db_rename = "COMPLETED"]
+    Completed,
+    /// Turned down by the provider; it no longer counts against the payment
+    #[db_rename = "FAILED"]
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Refund {
+    pub refund_id: i64,
+    pub payment_id: i64,
+    pub order_id: i64,
+    pub amount: BigDecimal,
+    pub reason: String,
+    /// Whether the refunded items were put back into stock
+    pub restocked: bool,
+    /// Transfer slip showing the money was sent back, kept in the private bucket and served by
+    /// `GET /refunds/:id/evidence`
+    pub evidence_object: Option<String>,
+    /// Refund ID at the payment provider, for gateway payments
+    pub provider_reference: Option<String>,
+    pub created_by: String,
+    pub created_at: NaiveDateTime,
+    pub refund_status: RefundStatus,
+    pub items: Vec<RefundItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefundItem {
+    pub order_item_id: i64,
+    pub quantity: i32,
+    pub amount: BigDecimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefundLine {
+    pub order_item_id: i64,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueRefundRequest {
+    /// Order lines to refund; omit to refund everything not yet refunded
+    pub items: Option<Vec<RefundLine>>,
+    pub reason: String,
+    /// Put the refunded items back into stock, e.g. for cancellations but not defective returns.
+    /// Pre-order lines never took stock and are left out.
+    pub restock: Option<bool>,
+}
+
+// What a refund would pay out, checked before money is sent through a provider
+#[derive(Debug, Clone)]
+pub struct RefundQuote {
+    pub amount: BigDecimal,
+    pub provider: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewRefund {
+    pub payment_id: i64,
+    pub lines: Option<Vec<RefundLine>>,
+    pub reason: String,
+    pub restock: bool,
+    pub created_by: String,
+    /// The quoted amount; recording fails if the refundable balance changed meanwhile
+    pub amount: BigDecimal,
+}
+
+// A refund recorded as pending, with the gateway charge to refund if the payment has one
+#[derive(Debug, Clone)]
+pub struct PendingRefund {
+    pub refund: Refund,
+    pub provider_reference: Option<String>,
+}
+
+// A recorded refund with the order owner to notify
+#[derive(Debug, Clone)]
+pub struct IssuedRefund {
+    pub refund: Refund,
+    pub user_id: i64,
+}
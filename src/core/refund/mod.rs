@@ -0,0 +1,4 @@
+pub mod diesel;
+pub mod entity;
+pub mod repository;
+pub mod service;
@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+
+use crate::utils::errors::Error;
+
+use super::entity::{IssuedRefund, NewRefund, PendingRefund, Refund, RefundLine, RefundQuote};
+
+#[async_trait]
+pub trait RefundRepository: Send + Sync {
+    /// Works out what refunding `lines` (or the whole remaining balance when `None`) would pay
+    /// out, without recording anything.
+    async fn quote(
+        &self,
+        payment_id: i64,
+        lines: Option<&[RefundLine]>,
+    ) -> Result<RefundQuote, Error>;
+    /// Re-checks the quote under the payment lock and records the refund as `PENDING`, so it
+    /// counts against the payment before any money is sent.
+    async fn create(&self, new_refund: NewRefund) -> Result<PendingRefund, Error>;
+    /// Marks a pending refund `COMPLETED`, optionally restocks its items and moves the order to
+    /// `PARTIALLY_REFUNDED` or `REFUNDED`.
+    async fn complete(
+        &self,
+        refund_id: i64,
+        provider_reference: Option<&str>,
+    ) -> Result<IssuedRefund, Error>;
+    /// Marks a pending refund `FAILED`, giving its amount back to the payment
+    async fn fail(&self, refund_id: i64) -> Result<(), Error>;
+    async fn find_by_payment(&self, payment_id: i64) -> Result<Vec<Refund>, Error>;
+    /// The refund together with the ID of the customer whose order it refunds
+    async fn find_with_owner(&self, refund_id: i64) -> Result<(Refund, i64), Error>;
+    async fn set_evidence(&self, refund_id: i64, evidence_object: &str) -> Result<Refund, Error>;
+}
@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use tracing::error;
+
+use crate::core::notification::entity::{NewNotification, NotificationType};
+use crate::core::notification::service::NotificationService;
+use crate::core::payment::provider::PaymentProvider;
+use crate::utils::errors::{Error, ErrorCode};
+use crate::utils::storage::StorageService;
+
+use super::entity::{IssueRefundRequest, NewRefund, Refund};
+use super::repository::RefundRepository;
+
+// Refund slips show the customer's bank account, so they go to the private bucket
+const EVIDENCE_FOLDER: &str = "refund-slips";
+const MAX_EVIDENCE_SIZE: usize = 10 * 1024 * 1024;
+
+#[derive(Clone)]
+pub struct RefundService {
+    repo: Arc<dyn RefundRepository>,
+    storage: StorageService,
+    notifications: NotificationService,
+    provider: Option<Arc<dyn PaymentProvider>>,
+}
+
+impl RefundService {
+    pub fn new(
+        repo: Arc<dyn RefundRepository>,
+        storage: StorageService,
+        notifications: NotificationService,
+        provider: Option<Arc<dyn PaymentProvider>>,
+    ) -> Self {
+        Self {
+            repo,
+            storage,
+            notifications,
+            provider,
+        }
+    }
+
+    /// Refunds the given lines of a verified payment, or everything left on it when no lines
+    /// are given. Gateway payments are refunded through their provider; for transfers the admin
+    /// sends the money and uploads the slip afterwards. A gateway refund whose outcome is
+    /// unknown stays `PENDING` and keeps its amount off the payment's refundable balance.
+    pub async fn issue_refund(
+        &self,
+        payment_id: i64,
+        req: IssueRefundRequest,
+        created_by: &str,
+    ) -> Result<Refund, Error> {
+        let reason = req.reason.trim().to_string();
+        if reason.is_empty() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Refund reason is required",
+            ));
+        }
+
+        let quote = self.repo.quote(payment_id, req.items.as_deref()).await?;
+        let provider = match quote.provider.as_deref() {
+            Some(name) => Some(
+                self.provider
+                    .as_ref()
+                    .filter(|provider| provider.name() == name)
+                    .ok_or_else(|| {
+                        Error::with_message(
+                            ErrorCode::ValidationError,
+                            format!("Payment provider '{}' is not configured", name),
+                        )
+                    })?,
+            ),
+            None => None,
+        };
+
+        // Recorded before any money moves, so a concurrent refund of the same payment sees
+        // the reduced balance
+        let pending = self
+            .repo
+            .create(NewRefund {
+                payment_id,
+                lines: req.items,
+                reason,
+                restock: req.restock.unwrap_or(false),
+                created_by: created_by.to_string(),
+                amount: quote.amount,
+            })
+            .await?;
+        let refund_id = pending.refund.refund_id;
+
+        let provider_reference = match (provider, pending.provider_reference.as_deref()) {
+            (Some(provider), Some(reference)) => {
+                let idempotency_key = format!("refund-{}", refund_id);
+                match provider
+                    .refund(reference, &pending.refund.amount, &idempotency_key)
+                    .await
+                {
+                    Ok(provider_refund) => Some(provider_refund.reference),
+                    Err(e)
+                        if matches!(
+                            e.code,
+                            ErrorCode::ValidationError | ErrorCode::ResourceNotFound
+                        ) =>
+                    {
+                        if let Err(fail_err) = self.repo.fail(refund_id).await {
+                            error!(error = %fail_err, refund_id, "Failed to mark refund as failed");
+                        }
+                        return Err(e);
+                    }
+                    Err(e) => {
+                        // The money may or may not have gone out, so the refund stays pending
+                        // and keeps holding its share of the payment
+                        error!(error = %e, refund_id, "Provider refund outcome unknown; left pending");
+                        return Err(e);
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let issued = self
+            .repo
+            .complete(refund_id, provider_reference.as_deref())
+            .await
+            .inspect_err(|e| {
+                if let Some(reference) = &provider_reference {
+                    error!(error = %e, refund_id, reference, "Refunded at provider but not completed");
+                }
+            })?;
+
+        let notification = NewNotification {
+            user_id: issued.user_id,
+            order_id: Some(issued.refund.order_id),
+            notification_type: NotificationType::RefundIssued,
+            message: format!(
+                "A refund of {} THB for order #{} has been issued",
+                issued.refund.amount, issued.refund.order_id
+            ),
+        };
+        if let Err(e) = self.notifications.notify(notification).await {
+            error!(error = %e, refund_id = issued.refund.refund_id, "Failed to send refund notification");
+        }
+
+        Ok(issued.refund)
+    }
+
+    pub async fn list_refunds(&self, payment_id: i64) -> Result<Vec<Refund>, Error> {
+        self.repo.find_by_payment(payment_id).await
+    }
+
+    pub async fn upload_evidence(
+        &self,
+        refund_id: i64,
+        data: Vec<u8>,
+        filename: &str,
+    ) -> Result<Refund, Error> {
+        if data.is_empty() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Refund slip is required",
+            ));
+        }
+
+        if data.len() > MAX_EVIDENCE_SIZE {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Refund slip must be 10MB or smaller",
+            ));
+        }
+
+        let mime = mime_guess::from_path(filename).first_or_octet_stream();
+        if mime.type_() != mime_guess::mime::IMAGE {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Refund slip must be an image",
+            ));
+        }
+
+        let object_name = Box::pin(self.storage.upload_private_file(
+            data,
+            filename,
+            EVIDENCE_FOLDER,
+        ))
+        .await
+        .map_err(|e| {
+            error!(error = %e, refund_id, "Failed to upload refund slip");
+            Error::with_message(ErrorCode::InternalError, "Failed to upload refund slip")
+        })?;
+
+        self.repo.set_evidence(refund_id, &object_name).await
+    }
+
+    /// The refund's slip and its content type, for the customer refunded and admins
+    pub async fn evidence(
+        &self,
+        refund_id: i64,
+        user_id: i64,
+        is_admin: bool,
+    ) -> Result<(Vec<u8>, String), Error> {
+        let (refund, owner_id) = self.repo.find_with_owner(refund_id).await?;
+        let evidence_object = refund
+            .evidence_object
+            .filter(|_| owner_id == user_id || is_admin)
+            .ok_or_else(|| Error::with_message(ErrorCode::ResourceNotFound, "Slip not found"))?;
+
+        Box::pin(self.storage.read_private_file(&evidence_object))
+            .await
+            .map_err(|e| {
+                error!(error = %e, refund_id, "Failed to read refund slip");
+                Error::with_message(ErrorCode::InternalError, "Failed to read refund slip")
+            })
+    }
+}
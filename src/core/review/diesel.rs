@@ -0,0 +1,267 @@
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use diesel::dsl::exists;
+use diesel::prelude::*;
+use diesel::result::DatabaseErrorKind;
+
+use crate::core::order::entity::OrderStatus;
+use crate::schema::{order_item_components, order_items, orders, product_reviews, users, variants};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{Review, ReviewStatus};
+use super::repository::ReviewRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = product_reviews)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct ReviewModel {
+    pub review_id: i64,
+    pub product_id: i64,
+    pub user_id: i64,
+    pub rating: i16,
+    pub body: Option<String>,
+    pub photo_urls: Vec<String>,
+    pub status: ReviewStatus,
+    pub moderated_by: Option<String>,
+    pub moderated_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl ReviewModel {
+    fn into_review(self, reviewer: Option<String>) -> Review {
+        Review {
+            review_id: self.review_id,
+            product_id: self.product_id,
+            user_id: self.user_id,
+            reviewer,
+            rating: self.rating,
+            body: self.body,
+            photo_urls: self.photo_urls,
+            status: self.status,
+            moderated_by: self.moderated_by,
+            moderated_at: self.moderated_at,
+            created_at: self.created_at,
+        }
+    }
+}
+
+fn write_error(e: diesel::result::Error) -> Error {
+    match e {
+        diesel::result::Error::NotFound => {
+            Error::with_message(ErrorCode::ResourceNotFound, "Review not found")
+        }
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+            Error::with_message(
+                ErrorCode::ResourceAlreadyExists,
+                "You have already reviewed this product",
+            )
+        }
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+            Error::with_message(ErrorCode::ResourceNotFound, "Product not found")
+        }
+        _ => e.into(),
+    }
+}
+
+fn load_review(conn: &mut PgConnection, review_id: i64) -> Result<Review, Error> {
+    let (model, reviewer): (ReviewModel, Option<String>) = product_reviews::table
+        .inner_join(users::table)
+        .filter(product_reviews::review_id.eq(review_id))
+        .select((ReviewModel::as_select(), users::full_name))
+        .first(conn)
+        .map_err(write_error)?;
+
+    Ok(model.into_review(reviewer))
+}
+
+pub struct DieselReviewRepository {
+    pool: DBPool,
+}
+
+impl DieselReviewRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ReviewRepository for DieselReviewRepository {
+    async fn find_by_product(
+        &self,
+        product_id: i64,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<Review>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: Vec<(ReviewModel, Option<String>)> = product_reviews::table
+            .inner_join(users::table)
+            .filter(product_reviews::product_id.eq(product_id))
+            .filter(product_reviews::status.eq(ReviewStatus::Approved))
+            .select((ReviewModel::as_select(), users::full_name))
+            .order((
+                product_reviews::created_at.desc(),
+                product_reviews::review_id.desc(),
+            ))
+            .offset(offset)
+            .limit(limit)
+            .load(&mut conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(model, reviewer)| model.into_review(reviewer))
+            .collect())
+    }
+
+    async fn count_by_product(&self, product_id: i64) -> Result<i64, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let total = product_reviews::table
+            .filter(product_reviews::product_id.eq(product_id))
+            .filter(product_reviews::status.eq(ReviewStatus::Approved))
+            .count()
+            .get_result(&mut conn)?;
+
+        Ok(total)
+    }
+
+    async fn has_purchased(&self, user_id: i64, product_id: i64) -> Result<bool, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let bought: bool = diesel::select(exists(
+            order_items::table
+                .inner_join(orders::table)
+                .inner_join(variants::table)
+                .filter(orders::user_id.eq(user_id))
+                .filter(orders::order_status.eq(OrderStatus::Completed))
+                .filter(variants::product_id.eq(product_id)),
+        ))
+        .get_result(&mut conn)?;
+        if bought {
+            return Ok(true);
+        }
+
+        let in_bundle: bool = diesel::select(exists(
+            order_item_components::table
+                .inner_join(order_items::table.inner_join(orders::table))
+                .inner_join(variants::table)
+                .filter(orders::user_id.eq(user_id))
+                .filter(orders::order_status.eq(OrderStatus::Completed))
+                .filter(variants::product_id.eq(product_id)),
+        ))
+        .get_result(&mut conn)?;
+
+        Ok(in_bundle)
+    }
+
+    async fn has_reviewed(&self, user_id: i64, product_id: i64) -> Result<bool, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let reviewed = diesel::select(exists(
+            product_reviews::table
+                .filter(product_reviews::user_id.eq(user_id))
+                .filter(product_reviews::product_id.eq(product_id)),
+        ))
+        .get_result(&mut conn)?;
+
+        Ok(reviewed)
+    }
+
+    async fn create(
+        &self,
+        product_id: i64,
+        user_id: i64,
+        rating: i16,
+        body: Option<&str>,
+        photo_urls: &[String],
+    ) -> Result<Review, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let review_id: i64 = diesel::insert_into(product_reviews::table)
+            .values((
+                product_reviews::product_id.eq(product_id),
+                product_reviews::user_id.eq(user_id),
+                product_reviews::rating.eq(rating),
+                product_reviews::body.eq(body),
+                product_reviews::photo_urls.eq(photo_urls),
+            ))
+            .returning(product_reviews::review_id)
+            .get_result(&mut conn)
+            .map_err(write_error)?;
+
+        load_review(&mut conn, review_id)
+    }
+
+    async fn delete(&self, product_id: i64, user_id: i64) -> Result<(), Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let deleted = diesel::delete(
+            product_reviews::table
+                .filter(product_reviews::product_id.eq(product_id))
+                .filter(product_reviews::user_id.eq(user_id)),
+        )
+        .execute(&mut conn)?;
+
+        if deleted == 0 {
+            return Err(Error::with_message(
+                ErrorCode::ResourceNotFound,
+                "You have not reviewed this product",
+            ));
+        }
+        Ok(())
+    }
+
+    async fn list(&self, status: ReviewStatus) -> Result<Vec<Review>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: Vec<(ReviewModel, Option<String>)> = product_reviews::table
+            .inner_join(users::table)
+            .filter(product_reviews::status.eq(status))
+            .select((ReviewModel::as_select(), users::full_name))
+            .order(product_reviews::created_at.asc())
+            .load(&mut conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(model, reviewer)| model.into_review(reviewer))
+            .collect())
+    }
+
+    async fn moderate(
+        &self,
+        review_id: i64,
+        status: ReviewStatus,
+        moderated_by: &str,
+    ) -> Result<Review, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        diesel::update(product_reviews::table.filter(product_reviews::review_id.eq(review_id)))
+            .set((
+                product_reviews::status.eq(status),
+                product_reviews::moderated_by.eq(Some(moderated_by)),
+                product_reviews::moderated_at.eq(Some(Utc::now().naive_utc())),
+            ))
+            .returning(product_reviews::review_id)
+            .get_result::<i64>(&mut conn)
+            .map_err(write_error)?;
+
+        load_review(&mut conn, review_id)
+    }
+}
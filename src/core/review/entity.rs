@@ -0,0 +1,62 @@
+use chrono::NaiveDateTime;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, DbEnum)]
+#[ExistingTypePath = "crate::schema::sql_types::ReviewStatus"]
+pub enum ReviewStatus {
+    #[db_rename = "PENDING"]
+    Pending,
+    #[db_rename = "APPROVED"]
+    Approved,
+    #[db_rename = "HIDDEN"]
+    Hidden,
+}
+
+/// A customer's rating of a product they bought. Only approved reviews are shown and count
+/// towards the product's rating.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Review {
+    pub review_id: i64,
+    pub product_id: i64,
+    pub user_id: i64,
+    /// The reviewer's name as on their account, if they gave one
+    pub reviewer: Option<String>,
+    /// 1 to 5 stars
+    pub rating: i16,
+    pub body: Option<String>,
+    pub photo_urls: Vec<String>,
+    pub status: ReviewStatus,
+    pub moderated_by: Option<String>,
+    pub moderated_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// A review as posted, with its photos not yet uploaded
+#[derive(Debug, Clone)]
+pub struct ReviewSubmission {
+    pub rating: i16,
+    pub body: Option<String>,
+    /// File contents and original file names
+    pub photos: Vec<(Vec<u8>, String)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewPage {
+    pub product_id: i64,
+    pub reviews: Vec<Review>,
+    pub total: i64,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReviewListQuery {
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReviewModerationQuery {
+    pub status: Option<ReviewStatus>,
+}
@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+
+use crate::utils::errors::Error;
+
+use super::entity::{Review, ReviewStatus};
+
+#[async_trait]
+pub trait ReviewRepository: Send + Sync {
+    /// Approved reviews of the product, newest first
+    async fn find_by_product(
+        &self,
+        product_id: i64,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<Review>, Error>;
+    async fn count_by_product(&self, product_id: i64) -> Result<i64, Error>;
+    /// Whether the user has a completed order containing the product, on its own or as part
+    /// of a bundle
+    async fn has_purchased(&self, user_id: i64, product_id: i64) -> Result<bool, Error>;
+    /// Whether the user already has a review of the product, whatever its status
+    async fn has_reviewed(&self, user_id: i64, product_id: i64) -> Result<bool, Error>;
+    async fn create(
+        &self,
+        product_id: i64,
+        user_id: i64,
+        rating: i16,
+        body: Option<&str>,
+        photo_urls: &[String],
+    ) -> Result<Review, Error>;
+    async fn delete(&self, product_id: i64, user_id: i64) -> Result<(), Error>;
+    /// Oldest reviews first
+    async fn list(&self, status: ReviewStatus) -> Result<Vec<Review>, Error>;
+    async fn moderate(
+        &self,
+        review_id: i64,
+        status: ReviewStatus,
+        moderated_by: &str,
+    ) -> Result<Review, Error>;
+}
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use tracing::error;
+
+use crate::utils::errors::{Error, ErrorCode};
+use crate::utils::storage::StorageService;
+
+use super::entity::{Review, ReviewPage, ReviewStatus, ReviewSubmission};
+use super::repository::ReviewRepository;
+
+const PHOTO_FOLDER: &str = "reviews/photos";
+const MAX_PHOTOS: usize = 5;
+const MAX_PHOTO_SIZE: usize = 5 * 1024 * 1024;
+const MAX_BODY_LEN: usize = 2000;
+
+#[derive(Clone)]
+pub struct ReviewService {
+    repo: Arc<dyn ReviewRepository>,
+    storage: StorageService,
+}
+
+impl ReviewService {
+    pub fn new(repo: Arc<dyn ReviewRepository>, storage: StorageService) -> Self {
+        Self { repo, storage }
+    }
+
+    pub async fn list_reviews(
+        &self,
+        product_id: i64,
+        page: u32,
+        page_size: u32,
+    ) -> Result<ReviewPage, Error> {
+        if page_size == 0 || page_size > 100 {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Page size must be between 1 and 100",
+            ));
+        }
+
+        let offset = i64::from(page.saturating_sub(1) * page_size);
+        let limit = i64::from(page_size);
+
+        let reviews = self.repo.find_by_product(product_id, offset, limit).await?;
+        let total = self.repo.count_by_product(product_id).await?;
+
+        Ok(ReviewPage {
+            product_id,
+            reviews,
+            total,
+            page,
+            page_size,
+        })
+    }
+
+    /// Posts the customer's review for approval. Photos are only uploaded once the review
+    /// is known to be allowed.
+    pub async fn submit_review(
+        &self,
+        product_id: i64,
+        user_id: i64,
+        submission: ReviewSubmission,
+    ) -> Result<Review, Error> {
+        if !(1..=5).contains(&submission.rating) {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Rating must be between 1 and 5",
+            ));
+        }
+
+        let body = submission
+            .body
+            .as_deref()
+            .map(str::trim)
+            .filter(|body| !body.is_empty());
+        if body.is_some_and(|body| body.chars().count() > MAX_BODY_LEN) {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Review must be {} characters or fewer", MAX_BODY_LEN),
+            ));
+        }
+
+        validate_photos(&submission.photos)?;
+
+        if !self.repo.has_purchased(user_id, product_id).await? {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Only customers with a completed order for this product can review it",
+            ));
+        }
+        if self.repo.has_reviewed(user_id, product_id).await? {
+            return Err(Error::with_message(
+                ErrorCode::ResourceAlreadyExists,
+                "You have already reviewed this product",
+            ));
+        }
+
+        let photo_urls = if submission.photos.is_empty() {
+            Vec::new()
+        } else {
+            Box::pin(self.storage.upload_files(submission.photos, PHOTO_FOLDER))
+                .await
+                .map_err(|e| {
+                    error!(error = %e, product_id, user_id, "Failed to upload review photos");
+                    Error::with_message(ErrorCode::InternalError, "Failed to upload review photos")
+                })?
+                .into_iter()
+                .map(|(url, _)| url)
+                .collect()
+        };
+
+        self.repo
+            .create(product_id, user_id, submission.rating, body, &photo_urls)
+            .await
+    }
+
+    pub async fn delete_review(&self, product_id: i64, user_id: i64) -> Result<(), Error> {
+        self.repo.delete(product_id, user_id).await
+    }
+
+    pub async fn list_for_moderation(&self, status: ReviewStatus) -> Result<Vec<Review>, Error> {
+        self.repo.list(status).await
+    }
+
+    pub async fn approve_review(
+        &self,
+        review_id: i64,
+        moderated_by: &str,
+    ) -> Result<Review, Error> {
+        self.repo
+            .moderate(review_id, ReviewStatus::Approved, moderated_by)
+            .await
+    }
+
+    /// Takes the review off the product page and out of its rating
+    pub async fn hide_review(&self, review_id: i64, moderated_by: &str) -> Result<Review, Error> {
+        self.repo
+            .moderate(review_id, ReviewStatus::Hidden, moderated_by)
+            .await
+    }
+}
+
+fn validate_photos(photos: &[(Vec<u8>, String)]) -> Result<(), Error> {
+    if photos.len() > MAX_PHOTOS {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("A review can have at most {} photos", MAX_PHOTOS),
+        ));
+    }
+
+    for (data, filename) in photos {
+        if data.is_empty() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Photo {} is empty", filename),
+            ));
+        }
+        if data.len() > MAX_PHOTO_SIZE {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Photo {} must be 5MB or smaller", filename),
+            ));
+        }
+        let mime = mime_guess::from_path(filename).first_or_octet_stream();
+        if mime.type_() != mime_guess::mime::IMAGE {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("Photo {} must be an image", filename),
+            ));
+        }
+    }
+    Ok(())
+}
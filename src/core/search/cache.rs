@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use super::entity::{ProductSuggestion, SuggestionSource, Suggestions};
+
+// Same cut-off pg_trgm uses for its `%` operator
+const SIMILARITY_THRESHOLD: f64 = 0.3;
+
+struct Entry<T> {
+    key: String,
+    trigrams: HashSet<String>,
+    value: T,
+}
+
+impl<T> Entry<T> {
+    fn new(text: &str, value: T) -> Self {
+        let key = text.to_lowercase();
+        Self {
+            trigrams: trigrams(&key),
+            key,
+            value,
+        }
+    }
+
+    // Lower is better: whole-text prefix, then word prefix, then substring, then typo match
+    fn rank(&self, query: &str, query_trigrams: &HashSet<String>) -> Option<(u8, f64)> {
+        if self.key.starts_with(query) {
+            return Some((0, 0.0));
+        }
+        if self
+            .key
+            .split_whitespace()
+            .any(|word| word.starts_with(query))
+        {
+            return Some((1, 0.0));
+        }
+        if self.key.contains(query) {
+            return Some((2, 0.0));
+        }
+        if query_trigrams.is_empty() {
+            return None;
+        }
+        let shared = self.trigrams.intersection(query_trigrams).count();
+        let total = self.trigrams.union(query_trigrams).count();
+        let similarity = as_f64(shared) / as_f64(total.max(1));
+        (similarity >= SIMILARITY_THRESHOLD).then_some((3, -similarity))
+    }
+}
+
+#[derive(Default)]
+struct SuggestionIndex {
+    products: Vec<Entry<ProductSuggestion>>,
+    categories: Vec<Entry<String>>,
+    queries: Vec<Entry<String>>,
+}
+
+/// In-process index of suggestion candidates so per-keystroke lookups never touch the
+/// database. A refresh swaps the whole index; until the first one it suggests nothing.
+#[derive(Clone, Default)]
+pub struct SuggestionCache {
+    index: Arc<RwLock<SuggestionIndex>>,
+}
+
+impl SuggestionCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn replace(&self, source: SuggestionSource) {
+        let index = SuggestionIndex {
+            products: source
+                .products
+                .into_iter()
+                .map(|product| {
+                    let name = product.name.clone();
+                    Entry::new(&name, product)
+                })
+                .collect(),
+            categories: source
+                .categories
+                .into_iter()
+                .map(|(category, _)| Entry::new(&category.clone(), category))
+                .collect(),
+            queries: source
+                .queries
+                .into_iter()
+                .map(|(query, _)| Entry::new(&query.clone(), query))
+                .collect(),
+        };
+        *self.index.write().unwrap_or_else(PoisonError::into_inner) = index;
+    }
+
+    /// Looks up `query`, which must already be lowercased and trimmed.
+    pub fn suggest(&self, query: &str, limit: usize) -> Suggestions {
+        // Single characters share too few trigrams for a typo match to mean anything
+        let query_trigrams = if query.chars().count() >= 3 {
+            trigrams(query)
+        } else {
+            HashSet::new()
+        };
+
+        let index = self.index.read().unwrap_or_else(PoisonError::into_inner);
+        Suggestions {
+            products: best(&index.products, query, &query_trigrams, limit),
+            categories: best(&index.categories, query, &query_trigrams, limit),
+            queries: best(&index.queries, query, &query_trigrams, limit),
+        }
+    }
+}
+
+// Entries are kept in popularity order, so the stable sort breaks ties by popularity
+fn best<T: Clone>(
+    entries: &[Entry<T>],
+    query: &str,
+    query_trigrams: &HashSet<String>,
+    limit: usize,
+) -> Vec<T> {
+    let mut matches: Vec<((u8, f64), &T)> = entries
+        .iter()
+        .filter_map(|entry| {
+            entry
+                .rank(query, query_trigrams)
+                .map(|rank| (rank, &entry.value))
+        })
+        .collect();
+    matches.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, value)| value.clone())
+        .collect()
+}
+
+// Trigrams the way pg_trgm builds them: each word padded with two leading spaces and one
+// trailing space
+fn trigrams(text: &str) -> HashSet<String> {
+    let mut set = HashSet::new();
+    for word in text.split_whitespace() {
+        let padded: Vec<char> = format!("  {} ", word).chars().collect();
+        for window in padded.windows(3) {
+            set.insert(window.iter().collect());
+        }
+    }
+    set
+}
+
+fn as_f64(count: usize) -> f64 {
+    f64::from(u32::try_from(count).unwrap_or(u32::MAX))
+}
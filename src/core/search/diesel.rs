@@ -0,0 +1,133 @@
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use diesel::dsl::count_star;
+use diesel::prelude::*;
+
+use crate::core::product::diesel::on_sale;
+use crate::schema::{products, search_queries};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{ProductSuggestion, SuggestionSource, ZeroResultQuery};
+use super::repository::SearchRepository;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = search_queries)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+struct ZeroResultQueryModel {
+    pub query: String,
+    pub search_count: i32,
+    pub zero_result_count: i32,
+    pub last_searched_at: NaiveDateTime,
+}
+
+impl From<ZeroResultQueryModel> for ZeroResultQuery {
+    fn from(m: ZeroResultQueryModel) -> Self {
+        ZeroResultQuery {
+            query: m.query,
+            search_count: m.search_count,
+            zero_result_count: m.zero_result_count,
+            last_searched_at: m.last_searched_at,
+        }
+    }
+}
+
+pub struct DieselSearchRepository {
+    pool: DBPool,
+}
+
+impl DieselSearchRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SearchRepository for DieselSearchRepository {
+    async fn record(&self, query: &str, result_count: i64) -> Result<(), Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let zero_result = i32::from(result_count == 0);
+        let now = Utc::now().naive_utc();
+        diesel::insert_into(search_queries::table)
+            .values((
+                search_queries::query.eq(query),
+                search_queries::search_count.eq(1),
+                search_queries::zero_result_count.eq(zero_result),
+                search_queries::last_result_count.eq(result_count),
+                search_queries::last_searched_at.eq(now),
+            ))
+            .on_conflict(search_queries::query)
+            .do_update()
+            .set((
+                search_queries::search_count.eq(search_queries::search_count + 1),
+                search_queries::zero_result_count
+                    .eq(search_queries::zero_result_count + zero_result),
+                search_queries::last_result_count.eq(result_count),
+                search_queries::last_searched_at.eq(now),
+            ))
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn load_suggestion_source(&self, max_queries: i64) -> Result<SuggestionSource, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let products: Vec<(i64, String)> = products::table
+            .filter(on_sale())
+            .select((products::id, products::name))
+            .order(products::id.desc())
+            .load(&mut conn)?;
+
+        let categories: Vec<(Option<String>, i64)> = products::table
+            .filter(products::category.is_not_null())
+            .filter(on_sale())
+            .group_by(products::category)
+            .select((products::category, count_star()))
+            .order(count_star().desc())
+            .load(&mut conn)?;
+
+        // Queries that came back empty would only suggest more dead ends
+        let queries: Vec<(String, i32)> = search_queries::table
+            .filter(search_queries::last_result_count.gt(0))
+            .select((search_queries::query, search_queries::search_count))
+            .order(search_queries::search_count.desc())
+            .limit(max_queries)
+            .load(&mut conn)?;
+
+        Ok(SuggestionSource {
+            products: products
+                .into_iter()
+                .map(|(product_id, name)| ProductSuggestion { product_id, name })
+                .collect(),
+            categories: categories
+                .into_iter()
+                .filter_map(|(category, count)| category.map(|category| (category, count)))
+                .collect(),
+            queries,
+        })
+    }
+
+    async fn find_zero_result(&self, limit: i64) -> Result<Vec<ZeroResultQuery>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let rows: Vec<ZeroResultQueryModel> = search_queries::table
+            .filter(search_queries::zero_result_count.gt(0))
+            .select(ZeroResultQueryModel::as_select())
+            .order((
+                search_queries::zero_result_count.desc(),
+                search_queries::last_searched_at.desc(),
+            ))
+            .limit(limit)
+            .load(&mut conn)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
@@ -0,0 +1,44 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Everything suggestions are drawn from, loaded in one go when the cache refreshes.
+#[derive(Debug, Clone, Default)]
+pub struct SuggestionSource {
+    pub products: Vec<ProductSuggestion>,
+    /// Categories with their product count, most products first
+    pub categories: Vec<(String, i64)>,
+    /// Past queries that found something, most searched first
+    pub queries: Vec<(String, i32)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductSuggestion {
+    pub product_id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Suggestions {
+    pub products: Vec<ProductSuggestion>,
+    pub categories: Vec<String>,
+    pub queries: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZeroResultQuery {
+    pub query: String,
+    pub search_count: i32,
+    pub zero_result_count: i32,
+    pub last_searched_at: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestQuery {
+    pub q: String,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ZeroResultReportQuery {
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,5 @@
+pub mod cache;
+pub mod diesel;
+pub mod entity;
+pub mod repository;
+pub mod service;
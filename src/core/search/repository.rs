@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+
+use crate::utils::errors::Error;
+
+use super::entity::{SuggestionSource, ZeroResultQuery};
+
+#[async_trait]
+pub trait SearchRepository: Send + Sync {
+    /// Counts one search for the (already normalised) query and how many products it found.
+    async fn record(&self, query: &str, result_count: i64) -> Result<(), Error>;
+    async fn load_suggestion_source(&self, max_queries: i64) -> Result<SuggestionSource, Error>;
+    async fn find_zero_result(&self, limit: i64) -> Result<Vec<ZeroResultQuery>, Error>;
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use tracing::error;
+
+use crate::utils::errors::Error;
+
+use super::cache::SuggestionCache;
+use super::entity::{Suggestions, ZeroResultQuery};
+use super::repository::SearchRepository;
+
+const MAX_QUERY_LEN: usize = 100;
+const MAX_CACHED_QUERIES: i64 = 1000;
+
+#[derive(Clone)]
+pub struct SearchService {
+    repo: Arc<dyn SearchRepository>,
+    cache: SuggestionCache,
+}
+
+impl SearchService {
+    pub fn new(repo: Arc<dyn SearchRepository>, cache: SuggestionCache) -> Self {
+        Self { repo, cache }
+    }
+
+    /// Product names, categories and past queries matching what has been typed so far.
+    /// Served from the in-process cache only.
+    pub fn suggest(&self, query: &str, limit: usize) -> Suggestions {
+        let query = normalize_query(query);
+        if query.is_empty() || query.chars().count() > MAX_QUERY_LEN {
+            return Suggestions::default();
+        }
+        self.cache.suggest(&query, limit)
+    }
+
+    /// Counts a search towards popular suggestions and the zero-result report. Failures are
+    /// logged rather than returned so they never fail the search itself.
+    pub async fn record_search(&self, query: &str, result_count: i64) {
+        let query = normalize_query(query);
+        if query.is_empty() || query.chars().count() > MAX_QUERY_LEN {
+            return;
+        }
+        if let Err(e) = self.repo.record(&query, result_count).await {
+            error!(error = %e, query, "Failed to record search query");
+        }
+    }
+
+    /// Reloads the suggestion cache from the database.
+    pub async fn refresh_suggestions(&self) -> Result<(), Error> {
+        let source = self.repo.load_suggestion_source(MAX_CACHED_QUERIES).await?;
+        self.cache.replace(source);
+        Ok(())
+    }
+
+    pub async fn zero_result_report(&self, limit: i64) -> Result<Vec<ZeroResultQuery>, Error> {
+        self.repo.find_zero_result(limit).await
+    }
+}
+
+// Searches differing only in case or spacing count as the same query
+fn normalize_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
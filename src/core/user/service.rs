@@ -115,7 +115,7 @@ impl Service {
             ));
         }
 
-        let claims = Claims::new(user.email.clone(), user.role.clone());
+        let claims = Claims::new(user.id, user.email.clone(), user.role.clone());
         let token = claims.jwt().map_err(|_| {
             Error::with_message(ErrorCode::InternalError, "Failed to generate JWT token")
         })?;
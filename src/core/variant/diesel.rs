@@ -0,0 +1,309 @@
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use diesel::prelude::*;
+use diesel::result::DatabaseErrorKind;
+use tracing::error;
+
+use crate::core::inventory::diesel::apply_movement;
+use crate::core::inventory::entity::{InventoryReason, NewInventoryMovement};
+use crate::core::product::diesel::VariantModel;
+use crate::core::product::entity::Variant;
+use crate::schema::{
+    bundle_components, cart_items, order_item_components, order_items, products, variants,
+};
+use crate::utils::db::DBPool;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{NewVariant, UpdateVariant, VariantOption};
+use super::repository::VariantRepository;
+
+#[derive(Insertable)]
+#[diesel(table_name = variants)]
+struct NewVariantModel {
+    pub product_id: i64,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub sku: Option<String>,
+    pub price_override: Option<BigDecimal>,
+    pub member_price_override: Option<BigDecimal>,
+    pub stock_quantity: i32,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = variants)]
+#[diesel(treat_none_as_null = true)]
+struct UpdateVariantModel {
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub sku: Option<String>,
+    pub price_override: Option<BigDecimal>,
+    pub member_price_override: Option<BigDecimal>,
+}
+
+impl From<UpdateVariant> for UpdateVariantModel {
+    fn from(update: UpdateVariant) -> Self {
+        UpdateVariantModel {
+            size: update.size,
+            color: update.color,
+            sku: update.sku,
+            price_override: update.price_override,
+            member_price_override: update.member_price_override,
+        }
+    }
+}
+
+pub(crate) fn write_error(e: diesel::result::Error) -> Error {
+    match e {
+        diesel::result::Error::NotFound => {
+            Error::with_message(ErrorCode::ResourceNotFound, "Variant not found")
+        }
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, ref info) => {
+            let message = if info.constraint_name() == Some("variants_sku_key") {
+                "SKU is already in use"
+            } else {
+                "A variant with this size and color already exists"
+            };
+            Error::with_message(ErrorCode::ResourceAlreadyExists, message)
+        }
+        diesel::result::Error::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+            Error::with_message(
+                ErrorCode::ResourceAlreadyExists,
+                "Variant has been ordered and cannot be deleted",
+            )
+        }
+        _ => e.into(),
+    }
+}
+
+// Locks the product so concurrent variant writes for it run one at a time
+fn lock_product(conn: &mut PgConnection, product_id: i64) -> Result<(), Error> {
+    products::table
+        .filter(products::id.eq(product_id))
+        .select(products::id)
+        .for_update()
+        .first::<i64>(conn)
+        .optional()?
+        .ok_or_else(|| Error::with_message(ErrorCode::ResourceNotFound, "Product not found"))?;
+
+    Ok(())
+}
+
+pub struct DieselVariantRepository {
+    pool: DBPool,
+}
+
+impl DieselVariantRepository {
+    pub fn new(pool: DBPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl VariantRepository for DieselVariantRepository {
+    async fn find_by_product(&self, product_id: i64) -> Result<Vec<Variant>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let product_exists: bool = diesel::select(diesel::dsl::exists(
+            products::table.filter(products::id.eq(product_id)),
+        ))
+        .get_result(&mut conn)?;
+        if !product_exists {
+            return Err(Error::with_message(
+                ErrorCode::ResourceNotFound,
+                "Product not found",
+            ));
+        }
+
+        let rows: Vec<VariantModel> = variants::table
+            .filter(variants::product_id.eq(product_id))
+            .select(VariantModel::as_select())
+            .order(variants::variant_id.asc())
+            .load(&mut conn)?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn create(
+        &self,
+        product_id: i64,
+        new_variant: NewVariant,
+        created_by: &str,
+    ) -> Result<Variant, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction::<_, Error, _>(|conn| {
+            lock_product(conn, product_id)?;
+
+            let mut created: VariantModel = diesel::insert_into(variants::table)
+                .values(&NewVariantModel {
+                    product_id,
+                    size: new_variant.size,
+                    color: new_variant.color,
+                    sku: new_variant.sku,
+                    price_override: new_variant.price_override,
+                    member_price_override: new_variant.member_price_override,
+                    stock_quantity: 0,
+                })
+                .returning(VariantModel::as_returning())
+                .get_result(conn)
+                .map_err(write_error)?;
+
+            // Opening stock goes through the ledger so reconciliation stays balanced
+            let opening = new_variant.stock_quantity.unwrap_or(0);
+            if opening > 0 {
+                let (_, balance) = apply_movement(
+                    conn,
+                    NewInventoryMovement {
+                        variant_id: created.variant_id,
+                        quantity_change: opening,
+                        reason: InventoryReason::Restock,
+                        order_id: None,
+                        note: Some("Opening stock".to_string()),
+                        created_by: Some(created_by.to_string()),
+                    },
+                )?;
+                created.stock_quantity = balance;
+            }
+
+            Ok(created.into())
+        })
+        .map_err(|e| {
+            error!(error = %e, product_id, "Failed to create variant");
+            e
+        })
+    }
+
+    async fn update(
+        &self,
+        product_id: i64,
+        variant_id: i64,
+        update: UpdateVariant,
+    ) -> Result<Variant, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        let changes: UpdateVariantModel = update.into();
+        let updated: VariantModel = diesel::update(
+            variants::table
+                .filter(variants::variant_id.eq(variant_id))
+                .filter(variants::product_id.eq(product_id)),
+        )
+        .set(&changes)
+        .returning(VariantModel::as_returning())
+        .get_result(&mut conn)
+        .map_err(write_error)?;
+
+        Ok(updated.into())
+    }
+
+    async fn delete(&self, product_id: i64, variant_id: i64) -> Result<(), Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction::<_, Error, _>(|conn| {
+            let variant: Option<i64> = variants::table
+                .filter(variants::variant_id.eq(variant_id))
+                .filter(variants::product_id.eq(product_id))
+                .select(variants::variant_id)
+                .for_update()
+                .first(conn)
+                .optional()?;
+            if variant.is_none() {
+                return Err(Error::with_message(
+                    ErrorCode::ResourceNotFound,
+                    "Variant not found",
+                ));
+            }
+
+            // Order lines keep pointing at the variant they sold, so it must stay
+            let ordered: i64 = order_items::table
+                .filter(order_items::variant_id.eq(variant_id))
+                .count()
+                .get_result(conn)?;
+            let sold_in_bundles: i64 = order_item_components::table
+                .filter(order_item_components::variant_id.eq(variant_id))
+                .count()
+                .get_result(conn)?;
+            if ordered + sold_in_bundles > 0 {
+                return Err(Error::with_message(
+                    ErrorCode::ResourceAlreadyExists,
+                    "Variant has been ordered and cannot be deleted",
+                ));
+            }
+
+            let in_bundles: i64 = bundle_components::table
+                .filter(bundle_components::variant_id.eq(variant_id))
+                .count()
+                .get_result(conn)?;
+            if in_bundles > 0 {
+                return Err(Error::with_message(
+                    ErrorCode::ResourceAlreadyExists,
+                    "Variant is part of a bundle and cannot be deleted",
+                ));
+            }
+
+            diesel::delete(cart_items::table.filter(cart_items::variant_id.eq(variant_id)))
+                .execute(conn)?;
+            diesel::delete(variants::table.filter(variants::variant_id.eq(variant_id)))
+                .execute(conn)
+                .map_err(write_error)?;
+
+            Ok(())
+        })
+    }
+
+    async fn create_missing(
+        &self,
+        product_id: i64,
+        options: Vec<VariantOption>,
+        price_override: Option<BigDecimal>,
+    ) -> Result<Vec<Variant>, Error> {
+        let mut conn = self.pool.get().map_err(|e| {
+            Error::with_message(ErrorCode::DatabaseError, format!("Connection error: {}", e))
+        })?;
+
+        conn.transaction::<_, Error, _>(|conn| {
+            lock_product(conn, product_id)?;
+
+            let existing: Vec<(Option<String>, Option<String>)> = variants::table
+                .filter(variants::product_id.eq(product_id))
+                .select((variants::size, variants::color))
+                .load(conn)?;
+
+            let new_rows: Vec<NewVariantModel> = options
+                .into_iter()
+                .filter(|option| {
+                    !existing
+                        .iter()
+                        .any(|(size, color)| *size == option.size && *color == option.color)
+                })
+                .map(|option| NewVariantModel {
+                    product_id,
+                    size: option.size,
+                    color: option.color,
+                    sku: option.sku,
+                    price_override: price_override.clone(),
+                    member_price_override: None,
+                    stock_quantity: 0,
+                })
+                .collect();
+            if new_rows.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let created: Vec<VariantModel> = diesel::insert_into(variants::table)
+                .values(&new_rows)
+                .returning(VariantModel::as_returning())
+                .get_results(conn)
+                .map_err(write_error)?;
+
+            Ok(created.into_iter().map(Into::into).collect())
+        })
+    }
+}
@@ -0,0 +1,52 @@
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+
+use crate::core::product::entity::Variant;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewVariant {
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub sku: Option<String>,
+    pub price_override: Option<BigDecimal>,
+    /// Replaces the product's member price for this variant
+    pub member_price_override: Option<BigDecimal>,
+    /// Opening stock, recorded as a restock movement
+    pub stock_quantity: Option<i32>,
+}
+
+// Replaces the variant's attributes; a field left out or null is cleared. Stock is changed
+// through inventory adjustments, not here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateVariant {
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub sku: Option<String>,
+    pub price_override: Option<BigDecimal>,
+    pub member_price_override: Option<BigDecimal>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GenerateMatrixRequest {
+    #[serde(default)]
+    pub sizes: Vec<String>,
+    #[serde(default)]
+    pub colors: Vec<String>,
+    /// Generated SKUs take the form `{prefix}-{size}-{color}`
+    pub sku_prefix: Option<String>,
+    pub price_override: Option<BigDecimal>,
+}
+
+#[derive(Debug, Clone)]
+pub struct VariantOption {
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub sku: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GeneratedMatrix {
+    pub created: Vec<Variant>,
+    /// Combinations that already existed and were left untouched
+    pub skipped: usize,
+}
@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+
+use crate::core::product::entity::Variant;
+use crate::utils::errors::Error;
+
+use super::entity::{NewVariant, UpdateVariant, VariantOption};
+
+#[async_trait]
+pub trait VariantRepository: Send + Sync {
+    async fn find_by_product(&self, product_id: i64) -> Result<Vec<Variant>, Error>;
+    async fn create(
+        &self,
+        product_id: i64,
+        new_variant: NewVariant,
+        created_by: &str,
+    ) -> Result<Variant, Error>;
+    async fn update(
+        &self,
+        product_id: i64,
+        variant_id: i64,
+        update: UpdateVariant,
+    ) -> Result<Variant, Error>;
+    async fn delete(&self, product_id: i64, variant_id: i64) -> Result<(), Error>;
+    /// Inserts the combinations that don't exist yet and returns the ones it created.
+    async fn create_missing(
+        &self,
+        product_id: i64,
+        options: Vec<VariantOption>,
+        price_override: Option<BigDecimal>,
+    ) -> Result<Vec<Variant>, Error>;
+}
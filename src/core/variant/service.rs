@@ -0,0 +1,214 @@
+use std::sync::Arc;
+
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::core::product::entity::Variant;
+use crate::utils::errors::{Error, ErrorCode};
+
+use super::entity::{
+    GenerateMatrixRequest, GeneratedMatrix, NewVariant, UpdateVariant, VariantOption,
+};
+use super::repository::VariantRepository;
+
+pub(crate) const MAX_SIZE_LEN: usize = 20;
+pub(crate) const MAX_COLOR_LEN: usize = 50;
+pub(crate) const MAX_SKU_LEN: usize = 64;
+const MAX_MATRIX_SIZE: usize = 100;
+
+#[derive(Clone)]
+pub struct VariantService {
+    repo: Arc<dyn VariantRepository>,
+}
+
+impl VariantService {
+    pub fn new(repo: Arc<dyn VariantRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list_variants(&self, product_id: i64) -> Result<Vec<Variant>, Error> {
+        self.repo.find_by_product(product_id).await
+    }
+
+    pub async fn create_variant(
+        &self,
+        product_id: i64,
+        mut new_variant: NewVariant,
+        created_by: &str,
+    ) -> Result<Variant, Error> {
+        new_variant.size = option_value(new_variant.size, "Size", MAX_SIZE_LEN)?;
+        new_variant.color = option_value(new_variant.color, "Color", MAX_COLOR_LEN)?;
+        new_variant.sku = option_value(new_variant.sku, "SKU", MAX_SKU_LEN)?;
+        validate_price_override(new_variant.price_override.as_ref())?;
+        validate_member_price_override(new_variant.member_price_override.as_ref())?;
+
+        if new_variant.stock_quantity.is_some_and(|stock| stock < 0) {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "Opening stock cannot be negative",
+            ));
+        }
+
+        self.repo.create(product_id, new_variant, created_by).await
+    }
+
+    pub async fn update_variant(
+        &self,
+        product_id: i64,
+        variant_id: i64,
+        mut update: UpdateVariant,
+    ) -> Result<Variant, Error> {
+        update.size = option_value(update.size, "Size", MAX_SIZE_LEN)?;
+        update.color = option_value(update.color, "Color", MAX_COLOR_LEN)?;
+        update.sku = option_value(update.sku, "SKU", MAX_SKU_LEN)?;
+        validate_price_override(update.price_override.as_ref())?;
+        validate_member_price_override(update.member_price_override.as_ref())?;
+
+        self.repo.update(product_id, variant_id, update).await
+    }
+
+    pub async fn delete_variant(&self, product_id: i64, variant_id: i64) -> Result<(), Error> {
+        self.repo.delete(product_id, variant_id).await
+    }
+
+    /// Creates a variant for every size × color combination the product doesn't have yet.
+    /// Either list may be empty to generate along a single dimension.
+    pub async fn generate_matrix(
+        &self,
+        product_id: i64,
+        req: GenerateMatrixRequest,
+    ) -> Result<GeneratedMatrix, Error> {
+        let sizes = distinct_values(req.sizes, "Size", MAX_SIZE_LEN)?;
+        let colors = distinct_values(req.colors, "Color", MAX_COLOR_LEN)?;
+        if sizes.is_empty() && colors.is_empty() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "At least one size or color is required",
+            ));
+        }
+        validate_price_override(req.price_override.as_ref())?;
+
+        let sizes: Vec<Option<String>> = widen(sizes);
+        let colors: Vec<Option<String>> = widen(colors);
+        if sizes.len() * colors.len() > MAX_MATRIX_SIZE {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                format!("A matrix can create at most {} variants", MAX_MATRIX_SIZE),
+            ));
+        }
+
+        let prefix = option_value(req.sku_prefix, "SKU prefix", MAX_SKU_LEN)?;
+        let mut options = Vec::with_capacity(sizes.len() * colors.len());
+        for size in &sizes {
+            for color in &colors {
+                let sku = match &prefix {
+                    Some(prefix) => Some(matrix_sku(prefix, size.as_deref(), color.as_deref())?),
+                    None => None,
+                };
+                options.push(VariantOption {
+                    size: size.clone(),
+                    color: color.clone(),
+                    sku,
+                });
+            }
+        }
+
+        let requested = options.len();
+        let created = self
+            .repo
+            .create_missing(product_id, options, req.price_override)
+            .await?;
+
+        Ok(GeneratedMatrix {
+            skipped: requested - created.len(),
+            created,
+        })
+    }
+}
+
+// Trims the value, treating blank as unset, and enforces the column length
+pub(crate) fn option_value(
+    value: Option<String>,
+    field: &str,
+    max_len: usize,
+) -> Result<Option<String>, Error> {
+    let value = value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    if value
+        .as_ref()
+        .is_some_and(|value| value.chars().count() > max_len)
+    {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!("{} must be {} characters or fewer", field, max_len),
+        ));
+    }
+
+    Ok(value)
+}
+
+fn distinct_values(values: Vec<String>, field: &str, max_len: usize) -> Result<Vec<String>, Error> {
+    let mut distinct: Vec<String> = Vec::with_capacity(values.len());
+    for value in values {
+        if let Some(value) = option_value(Some(value), field, max_len)?
+            && !distinct.contains(&value)
+        {
+            distinct.push(value);
+        }
+    }
+    Ok(distinct)
+}
+
+// An empty dimension contributes a single unset value so the product still has rows
+fn widen(values: Vec<String>) -> Vec<Option<String>> {
+    if values.is_empty() {
+        vec![None]
+    } else {
+        values.into_iter().map(Some).collect()
+    }
+}
+
+fn matrix_sku(prefix: &str, size: Option<&str>, color: Option<&str>) -> Result<String, Error> {
+    let sku = [Some(prefix), size, color]
+        .into_iter()
+        .flatten()
+        .map(|part| part.split_whitespace().collect::<Vec<_>>().join("-"))
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_uppercase();
+
+    if sku.chars().count() > MAX_SKU_LEN {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            format!(
+                "Generated SKU '{}' is longer than {} characters",
+                sku, MAX_SKU_LEN
+            ),
+        ));
+    }
+
+    Ok(sku)
+}
+
+pub(crate) fn validate_price_override(price_override: Option<&BigDecimal>) -> Result<(), Error> {
+    if price_override.is_some_and(|price| *price <= BigDecimal::zero()) {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Price override must be greater than 0",
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_member_price_override(
+    member_price: Option<&BigDecimal>,
+) -> Result<(), Error> {
+    if member_price.is_some_and(|price| *price <= BigDecimal::zero()) {
+        return Err(Error::with_message(
+            ErrorCode::ValidationError,
+            "Member price override must be greater than 0",
+        ));
+    }
+    Ok(())
+}
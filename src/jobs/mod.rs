@@ -0,0 +1,3 @@
+pub mod order_expiry;
+pub mod product_schedule;
+pub mod suggestion_refresh;
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info};
+
+use crate::config::AppConfig;
+use crate::core::notification::{
+    diesel::DieselNotificationRepository, service::NotificationService,
+};
+use crate::core::order::{diesel::DieselOrderRepository, service::OrderService};
+use crate::utils::db::DBPool;
+
+// Periodically cancels unpaid orders past their payment deadline and restores their stock
+pub fn spawn(pool: DBPool, cfg: &AppConfig) -> JoinHandle<()> {
+    let notifications =
+        NotificationService::new(Arc::new(DieselNotificationRepository::new(pool.clone())));
+    let service = OrderService::new(
+        Arc::new(DieselOrderRepository::new(pool)),
+        notifications,
+        chrono::Duration::minutes(cfg.order_payment_timeout_minutes),
+        cfg.promptpay_id.clone(),
+        cfg.shipping_fee.clone(),
+    );
+    let period = Duration::from_secs(cfg.order_expiry_interval_secs.max(1));
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+            match service.expire_overdue_orders().await {
+                Ok(0) => {}
+                Ok(count) => info!(count, "Expired unpaid orders"),
+                Err(e) => error!(error = %e, "Order expiry job failed"),
+            }
+        }
+    })
+}
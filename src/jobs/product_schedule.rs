@@ -0,0 +1,31 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info};
+
+use crate::config::AppConfig;
+use crate::core::product::{DieselProductRepository, ProductService};
+use crate::utils::db::DBPool;
+
+// Records scheduled product launches and take-downs once they are due. The storefront already
+// honours the timestamps, so this only keeps the stored visibility up to date.
+pub fn spawn(pool: DBPool, cfg: &AppConfig) -> JoinHandle<()> {
+    let service = ProductService::new(Arc::new(DieselProductRepository::new(pool)));
+    let period = Duration::from_secs(cfg.product_schedule_interval_secs.max(1));
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+            match service.apply_schedules().await {
+                Ok(0) => {}
+                Ok(count) => info!(count, "Applied product schedules"),
+                Err(e) => error!(error = %e, "Product schedule job failed"),
+            }
+        }
+    })
+}
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::error;
+
+use crate::config::AppConfig;
+use crate::core::search::{
+    cache::SuggestionCache, diesel::DieselSearchRepository, service::SearchService,
+};
+use crate::utils::db::DBPool;
+
+// Rebuilds the search suggestion cache, starting immediately so it is warm soon after boot
+pub fn spawn(pool: DBPool, cache: SuggestionCache, cfg: &AppConfig) -> JoinHandle<()> {
+    let service = SearchService::new(Arc::new(DieselSearchRepository::new(pool)), cache);
+    let period = Duration::from_secs(cfg.suggestion_refresh_secs.max(1));
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+            if let Err(e) = service.refresh_suggestions().await {
+                error!(error = %e, "Suggestion cache refresh failed");
+            }
+        }
+    })
+}
@@ -1,11 +1,13 @@
 use axum::{Router, routing::get};
 use dotenvy::dotenv;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tracing::{error, info};
 
 mod api;
 mod config;
 mod core;
+mod jobs;
 mod schema;
 mod utils;
 
@@ -31,13 +33,54 @@ async fn main() -> anyhow::Result<()> {
         return Err(e);
     }
 
-    let storage_service = StorageService::new(cfg.gcs_bucket_name).await?;
+    // Products created before slugs existed get theirs here, since Thai names are
+    // transliterated in Rust
+    let products = core::product::ProductService::new(Arc::new(
+        core::product::DieselProductRepository::new(pool.clone()),
+    ));
+    match products.fill_missing_slugs().await {
+        Ok(0) => {}
+        Ok(count) => info!(count, "Generated missing product slugs"),
+        Err(e) => error!(error = %e, "Failed to generate product slugs"),
+    }
+
+    let categories = core::category::service::CategoryService::new(Arc::new(
+        core::category::diesel::DieselCategoryRepository::new(pool.clone()),
+    ));
+    match categories.romanise_slugs().await {
+        Ok(0) => {}
+        Ok(count) => info!(count, "Romanised category slugs"),
+        Err(e) => error!(error = %e, "Failed to romanise category slugs"),
+    }
+
+    let storage_service = StorageService::new(
+        cfg.gcs_bucket_name.clone(),
+        cfg.gcs_private_bucket_name.clone(),
+    )
+    .await?;
     info!("Connected to Google Cloud Storage");
 
-    let app: Router =
-        api::router(&pool, storage_service).route("/", get(|| async { "intania-shop-api" }));
+    let payment_provider = core::payment::provider::from_config(&cfg)?;
+    if let Some(provider) = &payment_provider {
+        info!(provider = provider.name(), "Online payments enabled");
+    }
+
+    jobs::order_expiry::spawn(pool.clone(), &cfg);
+    jobs::product_schedule::spawn(pool.clone(), &cfg);
+
+    let suggestions = core::search::cache::SuggestionCache::new();
+    jobs::suggestion_refresh::spawn(pool.clone(), suggestions.clone(), &cfg);
 
     let addr: SocketAddr = cfg.server_addr.parse()?;
+    let app: Router = api::router(
+        &pool,
+        storage_service,
+        Arc::new(cfg),
+        payment_provider,
+        suggestions,
+    )
+    .route("/", get(|| async { "intania-shop-api" }));
+
     let listener = tokio::net::TcpListener::bind(addr).await?;
     axum::serve(listener, app).await?;
 
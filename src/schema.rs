@@ -1,27 +1,82 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
-    #[derive(diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "affiliation_status"))]
+    pub struct AffiliationStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "delivery_type"))]
     pub struct DeliveryType;
 
-    #[derive(diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "document_type"))]
+    pub struct DocumentType;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "inventory_reason"))]
+    pub struct InventoryReason;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "notification_type"))]
+    pub struct NotificationType;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "order_status"))]
     pub struct OrderStatus;
 
-    #[derive(diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "payment_status"))]
     pub struct PaymentStatus;
 
-    #[derive(diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "preorder_payment_mode"))]
+    pub struct PreorderPaymentMode;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "price_tier"))]
+    pub struct PriceTier;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "product_kind"))]
+    pub struct ProductKind;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "product_status"))]
     pub struct ProductStatus;
 
-    #[derive(diesel::sql_types::SqlType)]
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "product_visibility"))]
+    pub struct ProductVisibility;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "promotion_kind"))]
+    pub struct PromotionKind;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "refund_status"))]
+    pub struct RefundStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "review_status"))]
+    pub struct ReviewStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "user_role"))]
     pub struct UserRole;
 }
 
+diesel::table! {
+    bundle_components (component_id) {
+        component_id -> Int8,
+        bundle_id -> Int8,
+        product_id -> Int8,
+        variant_id -> Nullable<Int8>,
+        quantity -> Int4,
+        position -> Int4,
+    }
+}
+
 diesel::table! {
     cart (cart_id) {
         cart_id -> Int8,
@@ -39,6 +94,58 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    cart_item_choices (item_id, component_id) {
+        item_id -> Int8,
+        component_id -> Int8,
+        variant_id -> Int8,
+    }
+}
+
+diesel::table! {
+    cart_promotions (cart_id, promotion_id) {
+        cart_id -> Int8,
+        promotion_id -> Int8,
+        added_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    categories (category_id) {
+        category_id -> Int8,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 150]
+        slug -> Varchar,
+        parent_id -> Nullable<Int8>,
+        display_order -> Int4,
+        #[max_length = 255]
+        image_url -> Nullable<Varchar>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    category_slug_redirects (slug) {
+        #[max_length = 150]
+        slug -> Varchar,
+        category_id -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DocumentType;
+
+    document_counters (document_type, year) {
+        document_type -> DocumentType,
+        year -> Int4,
+        last_number -> Int4,
+    }
+}
+
 diesel::table! {
     favorites (user_id, product_id) {
         user_id -> Int8,
@@ -48,12 +155,69 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::InventoryReason;
+
+    inventory_movements (movement_id) {
+        movement_id -> Int8,
+        variant_id -> Int8,
+        quantity_change -> Int4,
+        reason -> InventoryReason,
+        order_id -> Nullable<Int8>,
+        note -> Nullable<Text>,
+        #[max_length = 100]
+        created_by -> Nullable<Varchar>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::NotificationType;
+
+    notifications (notification_id) {
+        notification_id -> Int8,
+        user_id -> Int8,
+        order_id -> Nullable<Int8>,
+        notification_type -> NotificationType,
+        message -> Text,
+        is_read -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::PriceTier;
+
     order_items (order_item_id) {
         order_item_id -> Int8,
         order_id -> Int8,
         variant_id -> Int8,
         quantity -> Nullable<Int4>,
         unit_price -> Nullable<Numeric>,
+        preorder -> Bool,
+        discount_amount -> Numeric,
+        price_tier -> PriceTier,
+    }
+}
+
+diesel::table! {
+    order_item_components (order_item_id, variant_id) {
+        order_item_id -> Int8,
+        variant_id -> Int8,
+        quantity -> Int4,
+    }
+}
+
+diesel::table! {
+    order_promotions (order_id, promotion_id) {
+        order_id -> Int8,
+        promotion_id -> Int8,
+        #[max_length = 40]
+        code -> Varchar,
+        discount_amount -> Numeric,
+        free_shipping -> Bool,
     }
 }
 
@@ -72,6 +236,22 @@ diesel::table! {
         #[max_length = 100]
         tracking_number -> Nullable<Varchar>,
         created_at -> Timestamp,
+        payment_deadline -> Nullable<Timestamp>,
+        cancelled_at -> Nullable<Timestamp>,
+        deposit_amount -> Nullable<Numeric>,
+        discount_amount -> Numeric,
+        shipping_fee -> Numeric,
+    }
+}
+
+diesel::table! {
+    payment_events (provider, event_id) {
+        #[max_length = 50]
+        provider -> Varchar,
+        #[max_length = 100]
+        event_id -> Varchar,
+        payment_id -> Int8,
+        received_at -> Timestamp,
     }
 }
 
@@ -84,15 +264,107 @@ diesel::table! {
         order_id -> Int8,
         amount_paid -> Nullable<Numeric>,
         #[max_length = 255]
-        slip_url -> Nullable<Varchar>,
+        slip_object -> Nullable<Varchar>,
         payment_status -> PaymentStatus,
         created_at -> Timestamp,
+        transferred_at -> Nullable<Timestamp>,
+        submitted_by -> Nullable<Int8>,
+        rejection_reason -> Nullable<Text>,
+        #[max_length = 100]
+        reviewed_by -> Nullable<Varchar>,
+        reviewed_at -> Nullable<Timestamp>,
+        #[max_length = 50]
+        provider -> Nullable<Varchar>,
+        #[max_length = 100]
+        provider_reference -> Nullable<Varchar>,
+        needs_refund -> Bool,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::PreorderPaymentMode;
+
+    preorder_campaigns (product_id) {
+        product_id -> Int8,
+        opens_at -> Timestamp,
+        closes_at -> Timestamp,
+        quota -> Nullable<Int4>,
+        estimated_delivery -> Nullable<Date>,
+        payment_mode -> PreorderPaymentMode,
+        deposit_percent -> Nullable<Int4>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    product_price_history (history_id) {
+        history_id -> Int8,
+        product_id -> Int8,
+        price -> Numeric,
+        compare_at_price -> Nullable<Numeric>,
+        sale_price -> Nullable<Numeric>,
+        sale_starts_at -> Nullable<Timestamp>,
+        sale_ends_at -> Nullable<Timestamp>,
+        changed_at -> Timestamp,
+        member_price -> Nullable<Numeric>,
+        variant_id -> Nullable<Int8>,
+        price_override -> Nullable<Numeric>,
+        member_price_override -> Nullable<Numeric>,
+    }
+}
+
+diesel::table! {
+    product_questions (question_id) {
+        question_id -> Int8,
+        product_id -> Int8,
+        user_id -> Int8,
+        body -> Text,
+        answer -> Nullable<Text>,
+        #[max_length = 100]
+        answered_by -> Nullable<Varchar>,
+        answered_at -> Nullable<Timestamp>,
+        #[max_length = 100]
+        hidden_by -> Nullable<Varchar>,
+        hidden_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ReviewStatus;
+
+    product_reviews (review_id) {
+        review_id -> Int8,
+        product_id -> Int8,
+        user_id -> Int8,
+        rating -> Int2,
+        body -> Nullable<Text>,
+        photo_urls -> Array<Text>,
+        status -> ReviewStatus,
+        #[max_length = 100]
+        moderated_by -> Nullable<Varchar>,
+        moderated_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    product_slug_redirects (slug) {
+        #[max_length = 160]
+        slug -> Varchar,
+        product_id -> Int8,
+        created_at -> Timestamp,
     }
 }
 
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::ProductStatus;
+    use super::sql_types::ProductVisibility;
+    use super::sql_types::ProductKind;
 
     products (id) {
         id -> Int8,
@@ -103,12 +375,83 @@ diesel::table! {
         status -> ProductStatus,
         #[max_length = 100]
         category -> Nullable<Varchar>,
-        stock_quantity -> Nullable<Int4>,
         created_at -> Nullable<Timestamp>,
         updated_at -> Nullable<Timestamp>,
         preview_image -> Nullable<Array<Nullable<Text>>>,
         preview_video -> Nullable<Array<Nullable<Text>>>,
         shipping -> Nullable<Array<Nullable<Text>>>,
+        category_id -> Nullable<Int8>,
+        #[max_length = 160]
+        slug -> Nullable<Varchar>,
+        archived_at -> Nullable<Timestamp>,
+        visibility -> ProductVisibility,
+        publish_at -> Nullable<Timestamp>,
+        unpublish_at -> Nullable<Timestamp>,
+        compare_at_price -> Nullable<Numeric>,
+        sale_price -> Nullable<Numeric>,
+        sale_starts_at -> Nullable<Timestamp>,
+        sale_ends_at -> Nullable<Timestamp>,
+        member_price -> Nullable<Numeric>,
+        kind -> ProductKind,
+        rating_average -> Nullable<Numeric>,
+        rating_count -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::PromotionKind;
+
+    promotions (promotion_id) {
+        promotion_id -> Int8,
+        #[max_length = 40]
+        code -> Varchar,
+        description -> Nullable<Text>,
+        kind -> PromotionKind,
+        value -> Nullable<Numeric>,
+        max_discount -> Nullable<Numeric>,
+        min_spend -> Nullable<Numeric>,
+        product_ids -> Array<Int8>,
+        category_ids -> Array<Int8>,
+        usage_limit -> Nullable<Int4>,
+        per_user_limit -> Nullable<Int4>,
+        starts_at -> Nullable<Timestamp>,
+        ends_at -> Nullable<Timestamp>,
+        stackable -> Bool,
+        active -> Bool,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    statement_entries (entry_id) {
+        entry_id -> Int8,
+        #[max_length = 20]
+        bank -> Varchar,
+        transferred_at -> Timestamp,
+        amount -> Numeric,
+        description -> Text,
+        occurrence -> Int2,
+        payment_id -> Nullable<Int8>,
+        imported_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::AffiliationStatus;
+
+    student_affiliations (user_id) {
+        user_id -> Int8,
+        #[max_length = 20]
+        student_id -> Varchar,
+        status -> AffiliationStatus,
+        note -> Nullable<Text>,
+        submitted_at -> Timestamp,
+        #[max_length = 100]
+        reviewed_by -> Nullable<Varchar>,
+        reviewed_at -> Nullable<Timestamp>,
     }
 }
 
@@ -121,6 +464,67 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DocumentType;
+
+    receipts (receipt_id) {
+        receipt_id -> Int8,
+        #[max_length = 30]
+        receipt_number -> Varchar,
+        document_type -> DocumentType,
+        order_id -> Int8,
+        #[max_length = 200]
+        buyer_name -> Varchar,
+        #[max_length = 13]
+        buyer_tax_id -> Nullable<Varchar>,
+        buyer_address -> Nullable<Text>,
+        issued_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    refund_items (refund_id, order_item_id) {
+        refund_id -> Int8,
+        order_item_id -> Int8,
+        quantity -> Int4,
+        amount -> Numeric,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::RefundStatus;
+
+    refunds (refund_id) {
+        refund_id -> Int8,
+        payment_id -> Int8,
+        order_id -> Int8,
+        amount -> Numeric,
+        reason -> Text,
+        restocked -> Bool,
+        #[max_length = 255]
+        evidence_object -> Nullable<Varchar>,
+        #[max_length = 100]
+        provider_reference -> Nullable<Varchar>,
+        #[max_length = 100]
+        created_by -> Varchar,
+        created_at -> Timestamp,
+        refund_status -> RefundStatus,
+    }
+}
+
+diesel::table! {
+    search_queries (query) {
+        #[max_length = 100]
+        query -> Varchar,
+        search_count -> Int4,
+        zero_result_count -> Int4,
+        last_result_count -> Int8,
+        last_searched_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::UserRole;
@@ -148,30 +552,90 @@ diesel::table! {
         size -> Nullable<Varchar>,
         #[max_length = 50]
         color -> Nullable<Varchar>,
-        stock_quantity -> Nullable<Int4>,
+        stock_quantity -> Int4,
+        #[max_length = 64]
+        sku -> Nullable<Varchar>,
+        price_override -> Nullable<Numeric>,
+        member_price_override -> Nullable<Numeric>,
     }
 }
 
+diesel::joinable!(bundle_components -> variants (variant_id));
 diesel::joinable!(cart -> users (user_id));
 diesel::joinable!(cart_items -> cart (cart_id));
+diesel::joinable!(cart_item_choices -> bundle_components (component_id));
+diesel::joinable!(cart_item_choices -> cart_items (item_id));
+diesel::joinable!(cart_item_choices -> variants (variant_id));
 diesel::joinable!(cart_items -> variants (variant_id));
+diesel::joinable!(cart_promotions -> cart (cart_id));
+diesel::joinable!(cart_promotions -> promotions (promotion_id));
+diesel::joinable!(category_slug_redirects -> categories (category_id));
 diesel::joinable!(favorites -> products (product_id));
 diesel::joinable!(favorites -> users (user_id));
+diesel::joinable!(inventory_movements -> orders (order_id));
+diesel::joinable!(inventory_movements -> variants (variant_id));
+diesel::joinable!(notifications -> orders (order_id));
+diesel::joinable!(notifications -> users (user_id));
 diesel::joinable!(order_items -> orders (order_id));
+diesel::joinable!(order_item_components -> order_items (order_item_id));
+diesel::joinable!(order_item_components -> variants (variant_id));
 diesel::joinable!(order_items -> variants (variant_id));
+diesel::joinable!(order_promotions -> orders (order_id));
+diesel::joinable!(order_promotions -> promotions (promotion_id));
 diesel::joinable!(orders -> users (user_id));
+diesel::joinable!(payment_events -> payments (payment_id));
 diesel::joinable!(payments -> orders (order_id));
+diesel::joinable!(payments -> users (submitted_by));
+diesel::joinable!(preorder_campaigns -> products (product_id));
+diesel::joinable!(product_price_history -> products (product_id));
+diesel::joinable!(product_price_history -> variants (variant_id));
+diesel::joinable!(product_questions -> products (product_id));
+diesel::joinable!(product_questions -> users (user_id));
+diesel::joinable!(product_reviews -> products (product_id));
+diesel::joinable!(product_reviews -> users (user_id));
+diesel::joinable!(product_slug_redirects -> products (product_id));
+diesel::joinable!(products -> categories (category_id));
+diesel::joinable!(receipts -> orders (order_id));
+diesel::joinable!(refund_items -> order_items (order_item_id));
+diesel::joinable!(refund_items -> refunds (refund_id));
+diesel::joinable!(refunds -> orders (order_id));
+diesel::joinable!(refunds -> payments (payment_id));
+diesel::joinable!(statement_entries -> payments (payment_id));
+diesel::joinable!(student_affiliations -> users (user_id));
 diesel::joinable!(user_addresses -> users (user_id));
 diesel::joinable!(variants -> products (product_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    bundle_components,
     cart,
+    cart_item_choices,
     cart_items,
+    cart_promotions,
+    categories,
+    category_slug_redirects,
+    document_counters,
     favorites,
+    inventory_movements,
+    notifications,
+    order_item_components,
     order_items,
+    order_promotions,
     orders,
+    payment_events,
     payments,
+    preorder_campaigns,
+    product_price_history,
+    product_questions,
+    product_reviews,
+    product_slug_redirects,
     products,
+    promotions,
+    receipts,
+    refund_items,
+    refunds,
+    search_queries,
+    statement_entries,
+    student_affiliations,
     user_addresses,
     users,
     variants,
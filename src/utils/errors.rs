@@ -53,3 +53,12 @@ impl From<anyhow::Error> for Error {
         Self::with_message(ErrorCode::InternalError, err.to_string())
     }
 }
+
+impl From<diesel::result::Error> for Error {
+    fn from(err: diesel::result::Error) -> Self {
+        match err {
+            diesel::result::Error::NotFound => Self::new(ErrorCode::ResourceNotFound),
+            _ => Self::with_message(ErrorCode::DatabaseError, format!("Database error: {}", err)),
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod db;
 pub mod errors;
+pub mod promptpay;
+pub mod slug;
 pub mod storage;
@@ -0,0 +1,173 @@
+use std::fmt::Write;
+use std::io::Cursor;
+
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+use image::{DynamicImage, ImageFormat, Luma};
+use qrcode::QrCode;
+use qrcode::render::svg;
+
+use crate::utils::errors::{Error, ErrorCode};
+
+// Application ID assigned to PromptPay credit transfers
+const PROMPTPAY_AID: &str = "A000000677010111";
+const THB_CURRENCY_CODE: &str = "764";
+const COUNTRY_CODE: &str = "TH";
+
+/// Builds an `EMVCo` merchant-presented QR payload for a `PromptPay` transfer.
+///
+/// `promptpay_id` may be a Thai mobile number, a 13-digit national or tax ID, or a 15-digit
+/// e-wallet ID; separators such as dashes and spaces are ignored. With an amount the QR is
+/// dynamic (single use), otherwise the payer types the amount in their banking app.
+pub fn payload(promptpay_id: &str, amount: Option<&BigDecimal>) -> Result<String, Error> {
+    let digits: String = promptpay_id.chars().filter(char::is_ascii_digit).collect();
+
+    let account = match digits.len() {
+        // Mobile numbers are sent as 0066 + the number without its leading zero
+        10 if digits.starts_with('0') => field("01", &format!("0066{}", &digits[1..])),
+        13 => field("02", &digits),
+        15 => field("03", &digits),
+        _ => {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "PromptPay ID must be a mobile number, 13-digit ID or 15-digit e-wallet ID",
+            ));
+        }
+    };
+
+    let mut payload = String::new();
+    payload.push_str(&field("00", "01"));
+    payload.push_str(&field("01", if amount.is_some() { "12" } else { "11" }));
+    payload.push_str(&field(
+        "29",
+        &format!("{}{}", field("00", PROMPTPAY_AID), account),
+    ));
+    payload.push_str(&field("58", COUNTRY_CODE));
+    payload.push_str(&field("53", THB_CURRENCY_CODE));
+    if let Some(amount) = amount {
+        if *amount <= BigDecimal::zero() {
+            return Err(Error::with_message(
+                ErrorCode::ValidationError,
+                "PromptPay amount must be greater than 0",
+            ));
+        }
+        let amount = amount.with_scale_round(2, RoundingMode::HalfUp).to_string();
+        payload.push_str(&field("54", &amount));
+    }
+
+    // The checksum covers everything up to and including its own tag and length
+    payload.push_str("6304");
+    let checksum = crc16(payload.as_bytes());
+    let _ = write!(payload, "{:04X}", checksum);
+
+    Ok(payload)
+}
+
+pub fn render_png(payload: &str) -> Result<Vec<u8>, Error> {
+    let code = QrCode::new(payload.as_bytes()).map_err(|e| {
+        Error::with_message(
+            ErrorCode::InternalError,
+            format!("Failed to encode QR: {}", e),
+        )
+    })?;
+    let image = code.render::<Luma<u8>>().min_dimensions(300, 300).build();
+
+    let mut png = Vec::new();
+    DynamicImage::ImageLuma8(image)
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| {
+            Error::with_message(
+                ErrorCode::InternalError,
+                format!("Failed to render QR: {}", e),
+            )
+        })?;
+
+    Ok(png)
+}
+
+pub fn render_svg(payload: &str) -> Result<String, Error> {
+    let code = QrCode::new(payload.as_bytes()).map_err(|e| {
+        Error::with_message(
+            ErrorCode::InternalError,
+            format!("Failed to encode QR: {}", e),
+        )
+    })?;
+
+    Ok(code
+        .render::<svg::Color<'_>>()
+        .min_dimensions(300, 300)
+        .build())
+}
+
+fn field(tag: &str, value: &str) -> String {
+    format!("{}{:02}{}", tag, value.len(), value)
+}
+
+// CRC-16/CCITT-FALSE, as required by the EMVCo QR specification
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for byte in data {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x1021
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn crc16_matches_the_ccitt_false_check_value() {
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn mobile_number_without_amount() {
+        assert_eq!(
+            payload("000-000-0000", None).unwrap(),
+            "00020101021129370016A000000677010111011300660000000005802TH530376463048956"
+        );
+        assert_eq!(
+            payload("081 234 5678", None).unwrap(),
+            "00020101021129370016A000000677010111011300668123456785802TH530376463045D82"
+        );
+    }
+
+    #[test]
+    fn mobile_number_with_amount() {
+        let amount = BigDecimal::from_str("4.22").unwrap();
+        assert_eq!(
+            payload("000-000-0000", Some(&amount)).unwrap(),
+            "00020101021229370016A000000677010111011300660000000005802TH530376454044.226304E469"
+        );
+    }
+
+    #[test]
+    fn national_id_with_and_without_amount() {
+        assert_eq!(
+            payload("1-1111-11111-11-1", None).unwrap(),
+            "00020101021129370016A000000677010111021311111111111115802TH530376463047B5A"
+        );
+
+        // Amounts are always written with two decimals
+        let amount = BigDecimal::from_str("1500").unwrap();
+        assert_eq!(
+            payload("1111111111111", Some(&amount)).unwrap(),
+            "00020101021229370016A000000677010111021311111111111115802TH530376454071500.0063047A1D"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_ids_and_non_positive_amounts() {
+        assert!(payload("12345", None).is_err());
+        assert!(payload("0812345678", Some(&BigDecimal::zero())).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+pub const MAX_SLUG_LEN: usize = 150;
+
+/// Turns text into a URL slug: lowercase ASCII letters and digits joined by hyphens.
+///
+/// Thai is romanised first so Thai names still give readable slugs. The romanisation is a
+/// simplified RTGS that works letter by letter, so it is close but not always exact. The
+/// result may be empty when the text has nothing usable in it.
+pub fn slugify(text: &str) -> String {
+    let latin = transliterate(&text.to_lowercase());
+
+    let mut slug = String::new();
+    for c in latin.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    // Only ASCII is left, so byte truncation can't split a character
+    slug.truncate(MAX_SLUG_LEN);
+    slug.trim_matches('-').to_string()
+}
+
+fn transliterate(text: &str) -> String {
+    // Tone marks don't change the romanisation, and dropping them makes lookahead simpler
+    let chars: Vec<char> = text.chars().filter(|c| !is_tone_mark(*c)).collect();
+    let mut parts: Vec<String> = Vec::new();
+    let mut after_consonant = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        // Vowels written before their consonant are read after it
+        if is_leading_vowel(c)
+            && let Some(consonant) = next.filter(|n| is_consonant(*n))
+        {
+            parts.push(if consonant == 'อ' {
+                String::new()
+            } else {
+                initial(consonant).to_string()
+            });
+            let (vowel, used) = leading_vowel(c, &chars[i + 2..]);
+            parts.push(vowel.to_string());
+            after_consonant = false;
+            i += 2 + used;
+            continue;
+        }
+
+        if c == '\u{0E4C}' {
+            // Thanthakhat silences the letter before it
+            parts.pop();
+            i += 1;
+            continue;
+        }
+
+        if is_consonant(c) {
+            let sound = match c {
+                // อ carries a vowel silently, otherwise it is the vowel "o"
+                'อ' if !after_consonant && next.is_some_and(is_following_vowel) => "",
+                'อ' => "o",
+                // A leading ห only marks the tone of a low sonorant after it
+                'ห' if next.is_some_and(|n| "งญนมยรลว".contains(n)) => "",
+                'ว' if after_consonant && next.is_some_and(is_consonant) => "ua",
+                _ => initial(c),
+            };
+            parts.push(sound.to_string());
+            after_consonant = !matches!(c, 'อ');
+            i += 1;
+            continue;
+        }
+
+        if is_following_vowel(c) {
+            let (vowel, used) = match (c, next) {
+                ('ั', Some('ว')) => ("ua", 2),
+                ('ื', Some('อ')) => ("ue", 2),
+                _ => (following_vowel(c), 1),
+            };
+            parts.push(vowel.to_string());
+            after_consonant = false;
+            i += used;
+            continue;
+        }
+
+        parts.push(if ('๐'..='๙').contains(&c) {
+            (u32::from(c) - u32::from('๐')).to_string()
+        } else if is_thai(c) {
+            String::new()
+        } else {
+            c.to_string()
+        });
+        after_consonant = false;
+        i += 1;
+    }
+
+    parts.concat()
+}
+
+fn is_thai(c: char) -> bool {
+    ('\u{0E00}'..='\u{0E7F}').contains(&c)
+}
+
+fn is_consonant(c: char) -> bool {
+    ('ก'..='ฮ').contains(&c)
+}
+
+fn is_tone_mark(c: char) -> bool {
+    ('\u{0E48}'..='\u{0E4B}').contains(&c)
+}
+
+fn is_leading_vowel(c: char) -> bool {
+    ('เ'..='ไ').contains(&c)
+}
+
+fn is_following_vowel(c: char) -> bool {
+    matches!(c, 'ะ' | 'ั' | 'า' | 'ำ' | 'ิ' | 'ี' | 'ึ' | 'ื' | 'ุ' | 'ู' | '็')
+}
+
+fn initial(c: char) -> &'static str {
+    match c {
+        'ก' => "k",
+        'ข' | 'ฃ' | 'ค' | 'ฅ' | 'ฆ' => "kh",
+        'ง' => "ng",
+        'จ' | 'ฉ' | 'ช' | 'ฌ' => "ch",
+        'ซ' | 'ศ' | 'ษ' | 'ส' => "s",
+        'ญ' | 'ย' => "y",
+        'ฎ' | 'ด' => "d",
+        'ฏ' | 'ต' => "t",
+        'ฐ' | 'ฑ' | 'ฒ' | 'ถ' | 'ท' | 'ธ' => "th",
+        'ณ' | 'น' => "n",
+        'บ' => "b",
+        'ป' => "p",
+        'ผ' | 'พ' | 'ภ' => "ph",
+        'ฝ' | 'ฟ' => "f",
+        'ม' => "m",
+        'ร' => "r",
+        'ฤ' => "rue",
+        'ล' | 'ฬ' => "l",
+        'ฦ' => "lue",
+        'ว' => "w",
+        'ห' | 'ฮ' => "h",
+        _ => "",
+    }
+}
+
+fn following_vowel(c: char) -> &'static str {
+    match c {
+        'ะ' | 'ั' | 'า' => "a",
+        'ำ' => "am",
+        'ิ' | 'ี' => "i",
+        'ึ' | 'ื' => "ue",
+        'ุ' | 'ู' => "u",
+        _ => "",
+    }
+}
+
+// Romanises a leading vowel together with the marks after its consonant that complete it.
+// Returns how many of those marks were used.
+fn leading_vowel(c: char, rest: &[char]) -> (&'static str, usize) {
+    match (c, rest) {
+        ('เ', ['ื', 'อ', ..]) => ("uea", 2),
+        ('เ', ['ี', 'ย', ..]) => ("ia", 2),
+        ('เ', ['า', ..]) => ("ao", 1),
+        ('เ', ['อ', ..]) => ("oe", 1),
+        ('เ', ['ะ' | '็', ..]) => ("e", 1),
+        ('เ', _) => ("e", 0),
+        ('แ', ['ะ', ..]) => ("ae", 1),
+        ('แ', _) => ("ae", 0),
+        ('โ', ['ะ', ..]) => ("o", 1),
+        ('โ', _) => ("o", 0),
+        _ => ("ai", 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latin_text_becomes_lowercase_words() {
+        assert_eq!(slugify("Summer Tee (2025)!"), "summer-tee-2025");
+        assert_eq!(slugify("  --Intania__Shop--  "), "intania-shop");
+    }
+
+    #[test]
+    fn thai_is_romanised() {
+        assert_eq!(slugify("เสื้อ"), "suea");
+        assert_eq!(slugify("กางเกง"), "kangkeng");
+        assert_eq!(slugify("เบอร์"), "boe");
+        assert_eq!(slugify("รุ่น ๖๘"), "run-68");
+    }
+
+    #[test]
+    fn mixed_thai_and_latin_keep_their_words() {
+        assert_eq!(
+            slugify("เสื้อ Engineering รุ่น 2568"),
+            "suea-engineering-run-2568"
+        );
+        assert_eq!(slugify("Hoodie-กางเกง"), "hoodie-kangkeng");
+    }
+
+    #[test]
+    fn long_text_is_cut_without_a_trailing_hyphen() {
+        assert_eq!(slugify(&"a".repeat(200)), "a".repeat(MAX_SLUG_LEN));
+
+        // The cut lands right after a word, on the hyphen that follows it
+        let slug = slugify(&"abcde ".repeat(40));
+        assert_eq!(slug.len(), MAX_SLUG_LEN - 1);
+        assert!(!slug.ends_with('-'));
+    }
+
+    #[test]
+    fn text_without_letters_or_digits_gives_an_empty_slug() {
+        assert_eq!(slugify(""), "");
+        assert_eq!(slugify("!!! --- ???"), "");
+        assert_eq!(slugify("\u{0E4C}\u{0E48}"), "");
+    }
+}
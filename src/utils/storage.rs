@@ -8,20 +8,24 @@ use uuid::Uuid;
 pub struct StorageService {
     client: Storage,
     bucket_name: String,
+    /// Grants no public access; its objects are only handed out by the API
+    private_bucket_name: String,
 }
 
 impl StorageService {
-    pub async fn new(bucket_name: String) -> Result<Self> {
+    pub async fn new(bucket_name: String, private_bucket_name: String) -> Result<Self> {
         let client = Storage::builder()
             .build()
             .await
             .context("Failed to create Google Cloud Storage client")?;
 
         let full_bucket_name = format!("projects/_/buckets/{}", bucket_name);
+        let full_private_bucket_name = format!("projects/_/buckets/{}", private_bucket_name);
 
         Ok(Self {
             client,
             bucket_name: full_bucket_name,
+            private_bucket_name: full_private_bucket_name,
         })
     }
 
@@ -31,6 +35,60 @@ impl StorageService {
         original_filename: &str,
         folder: &str,
     ) -> Result<(String, String)> {
+        let object_name =
+            Box::pin(self.write(&self.bucket_name, file_data, original_filename, folder)).await?;
+
+        let url = format!(
+            "https://storage.googleapis.com/{}/{}",
+            self.bucket_name, object_name
+        );
+
+        Ok((url, object_name))
+    }
+
+    /// Uploads to the private bucket and returns the object name; there is no URL to share.
+    /// Read it back with `read_private_file`.
+    pub async fn upload_private_file(
+        &self,
+        file_data: Vec<u8>,
+        original_filename: &str,
+        folder: &str,
+    ) -> Result<String> {
+        Box::pin(self.write(
+            &self.private_bucket_name,
+            file_data,
+            original_filename,
+            folder,
+        ))
+        .await
+    }
+
+    /// Contents and content type of an object in the private bucket
+    pub async fn read_private_file(&self, object_name: &str) -> Result<(Vec<u8>, String)> {
+        let mut response = self
+            .client
+            .read_object(&self.private_bucket_name, object_name)
+            .send()
+            .await
+            .context("Failed to read file from Google Cloud Storage")?;
+
+        let content_type = response.object().content_type;
+        let mut contents = Vec::new();
+        while let Some(chunk) = response.next().await {
+            let chunk = chunk.context("Failed to read file from Google Cloud Storage")?;
+            contents.extend_from_slice(&chunk);
+        }
+
+        Ok((contents, content_type))
+    }
+
+    async fn write(
+        &self,
+        bucket_name: &str,
+        file_data: Vec<u8>,
+        original_filename: &str,
+        folder: &str,
+    ) -> Result<String> {
         let extension = Path::new(original_filename)
             .extension()
             .and_then(|s| s.to_str())
@@ -53,19 +111,14 @@ impl StorageService {
 
         let object = Box::pin(
             self.client
-                .write_object(&self.bucket_name, &object_name, bytes_data)
+                .write_object(bucket_name, &object_name, bytes_data)
                 .set_content_type(&mime_type)
                 .send_buffered(),
         )
         .await
         .context("Failed to upload file to Google Cloud Storage")?;
 
-        let url = format!(
-            "https://storage.googleapis.com/{}/{}",
-            self.bucket_name, object.name
-        );
-
-        Ok((url, object.name))
+        Ok(object.name)
     }
 
     pub async fn upload_files(